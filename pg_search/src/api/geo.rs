@@ -0,0 +1,47 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::api::index::geo_point;
+use crate::schema::geo::GeoPoint;
+use pgrx::{direct_function_call, pg_extern, pg_sys, IntoDatum, JsonB};
+
+/// The distance in meters between a `point` location, whose x is its longitude and y its
+/// latitude, and `lat` and `lon`.  Ordering a "top N" query by it sorts inside the scan if the
+/// location is indexed as a geo field.
+#[pg_extern(name = "distance", immutable, parallel_safe)]
+pub fn distance_point(location: pg_sys::Point, lat: f64, lon: f64) -> Option<f64> {
+    let location = GeoPoint::new(location.y, location.x)?;
+    Some(location.distance(&geo_point(lat, lon)))
+}
+
+/// The distance in meters between a `{"lat": ..., "lon": ...}` location and `lat` and `lon`
+#[pg_extern(name = "distance", immutable, parallel_safe)]
+pub fn distance_jsonb(location: JsonB, lat: f64, lon: f64) -> Option<f64> {
+    let location = GeoPoint::from_json(&location.0)?;
+    Some(location.distance(&geo_point(lat, lon)))
+}
+
+pub fn distance_funcoids() -> [pg_sys::Oid; 2] {
+    [
+        c"paradedb.distance(point, double precision, double precision)",
+        c"paradedb.distance(jsonb, double precision, double precision)",
+    ]
+    .map(|signature| unsafe {
+        direct_function_call::<pg_sys::Oid>(pg_sys::regprocedurein, &[signature.into_datum()])
+            .expect("the `paradedb.distance` functions should exist")
+    })
+}
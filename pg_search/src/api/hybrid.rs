@@ -0,0 +1,250 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::index::reader::index::SearchIndexReader;
+use crate::index::BlockDirectoryType;
+use crate::query::SearchQueryInput;
+use pgrx::spi::{quote_identifier, quote_qualified_identifier};
+use pgrx::{iter::TableIterator, *};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+
+/// How the bm25 ranking and the other ranking of a hybrid search are fused into one
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize, PostgresEnum)]
+pub enum FusionMethod {
+    /// Reciprocal rank fusion, which only looks at the rank of each key in each ranking
+    #[default]
+    Rrf,
+    /// Rescale each ranking's scores to between 0 and 1 before adding them up
+    MinMax,
+    /// Rescale each ranking's scores to their number of standard deviations from the mean before
+    /// adding them up
+    ZScore,
+}
+
+/// Fuse the `limit` best matches of `query` in `index` with another ranking of the same keys, ie
+/// the results of a vector search, into a single ranking for hybrid search.
+///
+/// `ranked_keys` are the keys of the other ranking, best first.  They're compared with the index's
+/// key field as values of the key field's type, and the fused keys are returned as the type of
+/// `ranked_keys`' elements.  The `MinMax` and `ZScore`
+/// methods also need their `ranked_scores`, where higher is better, so pass a similarity rather
+/// than a distance.
+///
+/// With `Rrf`, each ranking contributes `weight / (k + rank)`.  Otherwise, each ranking
+/// contributes `weight` times its normalized score, and a key that's missing from a ranking
+/// gets the lowest normalized score of that ranking.
+#[allow(clippy::too_many_arguments)]
+#[pg_extern]
+pub fn hybrid(
+    index: PgRelation,
+    query: SearchQueryInput,
+    ranked_keys: AnyArray,
+    ranked_scores: default!(Option<Vec<f32>>, "NULL"),
+    method: default!(FusionMethod, "'Rrf'"),
+    k: default!(i32, "60"),
+    bm25_weight: default!(f32, "1.0"),
+    ranked_weight: default!(f32, "1.0"),
+    bm25_limit: default!(i32, "100"),
+    limit: default!(i32, "10"),
+) -> TableIterator<'static, (name!(key, AnyElement), name!(score, f32))> {
+    if k < 0 {
+        panic!("k must not be negative");
+    }
+
+    // # Safety
+    //
+    // Lock the index relation until the end of this function so it is not dropped or
+    // altered while we are reading it.
+    //
+    // Because we accept a PgRelation above, we have confidence that Postgres has already
+    // validated the existence of the relation. We are safe calling the function below as
+    // long we do not pass pg_sys::NoLock without any other locking mechanism of our own.
+    let index = unsafe { PgRelation::with_lock(index.oid(), pg_sys::AccessShareLock as _) };
+    let heap_relation = index
+        .heap_relation()
+        .expect("index should belong to a heap");
+    let key_field = SearchIndexReader::open(&index, BlockDirectoryType::Mvcc, false)
+        .expect("could not open search index reader")
+        .key_field()
+        .name
+        .0;
+
+    let key_type = heap_relation
+        .tuple_desc()
+        .iter()
+        .find(|attribute| attribute.name() == key_field)
+        .map(|attribute| attribute.type_oid().value())
+        .expect("key field should be a column of the table");
+    let element_type = unsafe { pg_sys::get_element_type(ranked_keys.oid()) };
+
+    // keys are compared by their text in the key field's type, so that ie `1::int4` and
+    // `1::bigint` are the same key, and so are differently cased uuids
+    let key_type_name = type_name(key_type);
+    let key_field = quote_identifier(key_field);
+    let (bm25_ranking, ranked_keys) = Spi::connect(|client| {
+        // going through SQL, rather than the index directly, means that we only see visible rows
+        let bm25_ranking = client
+            .select(
+                &format!(
+                    "SELECT {key_field}::text, paradedb.score({key_field}) FROM {} \
+                     WHERE {key_field} @@@ $1 ORDER BY 2 DESC LIMIT {}",
+                    quote_qualified_identifier(heap_relation.namespace(), heap_relation.name()),
+                    bm25_limit.max(0)
+                ),
+                None,
+                Some(vec![(
+                    PgOid::from(SearchQueryInput::type_oid()),
+                    query.into_datum(),
+                )]),
+            )?
+            .map(|row| Ok((row.get::<String>(1)?, row.get::<f32>(2)?)))
+            .collect::<Result<Vec<_>, pgrx::spi::Error>>()?;
+        let ranked_keys = client
+            .select(
+                &format!(
+                    "SELECT key::{key_type_name}::text FROM unnest($1) WITH ORDINALITY AS t(key, n) \
+                     ORDER BY n"
+                ),
+                None,
+                Some(vec![(PgOid::from(ranked_keys.oid()), ranked_keys.into_datum())]),
+            )?
+            .map(|row| row.get::<String>(1))
+            .collect::<Result<Vec<_>, pgrx::spi::Error>>()?;
+        Ok::<_, pgrx::spi::Error>((bm25_ranking, ranked_keys))
+    })
+    .unwrap_or_else(|err| panic!("could not run the bm25 query: {err}"));
+    let bm25_ranking = bm25_ranking
+        .into_iter()
+        .filter_map(|(key, score)| Some((key?, score.unwrap_or_default())))
+        .collect::<Vec<_>>();
+    let ranked_keys = ranked_keys
+        .into_iter()
+        .map(|key| key.unwrap_or_else(|| panic!("ranked_keys must not contain NULL")))
+        .collect::<Vec<_>>();
+    if let Some(ranked_scores) = &ranked_scores {
+        if ranked_scores.len() != ranked_keys.len() {
+            panic!("ranked_scores must have one score for every key of ranked_keys");
+        }
+    }
+
+    let ranked_ranking: Vec<(String, f32)> = match ranked_scores {
+        Some(ranked_scores) => ranked_keys.into_iter().zip(ranked_scores).collect(),
+        None if method == FusionMethod::Rrf => {
+            ranked_keys.into_iter().map(|key| (key, 0.0)).collect()
+        }
+        None => panic!("{method:?} fusion needs ranked_scores"),
+    };
+
+    let rankings = [(bm25_ranking, bm25_weight), (ranked_ranking, ranked_weight)];
+    let mut fused = rankings
+        .iter()
+        .flat_map(|(ranking, _)| ranking.iter().map(|(key, _)| (key.clone(), 0.0)))
+        .collect::<HashMap<String, f32>>();
+    for (ranking, weight) in rankings {
+        let contributions = contributions(&ranking, method, k);
+        // with rrf, a ranking adds nothing for the keys it's missing
+        let missing = match method {
+            FusionMethod::Rrf => 0.0,
+            _ => contributions.iter().copied().fold(0.0, f32::min),
+        };
+        let contributions = ranking
+            .into_iter()
+            .map(|(key, _)| key)
+            .zip(contributions)
+            .collect::<HashMap<_, _>>();
+        for (key, score) in fused.iter_mut() {
+            *score += weight * contributions.get(key).copied().unwrap_or(missing);
+        }
+    }
+
+    let mut fused = fused.into_iter().collect::<Vec<_>>();
+    fused.sort_by(|(a_key, a_score), (b_key, b_score)| {
+        b_score.total_cmp(a_score).then_with(|| a_key.cmp(b_key))
+    });
+    fused.truncate(limit.max(0) as usize);
+
+    let (mut input_function, mut typioparam) = (pg_sys::InvalidOid, pg_sys::InvalidOid);
+    unsafe { pg_sys::getTypeInputInfo(element_type, &mut input_function, &mut typioparam) };
+    let fused = fused
+        .into_iter()
+        .map(|(key, score)| {
+            let key = CString::new(key).expect("key should not contain a nul byte");
+            let key = unsafe {
+                let datum =
+                    pg_sys::OidInputFunctionCall(input_function, key.as_ptr() as _, typioparam, -1);
+                AnyElement::from_polymorphic_datum(datum, false, element_type)
+                    .expect("key should not be NULL")
+            };
+            (key, score)
+        })
+        .collect::<Vec<_>>();
+
+    TableIterator::new(fused)
+}
+
+/// The SQL name of the type `oid`, ie for a cast
+fn type_name(oid: pg_sys::Oid) -> String {
+    unsafe {
+        CStr::from_ptr(pg_sys::format_type_be(oid))
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
+/// What each entry of a `ranking`, best first, contributes to the fused score
+fn contributions(ranking: &[(String, f32)], method: FusionMethod, k: i32) -> Vec<f32> {
+    let scores = ranking.iter().map(|(_, score)| *score);
+    match method {
+        FusionMethod::Rrf => (1..=ranking.len())
+            .map(|rank| 1.0 / (k as f32 + rank as f32))
+            .collect(),
+        FusionMethod::MinMax => {
+            let min = scores.clone().fold(f32::INFINITY, f32::min);
+            let max = scores.clone().fold(f32::NEG_INFINITY, f32::max);
+            scores
+                .map(|score| {
+                    if max > min {
+                        (score - min) / (max - min)
+                    } else {
+                        1.0
+                    }
+                })
+                .collect()
+        }
+        FusionMethod::ZScore => {
+            let count = ranking.len().max(1) as f32;
+            let mean = scores.clone().sum::<f32>() / count;
+            let std_dev = (scores
+                .clone()
+                .map(|score| (score - mean).powi(2))
+                .sum::<f32>()
+                / count)
+                .sqrt();
+            scores
+                .map(|score| {
+                    if std_dev > 0.0 {
+                        (score - mean) / std_dev
+                    } else {
+                        0.0
+                    }
+                })
+                .collect()
+        }
+    }
+}
@@ -20,8 +20,13 @@ use pgrx::{iter::TableIterator, *};
 
 use crate::index::reader::index::SearchIndexReader;
 use crate::index::BlockDirectoryType;
+use crate::postgres::inet::InetValue;
 use crate::postgres::types::TantivyValue;
-use crate::query::{SearchQueryInput, TermInput};
+use crate::query::{
+    DecayFunction, Fuzziness, MinimumShouldMatch, MultiMatchType, SearchQueryInput, TermInput,
+    VectorMetric,
+};
+use crate::schema::geo::GeoPoint;
 use crate::schema::AnyEnum;
 use crate::schema::IndexRecordOption;
 use serde::{Deserialize, Serialize};
@@ -29,6 +34,7 @@ use std::collections::HashMap;
 use std::ffi::CStr;
 use std::fmt::{Display, Formatter};
 use std::ops::Bound;
+use std::str::FromStr;
 use tantivy::schema::{FieldType, OwnedValue, Value};
 
 #[allow(clippy::type_complexity)]
@@ -108,6 +114,8 @@ pub fn schema(
                     )
                 }
                 FieldType::Date(_) => ("Date".to_string(), None, None, None, None),
+                FieldType::IpAddr(_) => ("IpAddr".to_string(), None, None, None, None),
+                FieldType::Bytes(_) => ("Bytes".to_string(), None, None, None, None),
                 _ => ("Other".to_string(), None, None, None, None),
             };
 
@@ -140,11 +148,13 @@ pub fn boolean_arrays(
     must: default!(Vec<SearchQueryInput>, "ARRAY[]::searchqueryinput[]"),
     should: default!(Vec<SearchQueryInput>, "ARRAY[]::searchqueryinput[]"),
     must_not: default!(Vec<SearchQueryInput>, "ARRAY[]::searchqueryinput[]"),
+    minimum_should_match: default!(Option<String>, "NULL"),
 ) -> SearchQueryInput {
     SearchQueryInput::Boolean {
         must,
         should,
         must_not,
+        minimum_should_match: minimum_should_match.map(parse_minimum_should_match),
     }
 }
 
@@ -153,14 +163,51 @@ pub fn boolean_singles(
     must: default!(Option<SearchQueryInput>, "NULL"),
     should: default!(Option<SearchQueryInput>, "NULL"),
     must_not: default!(Option<SearchQueryInput>, "NULL"),
+    minimum_should_match: default!(Option<String>, "NULL"),
 ) -> SearchQueryInput {
     boolean_arrays(
         must.map_or(vec![], |v| vec![v]),
         should.map_or(vec![], |v| vec![v]),
         must_not.map_or(vec![], |v| vec![v]),
+        minimum_should_match,
     )
 }
 
+#[pg_extern(name = "boolean", immutable, parallel_safe)]
+pub fn boolean_arrays_count(
+    must: default!(Vec<SearchQueryInput>, "ARRAY[]::searchqueryinput[]"),
+    should: default!(Vec<SearchQueryInput>, "ARRAY[]::searchqueryinput[]"),
+    must_not: default!(Vec<SearchQueryInput>, "ARRAY[]::searchqueryinput[]"),
+    minimum_should_match: i64,
+) -> SearchQueryInput {
+    SearchQueryInput::Boolean {
+        must,
+        should,
+        must_not,
+        minimum_should_match: Some(MinimumShouldMatch::Count(minimum_should_match)),
+    }
+}
+
+#[pg_extern(name = "boolean", immutable, parallel_safe)]
+pub fn boolean_singles_count(
+    must: default!(Option<SearchQueryInput>, "NULL"),
+    should: default!(Option<SearchQueryInput>, "NULL"),
+    must_not: default!(Option<SearchQueryInput>, "NULL"),
+    minimum_should_match: i64,
+) -> SearchQueryInput {
+    boolean_arrays_count(
+        must.map_or(vec![], |v| vec![v]),
+        should.map_or(vec![], |v| vec![v]),
+        must_not.map_or(vec![], |v| vec![v]),
+        minimum_should_match,
+    )
+}
+
+fn parse_minimum_should_match(value: String) -> MinimumShouldMatch {
+    MinimumShouldMatch::from_str(&value)
+        .unwrap_or_else(|err| error!("invalid minimum_should_match argument: {err}"))
+}
+
 #[pg_extern(immutable, parallel_safe)]
 pub fn boost(factor: f32, query: SearchQueryInput) -> SearchQueryInput {
     SearchQueryInput::Boost {
@@ -169,6 +216,22 @@ pub fn boost(factor: f32, query: SearchQueryInput) -> SearchQueryInput {
     }
 }
 
+#[pg_extern(immutable, parallel_safe)]
+pub fn collapse(
+    field: FieldName,
+    query: SearchQueryInput,
+    max_per_group: default!(i32, "1"),
+) -> SearchQueryInput {
+    if max_per_group < 1 {
+        panic!("max_per_group must be at least 1");
+    }
+    SearchQueryInput::Collapse {
+        field: field.into_inner(),
+        query: Box::new(query),
+        max_per_group: max_per_group as usize,
+    }
+}
+
 #[pg_extern(immutable, parallel_safe)]
 pub fn const_score(score: f32, query: SearchQueryInput) -> SearchQueryInput {
     SearchQueryInput::ConstScore {
@@ -177,6 +240,59 @@ pub fn const_score(score: f32, query: SearchQueryInput) -> SearchQueryInput {
     }
 }
 
+#[pg_extern(name = "decay", immutable, parallel_safe)]
+pub fn decay_numeric(
+    field: FieldName,
+    query: SearchQueryInput,
+    origin: f64,
+    scale: f64,
+    offset: default!(f64, "0"),
+    decay: default!(f64, "0.5"),
+    function: default!(DecayFunction, "'Gauss'"),
+) -> SearchQueryInput {
+    SearchQueryInput::Decay {
+        field: field.into_inner(),
+        query: Box::new(query),
+        function,
+        origin: OwnedValue::F64(origin),
+        scale,
+        offset: Some(offset),
+        decay: Some(decay),
+    }
+}
+
+#[pg_extern(name = "decay", immutable, parallel_safe)]
+pub fn decay_timestamp_with_time_zone(
+    field: FieldName,
+    query: SearchQueryInput,
+    origin: pgrx::datum::TimestampWithTimeZone,
+    scale: pgrx::datum::Interval,
+    offset: default!(pgrx::datum::Interval, "'0 seconds'"),
+    decay: default!(f64, "0.5"),
+    function: default!(DecayFunction, "'Gauss'"),
+) -> SearchQueryInput {
+    SearchQueryInput::Decay {
+        field: field.into_inner(),
+        query: Box::new(query),
+        function,
+        origin: TantivyValue::try_from(origin)
+            .expect("value should be a valid TantivyValue representation")
+            .tantivy_schema_value(),
+        scale: interval_to_seconds(scale),
+        offset: Some(interval_to_seconds(offset)),
+        decay: Some(decay),
+    }
+}
+
+/// Postgres intervals keep months and days separate from the time component, so we use the same
+/// 30-day month that Postgres itself uses when it justifies an interval.
+fn interval_to_seconds(interval: pgrx::datum::Interval) -> f64 {
+    const SECONDS_PER_DAY: f64 = 86_400.0;
+    interval.months() as f64 * 30.0 * SECONDS_PER_DAY
+        + interval.days() as f64 * SECONDS_PER_DAY
+        + interval.micros() as f64 / 1_000_000.0
+}
+
 #[pg_extern(immutable, parallel_safe)]
 pub fn disjunction_max(
     disjuncts: Array<SearchQueryInput>,
@@ -200,6 +316,13 @@ pub fn exists(field: FieldName) -> SearchQueryInput {
     }
 }
 
+#[pg_extern(immutable, parallel_safe)]
+pub fn is_null(field: FieldName) -> SearchQueryInput {
+    SearchQueryInput::IsNull {
+        field: field.into_inner(),
+    }
+}
+
 // Not clear on whether this query makes sense to support, as only our "key_field" is a fast
 // field... and the user can just use SQL to select a range. We'll keep the implementation here
 // for now, but we should remove when we decide definitively that we don't need this.
@@ -266,6 +389,105 @@ pub fn fuzzy_phrase(
     }
 }
 
+pub fn geo_point(lat: f64, lon: f64) -> GeoPoint {
+    GeoPoint::new(lat, lon)
+        .unwrap_or_else(|| panic!("({lat}, {lon}) is not a valid latitude and longitude"))
+}
+
+#[pg_extern(immutable, parallel_safe)]
+pub fn geo_distance(field: FieldName, lat: f64, lon: f64, distance: f64) -> SearchQueryInput {
+    if distance.is_nan() || distance < 0.0 {
+        panic!("distance must not be negative");
+    }
+    SearchQueryInput::GeoDistance {
+        field: field.into_inner(),
+        origin: geo_point(lat, lon),
+        distance,
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+pub fn geo_bounding_box(
+    field: FieldName,
+    top: f64,
+    left: f64,
+    bottom: f64,
+    right: f64,
+) -> SearchQueryInput {
+    if top < bottom {
+        panic!("top must not be south of bottom");
+    }
+    SearchQueryInput::GeoBoundingBox {
+        field: field.into_inner(),
+        top_left: geo_point(top, left),
+        bottom_right: geo_point(bottom, right),
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+pub fn geo_polygon(field: FieldName, points: JsonB) -> SearchQueryInput {
+    let serde_json::Value::Array(points) = points.0 else {
+        panic!("polygon must be an array of points");
+    };
+    let points = points
+        .iter()
+        .map(|point| {
+            GeoPoint::from_json(point).unwrap_or_else(|| {
+                panic!("polygon point {point} must be an object of a valid 'lat' and 'lon'")
+            })
+        })
+        .collect::<Vec<_>>();
+    if points.len() < 3 {
+        panic!("polygon must have at least 3 points");
+    }
+    SearchQueryInput::GeoPolygon {
+        field: field.into_inner(),
+        points,
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+pub fn knn(
+    field: FieldName,
+    vector: Vec<f32>,
+    k: i32,
+    metric: default!(VectorMetric, "'Cosine'"),
+    filter: default!(Option<SearchQueryInput>, "NULL"),
+) -> SearchQueryInput {
+    if k < 0 {
+        panic!("k must not be negative");
+    }
+    SearchQueryInput::Knn {
+        field: field.into_inner(),
+        vector,
+        metric,
+        k: k as usize,
+        filter: filter.map(Box::new),
+    }
+}
+
+#[pg_extern(name = "match", immutable, parallel_safe)]
+pub fn match_query(
+    field: FieldName,
+    value: String,
+    tokenizer: default!(Option<JsonB>, "NULL"),
+    fuzziness: default!(Option<String>, "NULL"),
+    transposition_cost_one: default!(Option<bool>, "NULL"),
+    prefix: default!(Option<bool>, "NULL"),
+    conjunction_mode: default!(Option<bool>, "NULL"),
+) -> SearchQueryInput {
+    SearchQueryInput::Match {
+        field: field.into_inner(),
+        value,
+        tokenizer: tokenizer.map(|tokenizer| tokenizer.0),
+        fuzziness: fuzziness
+            .map(|fuzziness| Fuzziness::from_str(&fuzziness).unwrap_or_else(|err| panic!("{err}"))),
+        transposition_cost_one,
+        prefix,
+        conjunction_mode,
+    }
+}
+
 #[pg_extern(name = "more_like_this", immutable, parallel_safe)]
 pub fn more_like_this_empty() -> SearchQueryInput {
     panic!("more_like_this must be called with either document_id or document_fields");
@@ -337,16 +559,58 @@ pub fn more_like_this_id(
     }
 }
 
+#[pg_extern(immutable, parallel_safe)]
+pub fn multi_match(
+    fields: Vec<String>,
+    value: String,
+    match_type: default!(MultiMatchType, "'BestFields'"),
+    tie_breaker: default!(Option<f32>, "NULL"),
+    conjunction_mode: default!(Option<bool>, "NULL"),
+) -> SearchQueryInput {
+    SearchQueryInput::MultiMatch {
+        fields,
+        value,
+        match_type,
+        tie_breaker,
+        conjunction_mode,
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+pub fn named(name: String, query: SearchQueryInput) -> SearchQueryInput {
+    SearchQueryInput::Named {
+        name,
+        query: Box::new(query),
+    }
+}
+
 #[pg_extern(immutable, parallel_safe)]
 pub fn parse(
     query_string: String,
     lenient: default!(Option<bool>, "NULL"),
     conjunction_mode: default!(Option<bool>, "NULL"),
+    minimum_should_match: default!(Option<String>, "NULL"),
 ) -> SearchQueryInput {
     SearchQueryInput::Parse {
         query_string,
         lenient,
         conjunction_mode,
+        minimum_should_match: minimum_should_match.map(parse_minimum_should_match),
+    }
+}
+
+#[pg_extern(name = "parse", immutable, parallel_safe)]
+pub fn parse_count(
+    query_string: String,
+    lenient: default!(Option<bool>, "NULL"),
+    conjunction_mode: default!(Option<bool>, "NULL"),
+    minimum_should_match: i64,
+) -> SearchQueryInput {
+    SearchQueryInput::Parse {
+        query_string,
+        lenient,
+        conjunction_mode,
+        minimum_should_match: Some(MinimumShouldMatch::Count(minimum_should_match)),
     }
 }
 
@@ -356,12 +620,31 @@ pub fn parse_with_field(
     query_string: String,
     lenient: default!(Option<bool>, "NULL"),
     conjunction_mode: default!(Option<bool>, "NULL"),
+    minimum_should_match: default!(Option<String>, "NULL"),
+) -> SearchQueryInput {
+    SearchQueryInput::ParseWithField {
+        field: field.into_inner(),
+        query_string,
+        lenient,
+        conjunction_mode,
+        minimum_should_match: minimum_should_match.map(parse_minimum_should_match),
+    }
+}
+
+#[pg_extern(name = "parse_with_field", immutable, parallel_safe)]
+pub fn parse_with_field_count(
+    field: FieldName,
+    query_string: String,
+    lenient: default!(Option<bool>, "NULL"),
+    conjunction_mode: default!(Option<bool>, "NULL"),
+    minimum_should_match: i64,
 ) -> SearchQueryInput {
     SearchQueryInput::ParseWithField {
         field: field.into_inner(),
         query_string,
         lenient,
         conjunction_mode,
+        minimum_should_match: Some(MinimumShouldMatch::Count(minimum_should_match)),
     }
 }
 
@@ -399,6 +682,7 @@ pub fn range_i32(field: FieldName, range: Range<i32>) -> SearchQueryInput {
             lower_bound: Bound::Included(OwnedValue::I64(0)),
             upper_bound: Bound::Excluded(OwnedValue::I64(0)),
             is_datetime: false,
+            is_numeric: false,
         },
         Some((lower, upper)) => SearchQueryInput::Range {
             field: field.into_inner(),
@@ -413,6 +697,7 @@ pub fn range_i32(field: FieldName, range: Range<i32>) -> SearchQueryInput {
                 RangeBound::Exclusive(n) => Bound::Excluded(OwnedValue::I64(n as i64)),
             },
             is_datetime: false,
+            is_numeric: false,
         },
     }
 }
@@ -425,6 +710,7 @@ pub fn range_i64(field: FieldName, range: Range<i64>) -> SearchQueryInput {
             lower_bound: Bound::Included(OwnedValue::I64(0)),
             upper_bound: Bound::Excluded(OwnedValue::I64(0)),
             is_datetime: false,
+            is_numeric: false,
         },
         Some((lower, upper)) => SearchQueryInput::Range {
             field: field.into_inner(),
@@ -439,40 +725,37 @@ pub fn range_i64(field: FieldName, range: Range<i64>) -> SearchQueryInput {
                 RangeBound::Exclusive(n) => Bound::Excluded(OwnedValue::I64(n)),
             },
             is_datetime: false,
+            is_numeric: false,
         },
     }
 }
 
 #[pg_extern(name = "range", immutable, parallel_safe)]
 pub fn range_numeric(field: FieldName, range: Range<AnyNumeric>) -> SearchQueryInput {
+    // numerics are passed as their exact decimal text, see `term` on a numeric
+    let decimal = |n: AnyNumeric| OwnedValue::Str(n.to_string());
     match range.into_inner() {
         None => SearchQueryInput::Range {
             field: field.into_inner(),
-            lower_bound: Bound::Included(OwnedValue::F64(0.0)),
-            upper_bound: Bound::Excluded(OwnedValue::F64(0.0)),
+            lower_bound: Bound::Included(OwnedValue::Str("0".into())),
+            upper_bound: Bound::Excluded(OwnedValue::Str("0".into())),
             is_datetime: false,
+            is_numeric: true,
         },
         Some((lower, upper)) => SearchQueryInput::Range {
             field: field.into_inner(),
             lower_bound: match lower {
                 RangeBound::Infinite => Bound::Unbounded,
-                RangeBound::Inclusive(n) => Bound::Included(OwnedValue::F64(
-                    n.try_into().expect("numeric should be a valid f64"),
-                )),
-                RangeBound::Exclusive(n) => Bound::Excluded(OwnedValue::F64(
-                    n.try_into().expect("numeric should be a valid f64"),
-                )),
+                RangeBound::Inclusive(n) => Bound::Included(decimal(n)),
+                RangeBound::Exclusive(n) => Bound::Excluded(decimal(n)),
             },
             upper_bound: match upper {
                 RangeBound::Infinite => Bound::Unbounded,
-                RangeBound::Inclusive(n) => Bound::Included(OwnedValue::F64(
-                    n.try_into().expect("numeric should be a valid f64"),
-                )),
-                RangeBound::Exclusive(n) => Bound::Excluded(OwnedValue::F64(
-                    n.try_into().expect("numeric should be a valid f64"),
-                )),
+                RangeBound::Inclusive(n) => Bound::Included(decimal(n)),
+                RangeBound::Exclusive(n) => Bound::Excluded(decimal(n)),
             },
             is_datetime: false,
+            is_numeric: true,
         },
     }
 }
@@ -491,6 +774,7 @@ macro_rules! datetime_range_fn {
                         tantivy::DateTime::from_timestamp_micros(0),
                     )),
                     is_datetime: true,
+                    is_numeric: false,
                 },
                 Some((lower, upper)) => SearchQueryInput::Range {
                     field: field.into_inner(),
@@ -533,6 +817,7 @@ macro_rules! datetime_range_fn {
                         ),
                     },
                     is_datetime: true,
+                    is_numeric: false,
                 },
             }
         }
@@ -543,6 +828,38 @@ datetime_range_fn!(range_date, pgrx::datum::Date);
 datetime_range_fn!(range_timestamp, pgrx::datum::Timestamp);
 datetime_range_fn!(range_timestamptz, pgrx::datum::TimestampWithTimeZone);
 
+/// Matches the addresses from the start of `lower`'s network to the end of `upper`'s, inclusive.
+/// A NULL bound is unbounded.
+#[pg_extern(name = "range", immutable, parallel_safe)]
+pub fn range_inet(
+    field: FieldName,
+    lower: Option<pgrx::Inet>,
+    upper: Option<pgrx::Inet>,
+) -> SearchQueryInput {
+    SearchQueryInput::Range {
+        field: field.into_inner(),
+        lower_bound: match lower {
+            Some(lower) => {
+                Bound::Included(OwnedValue::IpAddr(parse_inet(&lower).network_bounds().0))
+            }
+            None => Bound::Unbounded,
+        },
+        upper_bound: match upper {
+            Some(upper) => {
+                Bound::Included(OwnedValue::IpAddr(parse_inet(&upper).network_bounds().1))
+            }
+            None => Bound::Unbounded,
+        },
+        is_datetime: false,
+        is_numeric: false,
+    }
+}
+
+fn parse_inet(value: &pgrx::Inet) -> InetValue {
+    InetValue::parse(&value.0)
+        .unwrap_or_else(|| panic!("'{}' is not a valid inet or cidr value", value.0))
+}
+
 #[pg_extern(immutable, parallel_safe)]
 pub fn regex(field: FieldName, pattern: String) -> SearchQueryInput {
     SearchQueryInput::Regex {
@@ -566,6 +883,23 @@ pub fn regex_phrase(
     }
 }
 
+#[pg_extern(immutable, parallel_safe)]
+pub fn sparse_vector(field: FieldName, vector: JsonB) -> SearchQueryInput {
+    let serde_json::Value::Object(weights) = vector.0 else {
+        panic!("sparse vector must be an object of token weights");
+    };
+    SearchQueryInput::SparseVector {
+        field: field.into_inner(),
+        vector: weights
+            .into_iter()
+            .map(|(token, weight)| match weight.as_f64() {
+                Some(weight) => (token, weight as f32),
+                None => panic!("weight of token '{token}' must be a number"),
+            })
+            .collect(),
+    }
+}
+
 macro_rules! term_fn {
     ($func_name:ident, $value_type:ty) => {
         #[pg_extern(name = "term", immutable, parallel_safe)]
@@ -586,6 +920,7 @@ macro_rules! term_fn {
                     field: field.map(|f| f.into_inner()),
                     value: tantivy_value,
                     is_datetime,
+                    is_numeric: false,
                 }
             } else {
                 panic!("no value provided to term query")
@@ -594,6 +929,56 @@ macro_rules! term_fn {
     };
 }
 
+/// An inet or cidr value with a netmask, like `'10.0.0.0/8'::cidr`, matches every address of its
+/// network.
+#[pg_extern(name = "term", immutable, parallel_safe)]
+pub fn term_inet(
+    field: default!(Option<FieldName>, "NULL"),
+    value: default!(Option<pgrx::Inet>, "NULL"),
+) -> SearchQueryInput {
+    let Some(value) = value else {
+        panic!("no value provided to term query")
+    };
+    let inet = parse_inet(&value);
+    if inet.is_host() {
+        return SearchQueryInput::Term {
+            field: field.map(|f| f.into_inner()),
+            value: OwnedValue::IpAddr(inet.addr),
+            is_datetime: false,
+            is_numeric: false,
+        };
+    }
+
+    let (first, last) = inet.network_bounds();
+    SearchQueryInput::Range {
+        field: field
+            .expect("a term query for a network must have a field")
+            .into_inner(),
+        lower_bound: Bound::Included(OwnedValue::IpAddr(first)),
+        upper_bound: Bound::Included(OwnedValue::IpAddr(last)),
+        is_datetime: false,
+        is_numeric: false,
+    }
+}
+
+/// A numeric is passed as its exact decimal text rather than an `f64`, so that it matches the
+/// same value in a field that indexes its column as an exact decimal.
+#[pg_extern(name = "term", immutable, parallel_safe)]
+pub fn numeric(
+    field: default!(Option<FieldName>, "NULL"),
+    value: default!(Option<AnyNumeric>, "NULL"),
+) -> SearchQueryInput {
+    let Some(value) = value else {
+        panic!("no value provided to term query")
+    };
+    SearchQueryInput::Term {
+        field: field.map(|f| f.into_inner()),
+        value: OwnedValue::Str(value.to_string()),
+        is_datetime: false,
+        is_numeric: true,
+    }
+}
+
 #[pg_extern(name = "term", immutable, parallel_safe)]
 pub fn term_anyenum(field: FieldName, value: AnyEnum) -> SearchQueryInput {
     let tantivy_value = TantivyValue::try_from(value)
@@ -605,6 +990,7 @@ pub fn term_anyenum(field: FieldName, value: AnyEnum) -> SearchQueryInput {
         field: Some(field.into_inner()),
         value: tantivy_value,
         is_datetime,
+        is_numeric: false,
     }
 }
 
@@ -640,7 +1026,6 @@ term_fn!(time, pgrx::datum::Time);
 term_fn!(timestamp, pgrx::datum::Timestamp);
 term_fn!(time_with_time_zone, pgrx::datum::TimeWithTimeZone);
 term_fn!(timestamp_with_time_zome, pgrx::datum::TimestampWithTimeZone);
-term_fn!(numeric, pgrx::AnyNumeric);
 term_fn!(uuid, pgrx::Uuid);
 term_fn_unsupported!(json, pgrx::Json, "json");
 term_fn_unsupported!(jsonb, pgrx::JsonB, "jsonb");
@@ -648,7 +1033,6 @@ term_fn_unsupported!(anyarray, pgrx::AnyArray, "array");
 term_fn_unsupported!(pg_box, pgrx::pg_sys::BOX, "box");
 term_fn_unsupported!(point, pgrx::pg_sys::Point, "point");
 term_fn_unsupported!(tid, pgrx::pg_sys::ItemPointerData, "tid");
-term_fn_unsupported!(inet, pgrx::Inet, "inet");
 term_fn_unsupported!(int4range, pgrx::Range<i32>, "int4 range");
 term_fn_unsupported!(int8range, pgrx::Range<i64>, "int8 range");
 term_fn_unsupported!(numrange, pgrx::Range<pgrx::AnyNumeric>, "numeric range");
@@ -826,11 +1210,12 @@ pub fn term_set(
                 field,
                 value,
                 is_datetime,
-                ..
+                is_numeric,
             } => field.map(|field| TermInput {
                 field,
                 value,
                 is_datetime,
+                is_numeric,
             }),
             _ => panic!("only term queries can be passed to term_set"),
         })
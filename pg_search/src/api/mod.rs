@@ -16,8 +16,13 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 pub mod config;
+pub mod geo;
+pub mod hybrid;
 pub mod index;
 pub mod operator;
+pub mod percolate;
+pub mod suggest;
+pub mod terms;
 pub mod tokenize;
 
 #[macro_export]
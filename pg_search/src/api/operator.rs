@@ -17,6 +17,7 @@
 
 mod searchqueryinput;
 mod text;
+mod tsquery;
 
 use crate::api::index::{fieldname_typoid, FieldName};
 use crate::index::reader::index::SearchIndexReader;
@@ -100,6 +101,16 @@ fn anyelement_text_opoid() -> pg_sys::Oid {
     }
 }
 
+fn anyelement_tsquery_opoid() -> pg_sys::Oid {
+    unsafe {
+        direct_function_call::<pg_sys::Oid>(
+            pg_sys::regoperatorin,
+            &[c"@@@(anyelement, tsquery)".into_datum()],
+        )
+        .expect("the `@@@(anyelement, tsquery)` operator should exist")
+    }
+}
+
 pub fn anyelement_query_input_opoid() -> pg_sys::Oid {
     unsafe {
         direct_function_call::<pg_sys::Oid>(
@@ -453,6 +464,7 @@ extension_sql!(
     r#"
 ALTER FUNCTION paradedb.search_with_text SUPPORT paradedb.text_support;
 ALTER FUNCTION paradedb.search_with_query_input SUPPORT paradedb.query_input_support;
+ALTER FUNCTION paradedb.search_with_tsquery SUPPORT paradedb.tsquery_support;
 
 CREATE OPERATOR pg_catalog.@@@ (
     PROCEDURE = search_with_text,
@@ -468,6 +480,14 @@ CREATE OPERATOR pg_catalog.@@@ (
     RESTRICT = query_input_restrict
 );
 
+/* a tsvector on the left is matched by Postgres' own `@@@(tsvector, tsquery)` operator instead */
+CREATE OPERATOR pg_catalog.@@@ (
+    PROCEDURE = search_with_tsquery,
+    LEFTARG = anyelement,
+    RIGHTARG = tsquery,
+    RESTRICT = tsquery_restrict
+);
+
 CREATE OPERATOR CLASS anyelement_bm25_ops DEFAULT FOR TYPE anyelement USING bm25 AS
     OPERATOR 1 pg_catalog.@@@(anyelement, text),                         /* for querying with a tantivy-compatible text query */
     OPERATOR 2 pg_catalog.@@@(anyelement, paradedb.searchqueryinput),    /* for querying with a paradedb.searchqueryinput structure */
@@ -483,5 +503,10 @@ CREATE OPERATOR CLASS anyelement_bm25_ops DEFAULT FOR TYPE anyelement USING bm25
         searchqueryinput::search_with_query_input,
         searchqueryinput::query_input_restrict,
         searchqueryinput::query_input_support,
+        // for using a tsquery on the rhs
+        tsquery::search_with_tsquery,
+        tsquery::tsquery_restrict,
+        tsquery::tsquery_support,
+        tsquery::tsquery,
     ]
 );
@@ -148,6 +148,7 @@ unsafe fn make_query_from_var_and_const(
             query_string,
             lenient: None,
             conjunction_mode: None,
+            minimum_should_match: None,
         },
 
         // the Var represents a table reference, and that means the Const value is to be used
@@ -156,6 +157,7 @@ unsafe fn make_query_from_var_and_const(
             query_string,
             lenient: None,
             conjunction_mode: None,
+            minimum_should_match: None,
         },
     };
     (heaprelid, query)
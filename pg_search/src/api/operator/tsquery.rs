@@ -0,0 +1,200 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::api::index::{fieldname_typoid, FieldName};
+use crate::api::operator::{
+    anyelement_query_input_opoid, anyelement_query_input_procoid, anyelement_tsquery_opoid,
+    attname_from_var, estimate_selectivity, make_search_query_input_opexpr_node,
+    searchqueryinput_typoid, ReturnedNodePointer,
+};
+use crate::postgres::tsearch::{tsquery_text, tsquery_to_search_query_input};
+use crate::postgres::utils::locate_bm25_index;
+use crate::query::SearchQueryInput;
+use crate::{nodecast, UNKNOWN_SELECTIVITY};
+use pgrx::{
+    direct_function_call, pg_extern, pg_getarg, pg_getarg_datum, pg_sys, Internal, IntoDatum,
+    PgList,
+};
+
+/// This is the function behind the `@@@(anyelement, tsquery)` operator.  Like
+/// [`super::text::search_with_text`], we transform those to use `@@@(anyelement, paradedb.searchqueryinput)`,
+/// so this function is only called if the rhs of the @@@ is some kind of volatile value.
+#[pg_extern(sql = "
+CREATE FUNCTION search_with_tsquery(element anyelement, query tsquery) RETURNS bool IMMUTABLE PARALLEL SAFE COST 1000000000 LANGUAGE c AS 'MODULE_PATHNAME', '@FUNCTION_NAME@';
+")]
+pub fn search_with_tsquery(fcinfo: pg_sys::FunctionCallInfo) -> bool {
+    let query = unsafe { pg_getarg_datum(fcinfo, 1) }
+        .map(|datum| unsafe { tsquery_text(datum) })
+        .unwrap_or_default();
+    panic!("query is incompatible with pg_search's `@@@(key_field, tsquery)` operator: `{query}`")
+}
+
+/// Translate a `tsquery` into a query of the field `field`.  Its lexemes are matched as they are,
+/// so `field` should either be a `tsvector` column, or be tokenized the same way the `tsquery` was.
+#[pg_extern(sql = "
+CREATE FUNCTION tsquery(field paradedb.fieldname, query tsquery) RETURNS paradedb.searchqueryinput IMMUTABLE STRICT PARALLEL SAFE LANGUAGE c AS 'MODULE_PATHNAME', '@FUNCTION_NAME@';
+", requires = [FieldName, SearchQueryInput])]
+pub fn tsquery(fcinfo: pg_sys::FunctionCallInfo) -> SearchQueryInput {
+    let field = unsafe { pg_getarg::<FieldName>(fcinfo, 0) }.expect("field must not be NULL");
+    let query = unsafe { pg_getarg_datum(fcinfo, 1) }.expect("query must not be NULL");
+    make_query(field.into_inner(), unsafe { tsquery_text(query) })
+}
+
+fn tsquery_procoid() -> pg_sys::Oid {
+    unsafe {
+        direct_function_call::<pg_sys::Oid>(
+            pg_sys::regprocedurein,
+            &[c"paradedb.tsquery(paradedb.fieldname, tsquery)".into_datum()],
+        )
+        .expect("the `paradedb.tsquery(paradedb.fieldname, tsquery)` function should exist")
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+pub unsafe fn tsquery_support(arg: Internal) -> ReturnedNodePointer {
+    tsquery_support_request_simplify(arg).unwrap_or(ReturnedNodePointer(None))
+}
+
+fn tsquery_support_request_simplify(arg: Internal) -> Option<ReturnedNodePointer> {
+    unsafe {
+        let srs = nodecast!(
+            SupportRequestSimplify,
+            T_SupportRequestSimplify,
+            arg.unwrap()?.cast_mut_ptr::<pg_sys::Node>()
+        )?;
+        if (*srs).root.is_null() {
+            return None;
+        }
+        let mut input_args = PgList::<pg_sys::Node>::from_pg((*(*srs).fcall).args);
+
+        let lhs = input_args.get_ptr(0)?;
+        let rhs = input_args.get_ptr(1)?;
+
+        let var = nodecast!(Var, T_Var, lhs)?;
+        let query = if let Some(const_) = nodecast!(Const, T_Const, rhs) {
+            // the field name comes from the lhs of the @@@ operator
+            let (_, query) = make_query_from_var_and_const((*srs).root, var, const_);
+            Some(query)
+        } else if let Some(param) = nodecast!(Param, T_Param, rhs) {
+            // rewrite the rhs to be a function call to our `paradedb.tsquery(...)` function
+            let mut tsquery_args = PgList::<pg_sys::Node>::new();
+            tsquery_args.push(
+                pg_sys::makeConst(
+                    fieldname_typoid(),
+                    -1,
+                    pg_sys::Oid::INVALID,
+                    -1,
+                    FieldName::from(field_from_var((*srs).root, var))
+                        .into_datum()
+                        .unwrap(),
+                    false,
+                    false,
+                )
+                .cast(),
+            );
+            tsquery_args.push(param.cast());
+
+            let funcexpr = pg_sys::makeFuncExpr(
+                tsquery_procoid(),
+                searchqueryinput_typoid(),
+                tsquery_args.into_pg(),
+                pg_sys::Oid::INVALID,
+                pg_sys::DEFAULT_COLLATION_OID,
+                pg_sys::CoercionForm::COERCE_EXPLICIT_CALL,
+            );
+            input_args.replace_ptr(1, funcexpr.cast());
+            None
+        } else {
+            // This would happen in situations where the rhs of @@@ is a tsquery that can't be
+            // evaluated during planning, either as a Const node or a Param node.
+            panic!("when the left side of the `@@@` operator is a column name the right side must be a tsquery literal");
+        };
+
+        Some(make_search_query_input_opexpr_node(
+            srs,
+            &mut input_args,
+            var,
+            query,
+            None,
+            anyelement_query_input_opoid(),
+            anyelement_query_input_procoid(),
+        ))
+    }
+}
+
+#[pg_extern(immutable, parallel_safe)]
+pub fn tsquery_restrict(
+    planner_info: Internal, // <pg_sys::PlannerInfo>,
+    operator_oid: pg_sys::Oid,
+    args: Internal, // <pg_sys::List>,
+    _var_relid: i32,
+) -> f64 {
+    fn inner_tsquery(
+        planner_info: Internal, // <pg_sys::PlannerInfo>,
+        args: Internal,         // <pg_sys::List>,
+    ) -> Option<f64> {
+        unsafe {
+            let info = planner_info.unwrap()?.cast_mut_ptr::<pg_sys::PlannerInfo>();
+            let args =
+                PgList::<pg_sys::Node>::from_pg(args.unwrap()?.cast_mut_ptr::<pg_sys::List>());
+            let var = nodecast!(Var, T_Var, args.get_ptr(0)?)?;
+            let const_ = nodecast!(Const, T_Const, args.get_ptr(1)?)?;
+
+            let (heaprelid, search_query_input) = make_query_from_var_and_const(info, var, const_);
+            let indexrel = locate_bm25_index(heaprelid)?;
+
+            estimate_selectivity(&indexrel, &search_query_input)
+        }
+    }
+
+    assert!(operator_oid == anyelement_tsquery_opoid());
+
+    let mut selectivity = inner_tsquery(planner_info, args).unwrap_or(UNKNOWN_SELECTIVITY);
+    if selectivity > 1.0 {
+        selectivity = UNKNOWN_SELECTIVITY;
+    }
+
+    selectivity
+}
+
+/// The tsquery's lexemes are matched against the field the lhs of the @@@ operator names, so it
+/// can't be a table reference
+unsafe fn field_from_var(root: *mut pg_sys::PlannerInfo, var: *mut pg_sys::Var) -> String {
+    attname_from_var(root, var)
+        .1
+        .expect("the left side of the `@@@` operator must be a column name when the right side is a tsquery")
+}
+
+unsafe fn make_query_from_var_and_const(
+    root: *mut pg_sys::PlannerInfo,
+    var: *mut pg_sys::Var,
+    const_: *mut pg_sys::Const,
+) -> (pg_sys::Oid, SearchQueryInput) {
+    let (heaprelid, _) = attname_from_var(root, var);
+    if (*const_).constisnull {
+        panic!("query must not be NULL");
+    }
+    let query = make_query(
+        field_from_var(root, var),
+        tsquery_text((*const_).constvalue),
+    );
+    (heaprelid, query)
+}
+
+fn make_query(field: String, tsquery: String) -> SearchQueryInput {
+    tsquery_to_search_query_input(&field, &tsquery).unwrap_or_else(|err| panic!("{err}"))
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::index::reader::index::SearchIndexReader;
+use crate::index::{setup_tokenizers, BlockDirectoryType};
+use crate::postgres::utils::{categorize_fields, row_to_search_document};
+use crate::query::SearchQueryInput;
+use anyhow::{anyhow, bail, Result};
+use pgrx::heap_tuple::PgHeapTuple;
+use pgrx::spi::{quote_identifier, quote_qualified_identifier};
+use pgrx::{iter::SetOfIterator, *};
+use tantivy::collector::Count;
+use tantivy::query::QueryParser;
+use tantivy::{Index, IndexWriter};
+
+/// The smallest memory budget tantivy allows for an index writer, which is plenty for the single
+/// document we percolate
+const PERCOLATE_MEMORY_BUDGET: usize = 15_000_000;
+
+/// Find which of the queries stored in `query_table` match `document`, ie to run saved searches
+/// and alerts against a newly inserted row.
+///
+/// `document` is read as a row of the table `index` belongs to, the way `jsonb_populate_record`
+/// reads it, so its keys are the table's column names and its values are cast to their types.
+/// Keys that aren't columns of the table are ignored, and missing columns are NULL.  The row is
+/// then indexed on its own into an in-memory index with the same schema and tokenizers as
+/// `index`, exactly as `index` would index it, and every stored query is run against it.
+///
+/// `query_table` must have an `id_column` that can be cast to a `bigint`, and a `query_column` of
+/// type `searchqueryinput`.  The ids of the matching queries are returned.  Queries that check
+/// the visibility of the table's rows, like `knn` and `collapse`, can't be run against a single
+/// document and are rejected.
+#[pg_extern]
+pub fn percolate(
+    index: PgRelation,
+    query_table: PgRelation,
+    document: JsonB,
+    id_column: default!(&str, "'id'"),
+    query_column: default!(&str, "'query'"),
+) -> Result<SetOfIterator<'static, i64>> {
+    // # Safety
+    //
+    // Lock the index relation until the end of this function so it is not dropped or
+    // altered while we are reading it.
+    //
+    // Because we accept a PgRelation above, we have confidence that Postgres has already
+    // validated the existence of the relation. We are safe calling the function below as
+    // long we do not pass pg_sys::NoLock without any other locking mechanism of our own.
+    let index = unsafe { PgRelation::with_lock(index.oid(), pg_sys::AccessShareLock as _) };
+
+    let search_reader = SearchIndexReader::open(&index, BlockDirectoryType::Mvcc, false)?;
+    let search_schema = search_reader.schema();
+    let heap_relation = index
+        .heap_relation()
+        .ok_or_else(|| anyhow!("index should belong to a heap relation"))?;
+    let tupdesc = heap_relation.tuple_desc();
+
+    if !document.0.is_object() {
+        bail!("percolate document must be a JSON object");
+    }
+    let row = Spi::get_one_with_args::<PgHeapTuple<'static, AllocatedByRust>>(
+        &format!(
+            "SELECT jsonb_populate_record(NULL::{}, $1)",
+            quote_qualified_identifier(heap_relation.namespace(), heap_relation.name())
+        ),
+        vec![(PgBuiltInOids::JSONBOID.oid(), document.into_datum())],
+    )
+    .map_err(|err| anyhow!("could not read percolate document: {err}"))?
+    .ok_or_else(|| anyhow!("could not read percolate document"))?;
+
+    let mut values = vec![pg_sys::Datum::from(0); tupdesc.len()];
+    let mut isnull = vec![true; tupdesc.len()];
+    unsafe {
+        pg_sys::heap_deform_tuple(
+            row.into_pg(),
+            tupdesc.as_ptr(),
+            values.as_mut_ptr(),
+            isnull.as_mut_ptr(),
+        );
+    }
+
+    // the document isn't a row of the table yet, so it doesn't need a key
+    let key_field_name = search_schema.key_field().name.0;
+    let mut categorized_fields = categorize_fields(&tupdesc, search_schema);
+    categorized_fields.retain(|(search_field, categorized)| {
+        !(search_schema.is_key_field(&search_field.name.0) && isnull[categorized.attno])
+    });
+    let mut document = search_schema.new_document();
+    unsafe {
+        row_to_search_document(
+            values.as_mut_ptr(),
+            isnull.as_mut_ptr(),
+            &key_field_name,
+            &categorized_fields,
+            &mut document,
+        )?;
+    }
+
+    let mut memory_index = Index::create_in_ram(search_schema.schema.clone());
+    setup_tokenizers(&mut memory_index, &index);
+    let mut writer: IndexWriter =
+        memory_index.writer_with_num_threads(1, PERCOLATE_MEMORY_BUDGET)?;
+    writer.add_document(document.into())?;
+    writer.commit()?;
+    let searcher = memory_index.reader()?.searcher();
+
+    let stored_queries = Spi::connect(|client| {
+        client
+            .select(
+                &format!(
+                    "SELECT {}::bigint, {} FROM {}",
+                    quote_identifier(id_column),
+                    quote_identifier(query_column),
+                    quote_qualified_identifier(query_table.namespace(), query_table.name())
+                ),
+                None,
+                None,
+            )?
+            .map(|row| Ok((row.get::<i64>(1)?, row.get::<SearchQueryInput>(2)?)))
+            .collect::<Result<Vec<_>, pgrx::spi::Error>>()
+    })
+    .map_err(|err| anyhow!("could not read the stored queries: {err}"))?;
+
+    let mut matches = vec![];
+    for (id, query) in stored_queries {
+        let (Some(id), Some(query)) = (id, query) else {
+            continue;
+        };
+        if query.needs_heap() {
+            bail!("stored query {id} needs the table's rows, so it can't be percolated");
+        }
+
+        let mut parser = QueryParser::for_index(
+            &memory_index,
+            search_schema
+                .fields
+                .iter()
+                .map(|search_field| search_field.id.0)
+                .collect::<Vec<_>>(),
+        );
+        let query = query
+            .into_tantivy_query(&(&index, search_schema), &mut parser, &searcher)
+            .map_err(|err| anyhow!("could not build stored query {id}: {err}"))?;
+        if searcher.search(&query, &Count)? > 0 {
+            matches.push(id);
+        }
+    }
+
+    Ok(SetOfIterator::new(matches))
+}
@@ -0,0 +1,390 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::index::reader::index::SearchIndexReader;
+use crate::index::BlockDirectoryType;
+use crate::query::SearchQueryInput;
+use crate::schema::completion::{completion_prefix, completion_term, SEPARATOR};
+use crate::schema::{IndexRecordOption, SearchFieldConfig, SearchFieldName};
+use levenshtein_automata::{Distance, LevenshteinAutomatonBuilder, DFA, SINK_STATE};
+use pgrx::{iter::TableIterator, *};
+use std::collections::{HashMap, HashSet};
+use tantivy::postings::TermInfo;
+use tantivy::query::EnableScoring;
+use tantivy::schema::FieldType;
+use tantivy::termdict::TermDictionary;
+use tantivy::{DocId, DocSet, TERMINATED};
+
+/// The largest edit distance we're willing to build a Levenshtein automaton for
+const MAX_EDITS: i32 = 2;
+
+/// Suggest corrections for `input` from the terms that actually exist in `field`, ie for a
+/// "did you mean" prompt when a search comes back empty.
+///
+/// `input` is analyzed with the field's tokenizer and must produce a single term, which is never
+/// suggested itself.  Suggestions are ranked by their edit distance from that term, and then by
+/// how many live documents contain them across all segments.  The `score` reflects that same
+/// ordering.
+#[pg_extern]
+pub fn suggest(
+    index: PgRelation,
+    field: &str,
+    input: &str,
+    max_edits: default!(i32, "2"),
+    limit: default!(i32, "5"),
+) -> TableIterator<
+    'static,
+    (
+        name!(suggestion, String),
+        name!(score, f32),
+        name!(doc_freq, i64),
+    ),
+> {
+    if !(0..=MAX_EDITS).contains(&max_edits) {
+        panic!("max_edits must be between 0 and {MAX_EDITS}");
+    }
+
+    // # Safety
+    //
+    // Lock the index relation until the end of this function so it is not dropped or
+    // altered while we are reading it.
+    //
+    // Because we accept a PgRelation above, we have confidence that Postgres has already
+    // validated the existence of the relation. We are safe calling the function below as
+    // long we do not pass pg_sys::NoLock without any other locking mechanism of our own.
+    let index = unsafe { PgRelation::with_lock(index.oid(), pg_sys::AccessShareLock as _) };
+
+    let search_reader = SearchIndexReader::open(&index, BlockDirectoryType::Mvcc, false)
+        .expect("could not open search index reader");
+    let schema = &search_reader.schema().schema;
+    let search_field = schema
+        .get_field(field)
+        .unwrap_or_else(|_| panic!("field '{field}' is not part of the pg_search index"));
+    if !matches!(
+        schema.get_field_entry(search_field).field_type(),
+        FieldType::Str(_)
+    ) {
+        panic!("field '{field}' must be a text field");
+    }
+
+    let mut analyzer = search_reader
+        .searcher()
+        .index()
+        .tokenizer_for_field(search_field)
+        .expect("field should have a tokenizer");
+    let mut stream = analyzer.token_stream(input);
+    let mut tokens = vec![];
+    while stream.advance() {
+        tokens.push(stream.token().text.clone());
+    }
+    let term = match tokens.as_slice() {
+        [] => return TableIterator::new(vec![]),
+        [term] => term.clone(),
+        _ => panic!(
+            "suggest input must be a single term, but '{input}' has {} terms",
+            tokens.len()
+        ),
+    };
+
+    let dfa = LevenshteinAutomatonBuilder::new(max_edits as u8, true).build_dfa(&term);
+
+    // the same term is usually in many segments, so sum up its document frequencies
+    let mut candidates = HashMap::<Vec<u8>, (u8, u64)>::new();
+    for segment_reader in search_reader.segment_readers() {
+        let alive_bitset = segment_reader.alive_bitset();
+        let inverted_index = segment_reader
+            .inverted_index(search_field)
+            .expect("should be able to open the inverted index");
+        search_terms(
+            inverted_index.terms(),
+            &dfa,
+            None,
+            |key, term_info, distance| {
+                // the input term is what we're suggesting corrections for
+                if distance == 0 {
+                    return;
+                }
+
+                // the term info's doc_freq still counts deleted documents, so count the live
+                // postings of segments that have any deletes
+                let doc_freq = match alive_bitset {
+                    None => term_info.doc_freq as u64,
+                    Some(alive_bitset) => {
+                        let mut postings = inverted_index
+                            .read_postings_from_terminfo(term_info, IndexRecordOption::Basic.into())
+                            .expect("should be able to read the postings");
+                        let mut doc_freq = 0;
+                        let mut doc = postings.doc();
+                        while doc != TERMINATED {
+                            if alive_bitset.is_alive(doc) {
+                                doc_freq += 1;
+                            }
+                            doc = postings.advance();
+                        }
+                        doc_freq
+                    }
+                };
+                if doc_freq == 0 {
+                    return;
+                }
+
+                let entry = candidates.entry(key.to_vec()).or_insert((distance, 0));
+                entry.1 += doc_freq;
+            },
+        )
+        .expect("should be able to search the term dictionary");
+    }
+
+    let max_doc_freq = candidates
+        .values()
+        .map(|(_, doc_freq)| *doc_freq)
+        .max()
+        .unwrap_or(0);
+    let mut suggestions = candidates
+        .into_iter()
+        .map(|(term, (distance, doc_freq))| {
+            // closer terms always outrank more frequent ones
+            let score =
+                (max_edits as u8 - distance) as f32 + doc_freq as f32 / (max_doc_freq + 1) as f32;
+            (
+                String::from_utf8_lossy(&term).into_owned(),
+                score,
+                doc_freq as i64,
+            )
+        })
+        .collect::<Vec<_>>();
+    suggestions.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    suggestions.truncate(limit.max(0) as usize);
+
+    TableIterator::new(suggestions)
+}
+
+/// Complete `prefix` from the inputs of a completion `field`, ie for an autocomplete dropdown.
+///
+/// With a `fuzziness` above zero, inputs that start with something within that many edits of
+/// `prefix` are suggested too.  If `context_field` and `context` are given, only inputs of rows
+/// whose `context_field` matches every term of `context` are suggested.
+///
+/// Suggestions are ranked by how many edits they needed, and then by their weight: the largest
+/// value of the field's `weight` column among the rows with that input, or the number of those
+/// rows if the field has no `weight`.  The `score` is that weight.
+#[pg_extern]
+pub fn complete(
+    index: PgRelation,
+    field: &str,
+    prefix: &str,
+    limit: default!(i32, "5"),
+    fuzziness: default!(i32, "0"),
+    context_field: default!(Option<String>, "NULL"),
+    context: default!(Option<String>, "NULL"),
+) -> TableIterator<'static, (name!(suggestion, String), name!(score, f32))> {
+    if !(0..=MAX_EDITS).contains(&fuzziness) {
+        panic!("fuzziness must be between 0 and {MAX_EDITS}");
+    }
+
+    // # Safety
+    //
+    // Lock the index relation until the end of this function so it is not dropped or
+    // altered while we are reading it.
+    //
+    // Because we accept a PgRelation above, we have confidence that Postgres has already
+    // validated the existence of the relation. We are safe calling the function below as
+    // long we do not pass pg_sys::NoLock without any other locking mechanism of our own.
+    let index = unsafe { PgRelation::with_lock(index.oid(), pg_sys::AccessShareLock as _) };
+
+    let search_reader = SearchIndexReader::open(&index, BlockDirectoryType::Mvcc, false)
+        .expect("could not open search index reader");
+    let search_field = search_reader
+        .schema()
+        .get_search_field(&SearchFieldName(field.into()))
+        .unwrap_or_else(|| panic!("field '{field}' is not part of the pg_search index"));
+    let SearchFieldConfig::Completion { weight, .. } = &search_field.config else {
+        panic!("field '{field}' must be a completion field");
+    };
+    let completion_field = search_field.id.0;
+
+    let context_query = match (context_field, context) {
+        (Some(context_field), Some(context)) => {
+            Some(search_reader.query(&SearchQueryInput::Match {
+                field: context_field,
+                value: context,
+                tokenizer: None,
+                fuzziness: None,
+                transposition_cost_one: None,
+                prefix: None,
+                conjunction_mode: Some(true),
+            }))
+        }
+        (None, None) => None,
+        _ => panic!("context_field and context must be given together"),
+    };
+    let context_weight = context_query.map(|query| {
+        query
+            .weight(EnableScoring::disabled_from_searcher(
+                search_reader.searcher(),
+            ))
+            .expect("should be able to build the context query")
+    });
+
+    let dfa = LevenshteinAutomatonBuilder::new(fuzziness as u8, true)
+        .build_prefix_dfa(&completion_prefix(prefix));
+
+    // the same input is usually in many segments, so combine its weights
+    let mut candidates = HashMap::<String, (u8, f64)>::new();
+    for segment_reader in search_reader.segment_readers() {
+        let context_docs = context_weight.as_ref().map(|weight| {
+            let mut scorer = weight
+                .scorer(segment_reader, 1.0)
+                .expect("should be able to search the context field");
+            let mut docs = HashSet::<DocId>::new();
+            let mut doc = scorer.doc();
+            while doc != TERMINATED {
+                docs.insert(doc);
+                doc = scorer.advance();
+            }
+            docs
+        });
+        let alive_bitset = segment_reader.alive_bitset();
+
+        let inverted_index = segment_reader
+            .inverted_index(completion_field)
+            .expect("should be able to open the inverted index");
+
+        // prefixes are only matched against the lowercased input, before the first separator
+        let end = Some(SEPARATOR as u8);
+        search_terms(
+            inverted_index.terms(),
+            &dfa,
+            end,
+            |key, term_info, distance| {
+                let Some((input, input_weight)) = completion_term(key) else {
+                    return;
+                };
+
+                // the rows with the input only need to be looked at if some of them might be
+                // deleted, or might not match the context
+                let num_rows = if alive_bitset.is_none() && context_docs.is_none() {
+                    term_info.doc_freq as f64
+                } else {
+                    let mut postings = inverted_index
+                        .read_postings_from_terminfo(term_info, IndexRecordOption::Basic.into())
+                        .expect("should be able to read the postings");
+                    let mut num_rows = 0.0;
+                    let mut doc = postings.doc();
+                    while doc != TERMINATED {
+                        let is_alive = alive_bitset.map_or(true, |bitset| bitset.is_alive(doc));
+                        let in_context = context_docs
+                            .as_ref()
+                            .map_or(true, |docs| docs.contains(&doc));
+                        if is_alive && in_context {
+                            num_rows += 1.0;
+                        }
+                        doc = postings.advance();
+                    }
+                    num_rows
+                };
+                if num_rows == 0.0 {
+                    return;
+                }
+
+                let matched = match weight {
+                    Some(_) => input_weight.unwrap_or(0.0),
+                    None => num_rows,
+                };
+                candidates
+                    .entry(input.to_string())
+                    .and_modify(|(_, existing)| {
+                        *existing = match weight {
+                            Some(_) => existing.max(matched),
+                            None => *existing + matched,
+                        }
+                    })
+                    .or_insert((distance, matched));
+            },
+        )
+        .expect("should be able to search the term dictionary");
+    }
+
+    let mut suggestions = candidates.into_iter().collect::<Vec<_>>();
+    suggestions.sort_by(|(a, (a_distance, a_weight)), (b, (b_distance, b_weight))| {
+        a_distance
+            .cmp(b_distance)
+            .then_with(|| b_weight.total_cmp(a_weight))
+            .then_with(|| a.cmp(b))
+    });
+    suggestions.truncate(limit.max(0) as usize);
+
+    TableIterator::new(
+        suggestions
+            .into_iter()
+            .map(|(input, (_, weight))| (input, weight as f32)),
+    )
+}
+
+/// Calls `f` with each term in `terms` that `dfa` matches, along with its edit distance.  If
+/// `end` is given, only the part of each term before the first `end` byte is matched.
+///
+/// Rather than reading every term, as soon as a term's leading bytes leave the automaton with no
+/// way to match, every term that starts with those bytes is skipped over.
+fn search_terms(
+    terms: &TermDictionary,
+    dfa: &DFA,
+    end: Option<u8>,
+    mut f: impl FnMut(&[u8], &TermInfo, u8),
+) -> std::io::Result<()> {
+    let mut stream = terms.stream()?;
+    while stream.advance() {
+        let key = stream.key();
+        let matched_len = end
+            .and_then(|end| key.iter().position(|byte| *byte == end))
+            .unwrap_or(key.len());
+        let mut state = dfa.initial_state();
+        let mut dead_end = None;
+        for (idx, byte) in key[..matched_len].iter().enumerate() {
+            state = dfa.transition(state, *byte);
+            if state == SINK_STATE {
+                dead_end = Some(idx + 1);
+                break;
+            }
+        }
+
+        match dead_end {
+            None => {
+                if let Distance::Exact(distance) = dfa.distance(state) {
+                    f(key, stream.value(), distance);
+                }
+            }
+            Some(len) => match next_prefix(&key[..len]) {
+                Some(next) => stream = terms.range().ge(next).into_stream()?,
+                None => break,
+            },
+        }
+    }
+    Ok(())
+}
+
+/// The smallest byte string that's greater than every byte string starting with `prefix`, if any
+fn next_prefix(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut next = prefix.to_vec();
+    while let Some(last) = next.pop() {
+        if last < u8::MAX {
+            next.push(last + 1);
+            return Some(next);
+        }
+    }
+    None
+}
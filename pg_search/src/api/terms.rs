@@ -0,0 +1,300 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::index::fast_fields_helper::FFType;
+use crate::index::reader::index::SearchIndexReader;
+use crate::index::BlockDirectoryType;
+use crate::postgres::types::TantivyValue;
+use crate::postgres::utils::categorize_fields;
+use crate::postgres::visibility_checker::VisibilityChecker;
+use crate::query::SearchQueryInput;
+use crate::schema::IndexRecordOption;
+use pgrx::{iter::TableIterator, *};
+use std::cmp::Reverse;
+use std::collections::binary_heap::PeekMut;
+use std::collections::{BTreeSet, BinaryHeap};
+use tantivy::collector::DocSetCollector;
+use tantivy::postings::Postings;
+use tantivy::schema::{Field, FieldType, OwnedValue};
+use tantivy::{DocSet, Term, TERMINATED};
+
+/// List the terms of `field` that are actually in the index, optionally only those starting with
+/// `prefix`, in term order.
+///
+/// `doc_freq` is the number of documents that contain the term, and `total_term_freq` is the
+/// number of times it occurs across all of them.  Both are counted across every segment, and
+/// leave out deleted documents.
+#[pg_extern]
+pub fn terms(
+    index: PgRelation,
+    field: &str,
+    prefix: default!(Option<String>, "NULL"),
+    limit: default!(Option<i32>, "NULL"),
+) -> TableIterator<
+    'static,
+    (
+        name!(term, String),
+        name!(doc_freq, i64),
+        name!(total_term_freq, i64),
+    ),
+> {
+    // # Safety
+    //
+    // Lock the index relation until the end of this function so it is not dropped or
+    // altered while we are reading it.
+    //
+    // Because we accept a PgRelation above, we have confidence that Postgres has already
+    // validated the existence of the relation. We are safe calling the function below as
+    // long we do not pass pg_sys::NoLock without any other locking mechanism of our own.
+    let index = unsafe { PgRelation::with_lock(index.oid(), pg_sys::AccessShareLock as _) };
+
+    let search_reader = SearchIndexReader::open(&index, BlockDirectoryType::Mvcc, false)
+        .expect("could not open search index reader");
+    let search_field = text_field(&search_reader, field);
+    let prefix = prefix.unwrap_or_default();
+
+    let limit = limit.map_or(usize::MAX, |limit| limit.max(0) as usize);
+
+    let segments = search_reader
+        .segment_readers()
+        .iter()
+        .map(|segment_reader| {
+            let inverted_index = segment_reader
+                .inverted_index(search_field)
+                .expect("should be able to open the inverted index");
+            (inverted_index, segment_reader.alive_bitset())
+        })
+        .collect::<Vec<_>>();
+    let mut streams = segments
+        .iter()
+        .map(|(inverted_index, _)| {
+            inverted_index
+                .terms()
+                .range()
+                .ge(prefix.as_bytes())
+                .into_stream()
+                .expect("should be able to stream the term dictionary")
+        })
+        .collect::<Vec<_>>();
+
+    // every segment's terms are in term order, so merge them, one term at a time, through a heap
+    // of each segment's next term until we have as many terms as we need
+    let mut next_terms = BinaryHeap::new();
+    for (segment_idx, stream) in streams.iter_mut().enumerate() {
+        if stream.advance() && stream.key().starts_with(prefix.as_bytes()) {
+            next_terms.push(Reverse((stream.key().to_vec(), segment_idx)));
+        }
+    }
+
+    let mut terms = vec![];
+    while terms.len() < limit {
+        let Some(Reverse((term, _))) = next_terms.peek().cloned() else {
+            break;
+        };
+
+        let (mut doc_freq, mut total_term_freq) = (0, 0);
+        while let Some(Reverse((_, segment_idx))) = next_terms
+            .peek_mut()
+            .and_then(|next| (next.0 .0 == term).then(|| PeekMut::pop(next)))
+        {
+            let (inverted_index, alive_bitset) = &segments[segment_idx];
+            let stream = &mut streams[segment_idx];
+            let mut postings = inverted_index
+                .read_postings_from_terminfo(stream.value(), IndexRecordOption::WithFreqs.into())
+                .expect("should be able to read the postings");
+            let mut doc = postings.doc();
+            while doc != TERMINATED {
+                if alive_bitset.map_or(true, |bitset| bitset.is_alive(doc)) {
+                    doc_freq += 1;
+                    total_term_freq += postings.term_freq() as i64;
+                }
+                doc = postings.advance();
+            }
+
+            if stream.advance() && stream.key().starts_with(prefix.as_bytes()) {
+                next_terms.push(Reverse((stream.key().to_vec(), segment_idx)));
+            }
+        }
+
+        if doc_freq > 0 {
+            terms.push((
+                String::from_utf8_lossy(&term).into_owned(),
+                doc_freq,
+                total_term_freq,
+            ));
+        }
+    }
+
+    TableIterator::new(terms)
+}
+
+/// List the terms that were recorded in `field` for the document whose key field is `key`, along
+/// with their positions and how often they occur, in term order.
+///
+/// Only the version of the row that's visible to the current snapshot is considered.  Its value
+/// is analyzed again with the field's tokenizer to find the terms to look up, so that we don't
+/// have to read the postings of every term in the index.  That means that if the field's
+/// tokenizer has changed since the row was indexed, say by an upgrade, the terms it produces now
+/// might not be the ones that were recorded, and those are left out.  Positions are only
+/// available if the field's `record` option is `position`.
+#[pg_extern]
+pub fn term_vectors(
+    index: PgRelation,
+    key: AnyElement,
+    field: &str,
+) -> TableIterator<
+    'static,
+    (
+        name!(term, String),
+        name!(term_freq, i32),
+        name!(positions, Vec<i32>),
+    ),
+> {
+    // # Safety
+    //
+    // Lock the index relation until the end of this function so it is not dropped or
+    // altered while we are reading it.
+    //
+    // Because we accept a PgRelation above, we have confidence that Postgres has already
+    // validated the existence of the relation. We are safe calling the function below as
+    // long we do not pass pg_sys::NoLock without any other locking mechanism of our own.
+    let index = unsafe { PgRelation::with_lock(index.oid(), pg_sys::AccessShareLock as _) };
+
+    let search_reader = SearchIndexReader::open(&index, BlockDirectoryType::Mvcc, false)
+        .expect("could not open search index reader");
+    let search_field = text_field(&search_reader, field);
+    let heap_relation = index
+        .heap_relation()
+        .expect("index should belong to a heap relation");
+    let (_, categorized) = categorize_fields(&heap_relation.tuple_desc(), search_reader.schema())
+        .into_iter()
+        .find(|(categorized_field, _)| categorized_field.id.0 == search_field)
+        .unwrap_or_else(|| panic!("field '{field}' is not read from a column of the table"));
+    let mut analyzer = search_reader
+        .searcher()
+        .index()
+        .tokenizer_for_field(search_field)
+        .expect("field should have a tokenizer");
+
+    let key_value = unsafe {
+        TantivyValue::try_from_datum(key.datum(), PgOid::from_untagged(key.oid()))
+            .unwrap_or_else(|err| panic!("could not read term_vectors key: {err}"))
+            .0
+    };
+    let key_query = search_reader.query(&SearchQueryInput::Term {
+        field: Some(search_reader.schema().key_field().name.0),
+        value: key_value,
+        is_datetime: false,
+        is_numeric: false,
+    });
+    let addresses = search_reader
+        .searcher()
+        .search(&key_query, &DocSetCollector)
+        .expect("should be able to search for the key");
+
+    let mut visibility_checker = unsafe {
+        VisibilityChecker::with_rel_and_snap(heap_relation.as_ptr(), pg_sys::GetActiveSnapshot())
+    };
+    let slot = unsafe { pg_sys::table_slot_create(heap_relation.as_ptr(), std::ptr::null_mut()) };
+
+    let mut vectors = vec![];
+    for address in addresses {
+        let segment_reader = search_reader.searcher().segment_reader(address.segment_ord);
+        let ctid = FFType::new(segment_reader.fast_fields(), "ctid")
+            .as_u64(address.doc_id)
+            .expect("ctid should be present");
+
+        // older versions of the row are still in the index until they're vacuumed away, so only
+        // the visible one has the terms we're after
+        let Some(values) = visibility_checker.exec_if_visible(ctid, slot, |_| unsafe {
+            let attno = categorized.attno;
+            pg_sys::slot_getsomeattrs_int(slot, attno as i32 + 1);
+            if *(*slot).tts_isnull.add(attno) {
+                return vec![];
+            }
+            let datum = *(*slot).tts_values.add(attno);
+            let values = if categorized.is_array {
+                TantivyValue::try_from_datum_array(datum, categorized.base_oid)
+            } else {
+                TantivyValue::try_from_datum(datum, categorized.base_oid).map(|value| vec![value])
+            };
+            values.unwrap_or_else(|err| panic!("could not read the value of '{field}': {err}"))
+        }) else {
+            continue;
+        };
+
+        let mut terms = BTreeSet::new();
+        for value in values {
+            if let OwnedValue::Str(text) = value.0 {
+                let mut stream = analyzer.token_stream(&text);
+                while stream.advance() {
+                    terms.insert(stream.token().text.clone());
+                }
+            }
+        }
+
+        let inverted_index = segment_reader
+            .inverted_index(search_field)
+            .expect("should be able to open the inverted index");
+        for term in terms {
+            let Some(mut postings) = inverted_index
+                .read_postings(
+                    &Term::from_field_text(search_field, &term),
+                    IndexRecordOption::WithFreqsAndPositions.into(),
+                )
+                .expect("should be able to read the postings")
+            else {
+                continue;
+            };
+            if postings.seek(address.doc_id) != address.doc_id {
+                continue;
+            }
+
+            let mut positions = vec![];
+            postings.positions(&mut positions);
+            vectors.push((
+                term,
+                postings.term_freq() as i32,
+                positions
+                    .into_iter()
+                    .map(|position| position as i32)
+                    .collect(),
+            ));
+        }
+    }
+
+    unsafe {
+        pg_sys::ExecDropSingleTupleTableSlot(slot);
+    }
+
+    TableIterator::new(vectors)
+}
+
+/// Look up the tantivy field for `field`, which must be a text field
+fn text_field(search_reader: &SearchIndexReader, field: &str) -> Field {
+    let schema = &search_reader.schema().schema;
+    let search_field = schema
+        .get_field(field)
+        .unwrap_or_else(|_| panic!("field '{field}' is not part of the pg_search index"));
+    if !matches!(
+        schema.get_field_entry(search_field).field_type(),
+        FieldType::Str(_)
+    ) {
+        panic!("field '{field}' must be a text field");
+    }
+    search_field
+}
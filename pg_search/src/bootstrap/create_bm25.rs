@@ -28,6 +28,7 @@ use crate::schema::IndexRecordOption;
 use crate::schema::SearchFieldConfig;
 use crate::schema::SearchFieldName;
 use crate::schema::SearchFieldType;
+use crate::schema::SearchSimilarity;
 use anyhow::bail;
 use anyhow::Result;
 use pgrx::prelude::*;
@@ -202,6 +203,74 @@ pub unsafe fn index_fields(index: PgRelation) -> JsonB {
             _ => panic!("'{name}' cannot be indexed as a datetime field"),
         });
 
+    let inet_fields =
+        rdopts
+            .get_inet_fields()
+            .into_iter()
+            .map(|(name, config)| match name_type_map.get(&name) {
+                Some(field_type @ SearchFieldType::Inet) => (name, config, *field_type),
+                _ => panic!("'{name}' cannot be indexed as an inet field"),
+            });
+
+    let bytes_fields = rdopts.get_bytes_fields().into_iter().map(|(name, config)| {
+        match name_type_map.get(&name) {
+            Some(field_type @ SearchFieldType::Bytes) => (name, config, *field_type),
+            _ => panic!("'{name}' cannot be indexed as a bytes field"),
+        }
+    });
+
+    let completion_fields = rdopts
+        .get_completion_fields()
+        .into_iter()
+        .map(|(name, config)| {
+            let column = SearchFieldName(config.column().unwrap_or(&name.0).into());
+            match name_type_map.get(&column) {
+                Some(field_type @ SearchFieldType::Text) => (name, config, *field_type),
+                _ => panic!("'{name}' cannot be indexed as a completion field"),
+            }
+        });
+
+    let vector_fields = rdopts
+        .get_vector_fields()
+        .into_iter()
+        .map(|(name, config)| {
+            let column = SearchFieldName(config.column().unwrap_or(&name.0).into());
+            match name_type_map.get(&column) {
+                Some(field_type @ SearchFieldType::F64) => (name, config, *field_type),
+                _ => panic!("'{name}' cannot be indexed as a vector field"),
+            }
+        });
+
+    let sparse_vector_fields =
+        rdopts
+            .get_sparse_vector_fields()
+            .into_iter()
+            .map(|(name, config)| {
+                let column = SearchFieldName(config.column().unwrap_or(&name.0).into());
+                match name_type_map.get(&column) {
+                    Some(field_type @ SearchFieldType::Json) => (name, config, *field_type),
+                    _ => panic!("'{name}' cannot be indexed as a sparse vector field"),
+                }
+            });
+
+    let geo_fields = rdopts.get_geo_fields().into_iter().map(|(name, config)| {
+        let column = SearchFieldName(config.column().unwrap_or(&name.0).into());
+        match name_type_map.get(&column) {
+            Some(field_type @ (SearchFieldType::Geo | SearchFieldType::Json)) => {
+                (name, config, *field_type)
+            }
+            _ => panic!("'{name}' cannot be indexed as a geo field"),
+        }
+    });
+
+    let uuid_fields = rdopts.get_uuid_fields().into_iter().map(|(name, config)| {
+        let column = SearchFieldName(config.column().unwrap_or(&name.0).into());
+        match name_type_map.get(&column) {
+            Some(field_type @ SearchFieldType::Text) => (name, config, *field_type),
+            _ => panic!("'{name}' cannot be indexed as a uuid field"),
+        }
+    });
+
     let key_field = rdopts.get_key_field().expect("must specify key_field");
     let key_field_type = match name_type_map.get(&key_field) {
         Some(field_type) => field_type,
@@ -213,6 +282,8 @@ pub unsafe fn index_fields(index: PgRelation) -> JsonB {
                 indexed: true,
                 fast: true,
                 stored: false,
+                index_nulls: false,
+                scale: None,
                 column: None,
             }
         }
@@ -224,6 +295,8 @@ pub unsafe fn index_fields(index: PgRelation) -> JsonB {
             tokenizer: SearchTokenizer::Raw(SearchTokenizerFilters::default()),
             record: IndexRecordOption::Basic,
             normalizer: SearchNormalizer::Raw,
+            similarity: SearchSimilarity::default(),
+            index_nulls: false,
             column: None,
         },
         SearchFieldType::Json => SearchFieldConfig::Json {
@@ -234,25 +307,33 @@ pub unsafe fn index_fields(index: PgRelation) -> JsonB {
             tokenizer: SearchTokenizer::Raw(SearchTokenizerFilters::default()),
             record: IndexRecordOption::Basic,
             normalizer: SearchNormalizer::Raw,
+            similarity: SearchSimilarity::default(),
             fieldnorms: true,
+            index_nulls: false,
             column: None,
         },
         SearchFieldType::Range => SearchFieldConfig::Range {
             stored: false,
+            index_nulls: false,
             column: None,
         },
         SearchFieldType::Bool => SearchFieldConfig::Boolean {
             indexed: true,
             fast: true,
             stored: false,
+            index_nulls: false,
             column: None,
         },
         SearchFieldType::Date => SearchFieldConfig::Date {
             indexed: true,
             fast: true,
             stored: false,
+            index_nulls: false,
             column: None,
         },
+        SearchFieldType::Geo => panic!("key_field cannot be a point column"),
+        SearchFieldType::Inet => panic!("key_field cannot be an inet or cidr column"),
+        SearchFieldType::Bytes => panic!("key_field cannot be a bytea column"),
     };
 
     // Concatenate the separate lists of fields.
@@ -262,6 +343,13 @@ pub unsafe fn index_fields(index: PgRelation) -> JsonB {
         .chain(json_fields)
         .chain(range_fields)
         .chain(datetime_fields)
+        .chain(inet_fields)
+        .chain(bytes_fields)
+        .chain(completion_fields)
+        .chain(vector_fields)
+        .chain(sparse_vector_fields)
+        .chain(geo_fields)
+        .chain(uuid_fields)
         .chain(std::iter::once((
             key_field.clone(),
             key_config,
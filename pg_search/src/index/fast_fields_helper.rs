@@ -20,8 +20,9 @@
 use crate::index::reader::index::SearchIndexReader;
 use crate::postgres::types::TantivyValue;
 use crate::schema::SearchFieldType;
+use std::net::Ipv6Addr;
 use std::sync::OnceLock;
-use tantivy::columnar::StrColumn;
+use tantivy::columnar::{BytesColumn, StrColumn};
 use tantivy::fastfield::{Column, FastFieldReaders};
 use tantivy::schema::OwnedValue;
 use tantivy::{DocAddress, DocId};
@@ -107,6 +108,8 @@ pub enum FFType {
     U64(Column<u64>),
     Bool(Column<bool>),
     Date(Column<tantivy::DateTime>),
+    Bytes(BytesColumn),
+    IpAddr(Column<Ipv6Addr>),
 }
 
 impl FFType {
@@ -126,6 +129,10 @@ impl FFType {
             Self::Bool(ff)
         } else if let Ok(ff) = ffr.date(field_name) {
             Self::Date(ff)
+        } else if let Ok(Some(ff)) = ffr.bytes(field_name) {
+            Self::Bytes(ff)
+        } else if let Ok(ff) = ffr.ip_addr(field_name) {
+            Self::IpAddr(ff)
         } else {
             panic!("`{field_name}` is missing or is not configured as a fast field")
         }
@@ -171,6 +178,22 @@ impl FFType {
                     .map(|first| first.into())
                     .unwrap_or(OwnedValue::Null),
             ),
+            FFType::Bytes(ff) => {
+                let mut bytes = vec![];
+                match ff.term_ords(doc).next() {
+                    Some(ord) => {
+                        ff.ord_to_bytes(ord, &mut bytes)
+                            .expect("bytes should be retrievable for term ord");
+                        TantivyValue(OwnedValue::Bytes(bytes))
+                    }
+                    None => TantivyValue(OwnedValue::Null),
+                }
+            }
+            FFType::IpAddr(ff) => TantivyValue(
+                ff.first(doc)
+                    .map(OwnedValue::IpAddr)
+                    .unwrap_or(OwnedValue::Null),
+            ),
         };
 
         value
@@ -232,6 +255,23 @@ impl FFType {
             None
         }
     }
+
+    /// Given a [`DocId`], what is its "fast field" value as an f64?  Dates are returned as
+    /// microseconds since the epoch.
+    ///
+    /// If this [`FFType`] isn't numeric or a date, or the doc has no value, this function
+    /// returns [`None`].
+    #[inline(always)]
+    pub fn as_f64(&self, doc: DocId) -> Option<f64> {
+        match self {
+            FFType::I64(ff) => ff.first(doc).map(|v| v as f64),
+            FFType::F64(ff) => ff.first(doc),
+            FFType::U64(ff) => ff.first(doc).map(|v| v as f64),
+            FFType::Bool(ff) => ff.first(doc).map(|v| v as u8 as f64),
+            FFType::Date(ff) => ff.first(doc).map(|v| v.into_timestamp_micros() as f64),
+            FFType::Junk | FFType::Text(_) | FFType::Bytes(_) | FFType::IpAddr(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Ord, Eq, PartialOrd, PartialEq)]
@@ -247,6 +287,8 @@ pub enum WhichFastField {
 pub enum FastFieldType {
     String,
     Numeric,
+    /// A `numeric` column indexed as an exact decimal with this scale
+    Decimal(u32),
 }
 
 impl From<SearchFieldType> for FastFieldType {
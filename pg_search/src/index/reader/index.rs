@@ -21,27 +21,29 @@ use crate::index::reader::index::scorer_iter::DeferredScorer;
 use crate::index::{setup_tokenizers, BlockDirectoryType};
 use crate::postgres::storage::block::CLEANUP_LOCK;
 use crate::postgres::storage::buffer::{BufferManager, PinnedBuffer};
-use crate::query::SearchQueryInput;
-use crate::schema::SearchField;
+use crate::query::{split_field_and_path, CollapseCollector, SearchQueryInput};
+use crate::schema::geo::{read_geo, GeoPoint};
+use crate::schema::{SearchField, SearchFieldConfig};
 use crate::schema::{SearchFieldName, SearchIndexSchema};
 use anyhow::Result;
 use pgrx::{pg_sys, PgRelation};
 use rustc_hash::FxHashMap;
 use std::cmp::Ordering;
-use std::collections::HashSet;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fmt::Debug;
 use std::path::PathBuf;
 use std::sync::Arc;
 use tantivy::collector::{Collector, TopDocs};
 use tantivy::index::Index;
 use tantivy::query::{EnableScoring, QueryParser, Weight};
-use tantivy::schema::FieldType;
+use tantivy::schema::{FieldType, ValueBytes};
 use tantivy::termdict::TermOrdinal;
 use tantivy::{
     query::Query, DocAddress, DocId, DocSet, IndexReader, Order, ReloadPolicy, Score, Searcher,
-    SegmentOrdinal, SegmentReader, TantivyDocument,
+    SegmentOrdinal, SegmentReader, TantivyDocument, Term,
 };
 use tantivy::{snippet::SnippetGenerator, Executor};
+use tantivy_common::json_path_writer::{JSON_END_OF_PATH, JSON_PATH_SEGMENT_SEP};
 
 /// Represents a matching document from a tantivy search.  Typically, it is returned as an Iterator
 /// Item alongside the originating tantivy [`DocAddress`]
@@ -103,6 +105,16 @@ pub enum SearchResults {
         FastFieldCache,
         std::vec::IntoIter<(TermOrdinal, DocAddress)>,
     ),
+    TopNByDistance(
+        Searcher,
+        FastFieldCache,
+        std::vec::IntoIter<(GeoDistanceKey, DocAddress)>,
+    ),
+    TopNByUuid(
+        Searcher,
+        FastFieldCache,
+        std::vec::IntoIter<(UuidKey, DocAddress)>,
+    ),
     SingleSegment(
         Searcher,
         SegmentOrdinal,
@@ -129,6 +141,44 @@ impl PartialOrd for TweakedScore {
     }
 }
 
+/// Orders documents by the distance from their geo point to an origin, where documents without a
+/// point are infinitely far away
+#[derive(PartialEq, Clone)]
+pub struct GeoDistanceKey {
+    dir: SortDirection,
+    distance: f64,
+}
+
+impl PartialOrd for GeoDistanceKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let cmp = self.distance.partial_cmp(&other.distance);
+        match self.dir {
+            SortDirection::Desc => cmp,
+            SortDirection::Asc => cmp.map(|o| o.reverse()),
+            SortDirection::None => Some(Ordering::Equal),
+        }
+    }
+}
+
+/// Orders documents by the 128-bit value of their uuid, which is the order postgres sorts uuids
+/// in, where documents without a uuid sort as the largest
+#[derive(PartialEq, Clone)]
+pub struct UuidKey {
+    dir: SortDirection,
+    uuid: Option<u128>,
+}
+
+impl PartialOrd for UuidKey {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let cmp = (self.uuid.is_none(), self.uuid).partial_cmp(&(other.uuid.is_none(), other.uuid));
+        match self.dir {
+            SortDirection::Desc => cmp,
+            SortDirection::Asc => cmp.map(|o| o.reverse()),
+            SortDirection::None => Some(Ordering::Equal),
+        }
+    }
+}
+
 impl Iterator for SearchResults {
     type Item = (SearchIndexScore, DocAddress);
 
@@ -147,6 +197,14 @@ impl Iterator for SearchResults {
                 let (_, doc_id) = iter.next()?;
                 (searcher, ff_lookup, (1.0, doc_id))
             }
+            SearchResults::TopNByDistance(searcher, ff_lookup, iter) => {
+                let (_, doc_id) = iter.next()?;
+                (searcher, ff_lookup, (1.0, doc_id))
+            }
+            SearchResults::TopNByUuid(searcher, ff_lookup, iter) => {
+                let (_, doc_id) = iter.next()?;
+                (searcher, ff_lookup, (1.0, doc_id))
+            }
             SearchResults::SingleSegment(searcher, segment_ord, fftype, iter) => {
                 let (score, doc_address) = iter.next()?;
                 let ctid_ff = fftype.get_or_insert_with(|| {
@@ -215,6 +273,8 @@ impl Iterator for SearchResults {
             SearchResults::TopNByScore(_, _, iter) => iter.size_hint(),
             SearchResults::TopNByTweakedScore(_, _, iter) => iter.size_hint(),
             SearchResults::TopNByField(_, _, iter) => iter.size_hint(),
+            SearchResults::TopNByDistance(_, _, iter) => iter.size_hint(),
+            SearchResults::TopNByUuid(_, _, iter) => iter.size_hint(),
             SearchResults::SingleSegment(_, _, _, iter) => iter.size_hint(),
             SearchResults::AllSegments(_, _, iters) => {
                 let hint = iters
@@ -235,6 +295,8 @@ impl Iterator for SearchResults {
             SearchResults::TopNByScore(_, _, iter) => iter.count(),
             SearchResults::TopNByTweakedScore(_, _, iter) => iter.count(),
             SearchResults::TopNByField(_, _, iter) => iter.count(),
+            SearchResults::TopNByDistance(_, _, iter) => iter.count(),
+            SearchResults::TopNByUuid(_, _, iter) => iter.count(),
             SearchResults::SingleSegment(_, _, _, iter) => iter.count(),
             SearchResults::AllSegments(_, _, iters) => {
                 iters.into_iter().map(|iter| iter.count()).sum()
@@ -363,20 +425,47 @@ impl SearchIndexReader {
         Ok(self.underlying_index.validate_checksum()?)
     }
 
+    /// A [`SnippetGenerator`] for `field_name`, which is either a text field or a path within a
+    /// JSON field, such as `metadata.color`, whose fragments are at most `max_num_chars` long
     pub fn snippet_generator(
         &self,
         field_name: &str,
         query: &SearchQueryInput,
+        max_num_chars: usize,
     ) -> (tantivy::schema::Field, SnippetGenerator) {
-        let field = self
+        let (field, path) = match self
             .schema
             .get_search_field(&SearchFieldName(field_name.into()))
-            .expect("cannot generate snippet, field does not exist");
+        {
+            Some(field) => (field, None),
+            None => {
+                let (field_name, path) = split_field_and_path(field_name);
+                let field = self
+                    .schema
+                    .get_search_field(&SearchFieldName(field_name))
+                    .expect("cannot generate snippet, field does not exist");
+                (field, path)
+            }
+        };
 
         match self.schema.schema.get_field_entry(field.into()).field_type() {
-            FieldType::Str(_) => {
+            FieldType::Str(_) if path.is_none() => {
                 let field:tantivy::schema::Field = field.into();
-                let generator = SnippetGenerator::create(&self.searcher, &self.query(query), field)
+                let mut generator = SnippetGenerator::create(&self.searcher, &self.query(query), field)
+                    .unwrap_or_else(|err| panic!("failed to create snippet generator for field: {field_name}... {err}"));
+                generator.set_max_num_chars(max_num_chars);
+                (field, generator)
+            }
+            FieldType::JsonObject(options) => {
+                let field: tantivy::schema::Field = field.into();
+                let generator = self
+                    .json_snippet_generator(
+                        field,
+                        path.as_deref().unwrap_or_default(),
+                        options.is_expand_dots_enabled(),
+                        query,
+                        max_num_chars,
+                    )
                     .unwrap_or_else(|err| panic!("failed to create snippet generator for field: {field_name}... {err}"));
                 (field, generator)
             }
@@ -384,6 +473,60 @@ impl SearchIndexReader {
         }
     }
 
+    /// [`SnippetGenerator::create`] only highlights the terms of plain text fields, so for a JSON
+    /// field we do the same thing it does with the text terms found at, or below, `path`
+    fn json_snippet_generator(
+        &self,
+        field: tantivy::schema::Field,
+        path: &str,
+        expand_dots: bool,
+        query: &SearchQueryInput,
+        max_num_chars: usize,
+    ) -> tantivy::Result<SnippetGenerator> {
+        let path_term = Term::from_field_json_path(field, path, expand_dots);
+        let path_bytes = path_term.serialized_value_bytes();
+        let path_prefix = &path_bytes[..path_bytes.len() - 1];
+
+        let mut terms = BTreeSet::new();
+        self.query(query).query_terms(&mut |term, _| {
+            if term.field() != field {
+                return;
+            }
+            let bytes = term.serialized_value_bytes();
+            let at_or_below_path = path.is_empty()
+                || (bytes.starts_with(path_prefix)
+                    && matches!(
+                        bytes.get(path_prefix.len()),
+                        Some(&JSON_END_OF_PATH) | Some(&JSON_PATH_SEGMENT_SEP)
+                    ));
+            if at_or_below_path {
+                terms.insert(term.clone());
+            }
+        });
+
+        let mut terms_text = BTreeMap::new();
+        for term in terms {
+            let bytes = term.serialized_value_bytes();
+            let Some(end_of_path) = bytes.iter().position(|b| *b == JSON_END_OF_PATH) else {
+                continue;
+            };
+            let Some(text) = ValueBytes::wrap(&bytes[end_of_path + 1..]).as_str() else {
+                continue;
+            };
+            if let Some(score) = snippet_term_score(&self.searcher, &term)? {
+                terms_text.insert(text.to_string(), score);
+            }
+        }
+
+        let tokenizer = self.searcher.index().tokenizer_for_field(field)?;
+        Ok(SnippetGenerator::new(
+            terms_text,
+            tokenizer,
+            field,
+            max_num_chars,
+        ))
+    }
+
     /// Search the Tantivy index for matching documents.
     ///
     /// The order of returned docs is unspecified.
@@ -397,6 +540,10 @@ impl SearchIndexReader {
         query: &SearchQueryInput,
         _estimated_rows: Option<usize>,
     ) -> SearchResults {
+        if let Some(hits) = self.ranked_hits(query, None) {
+            return self.scored_results(hits, SortDirection::Desc, usize::MAX);
+        }
+
         let iters = self
             .searcher()
             .segment_readers()
@@ -418,6 +565,9 @@ impl SearchIndexReader {
     ///
     /// The order of returned docs is unspecified.
     ///
+    /// If the query collapses its results or finds the `k` nearest neighbors, that only considers
+    /// the segment's own hits.
+    ///
     /// It has no understanding of Postgres MVCC visibility.  It is the caller's responsibility to
     /// handle that, if it's necessary.
     pub fn search_segment(
@@ -426,6 +576,10 @@ impl SearchIndexReader {
         segment_ord: SegmentOrdinal,
         query: &SearchQueryInput,
     ) -> SearchResults {
+        if let Some(hits) = self.ranked_hits(query, Some(segment_ord)) {
+            return self.scored_results(hits, SortDirection::Desc, usize::MAX);
+        }
+
         let weight = self.weight(need_scores, query);
         let segment_reader = self.searcher.segment_reader(segment_ord);
         let iter = scorer_iter::ScorerIter::new(
@@ -451,6 +605,14 @@ impl SearchIndexReader {
         n: usize,
         need_scores: bool,
     ) -> SearchResults {
+        if let Some(hits) = self.ranked_hits(query, None) {
+            assert!(
+                sort_field.is_none(),
+                "collapsed or nearest neighbor results can only be ordered by score"
+            );
+            return self.scored_results(hits, sortdir, n);
+        }
+
         if let Some(sort_field) = sort_field {
             self.top_by_field(query, sort_field, sortdir, n)
         } else {
@@ -463,6 +625,9 @@ impl SearchIndexReader {
     /// The documents are returned in score order.  Most relevant first if `sortdir` is [`SortDirection::Desc`],
     /// or least relevant first if it's [`SortDirection::Asc`].
     ///
+    /// If the query collapses its results or finds the `k` nearest neighbors, that only considers
+    /// the segment's own hits.
+    ///
     /// It has no understanding of Postgres MVCC visibility.  It is the caller's responsibility to
     /// handle that, if it's necessary.
     pub fn search_top_n_in_segment(
@@ -474,6 +639,14 @@ impl SearchIndexReader {
         n: usize,
         need_scores: bool,
     ) -> SearchResults {
+        if let Some(hits) = self.ranked_hits(query, Some(segment_ord)) {
+            assert!(
+                sort_field.is_none(),
+                "collapsed or nearest neighbor results can only be ordered by score"
+            );
+            return self.scored_results(hits, sortdir, n);
+        }
+
         if let Some(sort_field) = sort_field {
             assert!(
                 !need_scores,
@@ -485,6 +658,125 @@ impl SearchIndexReader {
         }
     }
 
+    /// Search the Tantivy index for the "top N" matching documents, ordered by the distance between
+    /// `origin` and their point in the geo field `sort_field`.
+    ///
+    /// The documents are returned closest first if `sortdir` is [`SortDirection::Asc`], or furthest
+    /// first if it's [`SortDirection::Desc`].  Documents without a point sort as the furthest.
+    ///
+    /// It has no understanding of Postgres MVCC visibility.  It is the caller's responsibility to
+    /// handle that, if it's necessary.
+    pub fn search_top_n_by_distance(
+        &self,
+        query: &SearchQueryInput,
+        sort_field: String,
+        origin: GeoPoint,
+        sortdir: SortDirection,
+        n: usize,
+    ) -> SearchResults {
+        assert!(
+            query.collapse().is_none() && query.knn().is_none(),
+            "collapsed or nearest neighbor results can only be ordered by score"
+        );
+        let collector = self.distance_collector(sort_field, origin, sortdir, n);
+        let top_docs = self.collect(query, collector, true);
+        SearchResults::TopNByDistance(
+            self.searcher.clone(),
+            Default::default(),
+            top_docs.into_iter(),
+        )
+    }
+
+    /// Search the Tantivy index for the "top N" matching documents (ordered by the distance to
+    /// `origin`) in a specific segment.
+    ///
+    /// It has no understanding of Postgres MVCC visibility.  It is the caller's responsibility to
+    /// handle that, if it's necessary.
+    pub fn search_top_n_by_distance_in_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        query: &SearchQueryInput,
+        sort_field: String,
+        origin: GeoPoint,
+        sortdir: SortDirection,
+        n: usize,
+    ) -> SearchResults {
+        assert!(
+            query.collapse().is_none() && query.knn().is_none(),
+            "collapsed or nearest neighbor results can only be ordered by score"
+        );
+        let collector = self.distance_collector(sort_field, origin, sortdir, n);
+        let weight = self.weight(true, query);
+        let top_docs = collector
+            .collect_segment(
+                weight.as_ref(),
+                segment_ord,
+                self.searcher.segment_reader(segment_ord),
+            )
+            .expect("should be able to collect top-n in segment");
+        let top_docs = collector
+            .merge_fruits(vec![top_docs])
+            .expect("should be able to merge top-n in segment");
+        SearchResults::TopNByDistance(
+            self.searcher.clone(),
+            Default::default(),
+            top_docs.into_iter(),
+        )
+    }
+
+    fn distance_collector(
+        &self,
+        sort_field: String,
+        origin: GeoPoint,
+        sortdir: SortDirection,
+        n: usize,
+    ) -> impl Collector<Fruit = Vec<(GeoDistanceKey, DocAddress)>> {
+        let search_field = self
+            .schema
+            .get_search_field(&SearchFieldName(sort_field.clone()))
+            .expect("sort field should exist in index schema");
+        if !matches!(search_field.config, SearchFieldConfig::Geo { .. }) {
+            panic!("cannot order by distance to field '{sort_field}', it is not a geo field");
+        }
+
+        TopDocs::with_limit(n).tweak_score(move |segment_reader: &SegmentReader| {
+            let points = segment_reader
+                .fast_fields()
+                .bytes(&sort_field)
+                .expect("geo field should be a bytes fast field");
+            let mut bytes = vec![];
+            move |doc: DocId, _original_score: Score| {
+                let point = points.as_ref().and_then(|points| {
+                    let ord = points.term_ords(doc).next()?;
+                    bytes.clear();
+                    points.ord_to_bytes(ord, &mut bytes).ok()?;
+                    read_geo(&bytes)
+                });
+                GeoDistanceKey {
+                    dir: sortdir,
+                    distance: point.map_or(f64::INFINITY, |point| origin.distance(&point)),
+                }
+            }
+        })
+    }
+
+    fn uuid_collector(
+        sort_field: String,
+        sortdir: SortDirection,
+        n: usize,
+    ) -> impl Collector<Fruit = Vec<(UuidKey, DocAddress)>> {
+        TopDocs::with_limit(n).tweak_score(move |segment_reader: &SegmentReader| {
+            let uuids = segment_reader
+                .fast_fields()
+                .ip_addr(&sort_field)
+                .expect("uuid field should be an ip address fast field");
+            move |doc: DocId, _original_score: Score| UuidKey {
+                dir: sortdir,
+                uuid: uuids.first(doc).map(u128::from),
+            }
+        })
+    }
+
     fn top_by_field(
         &self,
         query: &SearchQueryInput,
@@ -497,6 +789,16 @@ impl SearchIndexReader {
             .get_search_field(&SearchFieldName(sort_field.clone()))
             .expect("sort field should exist in index schema");
 
+        if matches!(sort_field.config, SearchFieldConfig::Uuid { .. }) {
+            let collector = Self::uuid_collector(sort_field.name.0.clone(), sortdir, n);
+            let top_docs = self.collect(query, collector, true);
+            return SearchResults::TopNByUuid(
+                self.searcher.clone(),
+                Default::default(),
+                top_docs.into_iter(),
+            );
+        }
+
         let collector =
             TopDocs::with_limit(n).order_by_u64_field(sort_field.name.0.clone(), sortdir.into());
         let top_docs = self.collect(query, collector, true);
@@ -586,6 +888,26 @@ impl SearchIndexReader {
             .get_search_field(&SearchFieldName(sort_field.clone()))
             .expect("sort field should exist in index schema");
 
+        if matches!(sort_field.config, SearchFieldConfig::Uuid { .. }) {
+            let collector = Self::uuid_collector(sort_field.name.0.clone(), sortdir, n);
+            let weight = self.weight(true, query);
+            let top_docs = collector
+                .collect_segment(
+                    weight.as_ref(),
+                    segment_ord,
+                    self.searcher.segment_reader(segment_ord),
+                )
+                .expect("should be able to collect top-n in segment");
+            let top_docs = collector
+                .merge_fruits(vec![top_docs])
+                .expect("should be able to merge top-n in segment");
+            return SearchResults::TopNByUuid(
+                self.searcher.clone(),
+                Default::default(),
+                top_docs.into_iter(),
+            );
+        }
+
         let collector =
             TopDocs::with_limit(n).order_by_u64_field(sort_field.name.0.clone(), sortdir.into());
         let query = self.query(query);
@@ -699,6 +1021,71 @@ impl SearchIndexReader {
         }
     }
 
+    /// The hits of a query whose results depend on every segment's hits, highest scoring first,
+    /// or [`None`] if they don't.  Those are the ones that survive the query's collapse, or the
+    /// `k` nearest of a nearest neighbor query, from every segment or only from `segment_ord`.
+    fn ranked_hits(
+        &self,
+        query: &SearchQueryInput,
+        segment_ord: Option<SegmentOrdinal>,
+    ) -> Option<Vec<(Score, DocAddress)>> {
+        if let Some((field, max_per_group)) = query.collapse() {
+            let collector =
+                CollapseCollector::new(field.to_string(), max_per_group, self.heap_relation_oid());
+            Some(self.collect_in(query, collector, segment_ord))
+        } else {
+            match query.knn()? {
+                0 => Some(vec![]),
+                k => Some(self.collect_in(query, TopDocs::with_limit(k), segment_ord)),
+            }
+        }
+    }
+
+    /// Collect the hits of `query` with `collector`, from every segment or only from `segment_ord`
+    fn collect_in<C: Collector + 'static>(
+        &self,
+        query: &SearchQueryInput,
+        collector: C,
+        segment_ord: Option<SegmentOrdinal>,
+    ) -> C::Fruit {
+        let Some(segment_ord) = segment_ord else {
+            return self.collect(query, collector, true);
+        };
+
+        let weight = self.weight(true, query);
+        let fruit = collector
+            .collect_segment(
+                weight.as_ref(),
+                segment_ord,
+                self.searcher.segment_reader(segment_ord),
+            )
+            .expect("should be able to collect the segment");
+        collector
+            .merge_fruits(vec![fruit])
+            .expect("should be able to merge the segment")
+    }
+
+    /// The first `n` of `hits`, which are highest scoring first, in `sortdir` order
+    fn scored_results(
+        &self,
+        mut hits: Vec<(Score, DocAddress)>,
+        sortdir: SortDirection,
+        n: usize,
+    ) -> SearchResults {
+        if sortdir == SortDirection::Asc {
+            hits.reverse();
+        }
+        hits.truncate(n);
+        SearchResults::TopNByScore(self.searcher.clone(), Default::default(), hits.into_iter())
+    }
+
+    fn heap_relation_oid(&self) -> pg_sys::Oid {
+        unsafe { PgRelation::with_lock(self.index_oid, pg_sys::AccessShareLock as _) }
+            .heap_relation()
+            .expect("index should belong to a heap relation")
+            .oid()
+    }
+
     pub fn estimate_docs(&self, search_query_input: &SearchQueryInput) -> Option<usize> {
         let largest_reader = self
             .searcher
@@ -751,6 +1138,14 @@ impl SearchIndexReader {
     }
 }
 
+/// How much a match of `term` is worth in a snippet, scored the way [`SnippetGenerator::create`]
+/// scores the terms of a plain text field, so rarer terms are preferred.  `None` if no document
+/// has the term.
+fn snippet_term_score(searcher: &Searcher, term: &Term) -> tantivy::Result<Option<Score>> {
+    let doc_freq = searcher.doc_freq(term)?;
+    Ok((doc_freq > 0).then(|| 1.0 / (1.0 + doc_freq as Score)))
+}
+
 mod scorer_iter {
     use tantivy::query::{Scorer, Weight};
     use tantivy::{DocAddress, DocId, DocSet, Score, SegmentOrdinal, SegmentReader};
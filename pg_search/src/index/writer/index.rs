@@ -202,4 +202,13 @@ pub enum IndexError {
 
     #[error("key_field column '{0}' cannot be NULL")]
     KeyIdNull(String),
+
+    #[error("vector field '{0}' must be an array of {1} numbers")]
+    InvalidVector(String, usize),
+
+    #[error("sparse vector field '{0}' must be an object of weights between 0 and 100")]
+    InvalidSparseVector(String),
+
+    #[error("geo field '{0}' must be a point or a json object of a 'lat' and 'lon' in range")]
+    InvalidGeoPoint(String),
 }
@@ -17,6 +17,7 @@
 
 use crate::api::Cardinality;
 use crate::postgres::customscan::CustomScan;
+use crate::schema::geo::GeoPoint;
 use pgrx::{pg_sys, PgList};
 use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter};
@@ -81,6 +82,8 @@ impl From<SortDirection> for u32 {
 pub enum OrderByStyle {
     Score(*mut pg_sys::PathKey),
     Field(*mut pg_sys::PathKey, String),
+    /// By the distance between the geo field's point and the origin
+    GeoDistance(*mut pg_sys::PathKey, String, GeoPoint),
 }
 
 impl OrderByStyle {
@@ -88,6 +91,7 @@ impl OrderByStyle {
         match self {
             OrderByStyle::Score(pathkey) => *pathkey,
             OrderByStyle::Field(pathkey, _) => *pathkey,
+            OrderByStyle::GeoDistance(pathkey, _, _) => *pathkey,
         }
     }
 
@@ -35,9 +35,11 @@ use crate::postgres::customscan::pdbscan::projections::score::{score_funcoid, us
 use crate::postgres::customscan::pdbscan::scan_state::PdbScanState;
 use crate::postgres::customscan::pdbscan::PdbScan;
 use crate::postgres::customscan::CustomScanState;
+use crate::postgres::types::TantivyValue;
 use crate::schema::SearchIndexSchema;
 use itertools::Itertools;
 use pgrx::{pg_sys, IntoDatum, PgList, PgOid, PgRelation, PgTupleDesc};
+use tantivy::schema::OwnedValue;
 use tantivy::DocAddress;
 
 pub struct FastFieldExecState {
@@ -117,6 +119,14 @@ unsafe fn ff_to_datum(
         WhichFastField::Named(_, FastFieldType::String)
     ) {
         strbuf.as_str().into_datum()
+    } else if let WhichFastField::Named(_, FastFieldType::Decimal(scale)) = which_fast_field {
+        match ff_helper.i64(field_index, doc_address) {
+            None => None,
+            Some(v) => TantivyValue(OwnedValue::I64(v))
+                .try_into_decimal(*scale)
+                .expect("value should be a valid decimal")
+                .into_datum(),
+        }
     } else if typid == pg_sys::TEXTOID || typid == pg_sys::VARCHAROID {
         // NB:  we don't actually support text-based fast fields... yet
         // but if we did, we'd want to do it this way
@@ -204,10 +214,15 @@ pub unsafe fn pullup_fast_fields(
                         .get((attno - 1) as usize)
                         .expect("attnum should exist in tupdesc");
                     if schema.is_fast_field(att.name()) {
+                        let decimal_scale = schema
+                            .get_search_field(&att.name().into())
+                            .and_then(|search_field| search_field.config.decimal_scale());
                         let ff_type = if (*var).vartype == pg_sys::TEXTOID
                             || (*var).vartype == pg_sys::VARCHAROID
                         {
                             FastFieldType::String
+                        } else if let Some(scale) = decimal_scale {
+                            FastFieldType::Decimal(scale)
                         } else {
                             FastFieldType::Numeric
                         };
@@ -21,6 +21,7 @@ use crate::postgres::customscan::pdbscan::exec_methods::{ExecMethod, ExecState};
 use crate::postgres::customscan::pdbscan::parallel::checkout_segment;
 use crate::postgres::customscan::pdbscan::scan_state::PdbScanState;
 use crate::query::SearchQueryInput;
+use crate::schema::geo::GeoPoint;
 use pgrx::{direct_function_call, pg_sys, IntoDatum};
 use tantivy::SegmentOrdinal;
 
@@ -40,6 +41,7 @@ pub struct TopNScanExecState {
     search_query_input: Option<SearchQueryInput>,
     search_reader: Option<SearchIndexReader>,
     sort_field: Option<String>,
+    sort_geo_origin: Option<GeoPoint>,
     search_results: SearchResults,
     did_query: bool,
 
@@ -82,14 +84,25 @@ impl TopNScanExecState {
                 self.current_segment = segment_ord;
 
                 let search_reader = state.search_reader.as_ref().unwrap();
-                search_reader.search_top_n_in_segment(
-                    segment_ord,
-                    self.search_query_input.as_ref().unwrap(),
-                    self.sort_field.clone(),
-                    self.sort_direction.into(),
-                    self.limit,
-                    self.need_scores,
-                )
+                match (&self.sort_field, self.sort_geo_origin) {
+                    (Some(sort_field), Some(origin)) => search_reader
+                        .search_top_n_by_distance_in_segment(
+                            segment_ord,
+                            self.search_query_input.as_ref().unwrap(),
+                            sort_field.clone(),
+                            origin,
+                            self.sort_direction.into(),
+                            self.limit,
+                        ),
+                    _ => search_reader.search_top_n_in_segment(
+                        segment_ord,
+                        self.search_query_input.as_ref().unwrap(),
+                        self.sort_field.clone(),
+                        self.sort_direction.into(),
+                        self.limit,
+                        self.need_scores,
+                    ),
+                }
             } else {
                 // no more segments to query
                 SearchResults::None
@@ -100,13 +113,22 @@ impl TopNScanExecState {
         } else {
             // not parallel, first time query
             let search_reader = state.search_reader.as_ref().unwrap();
-            search_reader.search_top_n(
-                self.search_query_input.as_ref().unwrap(),
-                self.sort_field.clone(),
-                self.sort_direction.into(),
-                self.limit,
-                self.need_scores,
-            )
+            match (&self.sort_field, self.sort_geo_origin) {
+                (Some(sort_field), Some(origin)) => search_reader.search_top_n_by_distance(
+                    self.search_query_input.as_ref().unwrap(),
+                    sort_field.clone(),
+                    origin,
+                    self.sort_direction.into(),
+                    self.limit,
+                ),
+                _ => search_reader.search_top_n(
+                    self.search_query_input.as_ref().unwrap(),
+                    self.sort_field.clone(),
+                    self.sort_direction.into(),
+                    self.limit,
+                    self.need_scores,
+                ),
+            }
         }
     }
 
@@ -124,6 +146,7 @@ impl ExecMethod for TopNScanExecState {
 
         self.search_query_input = Some(state.search_query_input.clone());
         self.sort_field = sort_field;
+        self.sort_geo_origin = state.sort_geo_origin;
         self.search_reader = state.search_reader.clone();
     }
 
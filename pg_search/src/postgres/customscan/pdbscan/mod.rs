@@ -23,6 +23,7 @@ mod projections;
 mod qual_inspect;
 mod scan_state;
 
+use crate::api::geo::distance_funcoids;
 use crate::api::operator::{
     anyelement_query_input_opoid, attname_from_var, estimate_selectivity, find_var_relation,
 };
@@ -44,11 +45,15 @@ use crate::postgres::customscan::pdbscan::exec_methods::fast_fields::{
     estimate_cardinality, is_string_agg_capable_ex,
 };
 use crate::postgres::customscan::pdbscan::privdat::PrivateData;
+use crate::postgres::customscan::pdbscan::projections::matched_queries::{
+    matched_queries_funcoid, uses_matched_queries, MatchedQueries,
+};
 use crate::postgres::customscan::pdbscan::projections::score::{
     is_score_func, score_funcoid, uses_scores,
 };
 use crate::postgres::customscan::pdbscan::projections::snippet::{
-    snippet_funcoid, uses_snippets, SnippetInfo,
+    snippet_funcoid, snippet_positions_funcoid, snippets_funcoid, uses_snippets, SnippetInfo,
+    SnippetType,
 };
 use crate::postgres::customscan::pdbscan::projections::{
     inject_placeholders, maybe_needs_const_projections, pullout_funcexprs,
@@ -59,12 +64,16 @@ use crate::postgres::customscan::{CustomScan, CustomScanState, ExecMethod};
 use crate::postgres::rel_get_bm25_index;
 use crate::postgres::visibility_checker::VisibilityChecker;
 use crate::query::SearchQueryInput;
+use crate::schema::geo::GeoPoint;
 use crate::schema::SearchIndexSchema;
 use crate::{nodecast, DEFAULT_STARTUP_COST, UNKNOWN_SELECTIVITY};
 use exec_methods::top_n::TopNScanExecState;
 use exec_methods::ExecState;
 use pgrx::pg_sys::{AsPgCStr, CustomExecMethods};
-use pgrx::{direct_function_call, pg_sys, IntoDatum, PgList, PgMemoryContexts, PgRelation};
+use pgrx::{
+    direct_function_call, pg_sys, FromDatum, IntoDatum, PgHeapTuple, PgList, PgMemoryContexts,
+    PgRelation,
+};
 use std::collections::HashMap;
 use std::ffi::CStr;
 use std::ptr::addr_of_mut;
@@ -157,6 +166,8 @@ impl CustomScan for PdbScan {
                 rti,
                 restrict_info.as_ptr().cast(),
                 anyelement_query_input_opoid(),
+                &table,
+                &schema,
             ) {
                 let selectivity = if let Some(limit) = limit {
                     // use the limit
@@ -174,6 +185,17 @@ impl CustomScan for PdbScan {
                     estimate_selectivity(&bm25_index, &search_config).unwrap_or(UNKNOWN_SELECTIVITY)
                 };
 
+                // postgres can't evaluate `paradedb.score()` comparisons itself, so if we've
+                // taken them over, we have to be the ones that run this query
+                let has_score_quals = quals.contains_score_expression();
+
+                // collapsing, and finding the k nearest neighbors, need every segment's hits,
+                // scored, so neither a parallel worker, which only sees some of the segments, nor a
+                // StringAgg can do them
+                let search_query_input = SearchQueryInput::from(&quals);
+                let ranks_all_segments =
+                    search_query_input.collapse().is_some() || search_query_input.knn().is_some();
+
                 builder.custom_private().set_heaprelid(table.oid());
                 builder.custom_private().set_indexrelid(bm25_index.oid());
                 builder.custom_private().set_range_table_index(rti);
@@ -181,16 +203,21 @@ impl CustomScan for PdbScan {
                 builder.custom_private().set_limit(limit);
 
                 // we must use this path if we need to do const projections for scores or snippets
-                builder = builder.set_force_path(maybe_needs_const_projections || is_topn);
+                builder = builder
+                    .set_force_path(maybe_needs_const_projections || is_topn || has_score_quals);
 
                 if is_topn {
-                    // sorting by a field only works if we're not doing const projections
-                    // the reason for this is that tantivy can't do both scoring and ordering by
-                    // a fast field at the same time.
+                    // sorting by a field, or by the distance to a geo field's point, only works if
+                    // we're not doing const projections.  the reason for this is that tantivy can't
+                    // do both scoring and ordering by a fast field at the same time.
                     //
-                    // and sorting by score always works
-                    if !(maybe_needs_const_projections
-                        && matches!(&pathkey, Some(OrderByStyle::Field(..))))
+                    // and sorting by score always works.  collapsed and nearest neighbor results can
+                    // only be sorted by score, as they're the highest scoring hits
+                    if !((maybe_needs_const_projections || ranks_all_segments)
+                        && matches!(
+                            &pathkey,
+                            Some(OrderByStyle::Field(..) | OrderByStyle::GeoDistance(..))
+                        ))
                     {
                         builder.custom_private().set_sort_info(&pathkey);
                     }
@@ -243,8 +270,12 @@ impl CustomScan for PdbScan {
                 builder = builder.set_total_cost(total_cost);
                 builder = builder.set_flag(Flags::Projection);
 
-                if pathkey.is_some()
-                    && !is_topn
+                // a StringAgg can only emit results sorted by a field's value, not by distance
+                if matches!(
+                    &pathkey,
+                    Some(OrderByStyle::Score(_) | OrderByStyle::Field(..))
+                ) && !is_topn
+                    && !ranks_all_segments
                     && is_string_agg_capable_ex(
                         builder.custom_private().limit(),
                         &which_fast_fields,
@@ -277,7 +308,7 @@ impl CustomScan for PdbScan {
                         builder = builder.add_path_key(&pathkey);
                         builder.custom_private().set_sort_info(&pathkey);
                     }
-                } else {
+                } else if !ranks_all_segments {
                     let sortdir = builder.custom_private().sort_direction();
                     builder = builder.set_parallel(
                         is_topn,
@@ -312,9 +343,21 @@ impl CustomScan for PdbScan {
             let mut attname_lookup = PgList::<pg_sys::Node>::new();
             let score_funcoid = score_funcoid();
             let snippet_funcoid = snippet_funcoid();
+            let snippets_funcoid = snippets_funcoid();
+            let snippet_positions_funcoid = snippet_positions_funcoid();
+            let matched_queries_funcoid = matched_queries_funcoid();
             for te in processed_tlist.iter_ptr() {
-                let func_vars_at_level =
-                    pullout_funcexprs(te.cast(), &[score_funcoid, snippet_funcoid], rti);
+                let func_vars_at_level = pullout_funcexprs(
+                    te.cast(),
+                    &[
+                        score_funcoid,
+                        snippet_funcoid,
+                        snippets_funcoid,
+                        snippet_positions_funcoid,
+                        matched_queries_funcoid,
+                    ],
+                    rti,
+                );
 
                 for (funcexpr, var) in func_vars_at_level {
                     // if we have a tlist, then we need to add the specific function that uses
@@ -392,6 +435,7 @@ impl CustomScan for PdbScan {
             builder.custom_state().limit = builder.custom_private().limit();
             builder.custom_state().sort_field = builder.custom_private().sort_field();
             builder.custom_state().sort_direction = builder.custom_private().sort_direction();
+            builder.custom_state().sort_geo_origin = builder.custom_private().sort_geo_origin();
 
             // store our query quals into our custom state too
             let quals = builder
@@ -447,9 +491,15 @@ impl CustomScan for PdbScan {
 
             let score_funcoid = score_funcoid();
             let snippet_funcoid = snippet_funcoid();
+            let snippets_funcoid = snippets_funcoid();
+            let snippet_positions_funcoid = snippet_positions_funcoid();
+            let matched_queries_funcoid = matched_queries_funcoid();
 
             builder.custom_state().score_funcoid = score_funcoid;
             builder.custom_state().snippet_funcoid = snippet_funcoid;
+            builder.custom_state().snippets_funcoid = snippets_funcoid;
+            builder.custom_state().snippet_positions_funcoid = snippet_positions_funcoid;
+            builder.custom_state().matched_queries_funcoid = matched_queries_funcoid;
 
             builder.custom_state().need_scores = uses_scores(
                 builder.target_list().as_ptr().cast(),
@@ -457,14 +507,26 @@ impl CustomScan for PdbScan {
                 (*builder.args().cscan).scan.scanrelid as pg_sys::Index,
             );
 
+            builder.custom_state().need_matched_queries = uses_matched_queries(
+                builder.target_list().as_ptr().cast(),
+                matched_queries_funcoid,
+                (*builder.args().cscan).scan.scanrelid as pg_sys::Index,
+            );
+
             let node = builder.target_list().as_ptr().cast();
             let rti = builder.custom_state().rti;
             let attname_lookup = &builder.custom_state().var_attname_lookup;
-            builder.custom_state().snippet_generators =
-                uses_snippets(rti, attname_lookup, node, snippet_funcoid)
-                    .into_iter()
-                    .map(|field| (field, None))
-                    .collect();
+            builder.custom_state().snippet_generators = uses_snippets(
+                rti,
+                attname_lookup,
+                node,
+                snippet_funcoid,
+                snippets_funcoid,
+                snippet_positions_funcoid,
+            )
+            .into_iter()
+            .map(|field| (field, None))
+            .collect();
 
             let need_snippets = builder.custom_state().need_snippets();
             let need_scores = builder.custom_state().need_scores();
@@ -547,6 +609,9 @@ impl CustomScan for PdbScan {
             if !matches!(sort_direction, SortDirection::None) {
                 if let Some(sort_field) = &state.custom_state().sort_field {
                     explainer.add_text("   Sort Field", sort_field);
+                    if let Some(origin) = &state.custom_state().sort_geo_origin {
+                        explainer.add_text("   Sort Distance From", origin.to_string());
+                    }
                 } else {
                     explainer.add_text("   Sort Field", "paradedb.score()");
                 }
@@ -670,7 +735,7 @@ impl CustomScan for PdbScan {
                 .drain()
                 .collect();
             for (snippet_info, generator) in &mut snippet_generators {
-                let mut new_generator = state
+                let new_generator = state
                     .custom_state()
                     .search_reader
                     .as_ref()
@@ -678,16 +743,22 @@ impl CustomScan for PdbScan {
                     .snippet_generator(
                         &snippet_info.field,
                         &state.custom_state().search_query_input,
+                        snippet_info.max_num_chars,
                     );
-                new_generator
-                    .1
-                    .set_max_num_chars(snippet_info.max_num_chars);
                 *generator = Some(new_generator);
             }
 
             state.custom_state_mut().snippet_generators = snippet_generators;
         }
 
+        if state.custom_state().need_matched_queries() {
+            state.custom_state_mut().matched_queries = MatchedQueries::new(
+                state.custom_state().search_reader.as_ref().unwrap(),
+                &state.custom_state().search_query_input,
+            )
+            .expect("should be able to build the named queries");
+        }
+
         unsafe {
             inject_score_and_snippet_placeholders(state);
         }
@@ -728,9 +799,10 @@ impl CustomScan for PdbScan {
 
                         if !state.custom_state().need_scores()
                             && !state.custom_state().need_snippets()
+                            && !state.custom_state().need_matched_queries()
                         {
                             //
-                            // we don't need scores or snippets
+                            // we don't need scores, snippets, or matched queries
                             // do the projection and return
                             //
 
@@ -763,11 +835,36 @@ impl CustomScan for PdbScan {
                                     for (snippet_info, const_snippet_node) in
                                         &state.custom_state().const_snippet_nodes
                                     {
-                                        if let Some(snippet) =
-                                            state.custom_state().make_snippet(ctid, snippet_info)
-                                        {
-                                            (**const_snippet_node).constvalue =
-                                                snippet.into_datum().unwrap();
+                                        let custom_state = state.custom_state();
+                                        let snippet = match snippet_info.snippet_type {
+                                            SnippetType::Text => custom_state
+                                                .make_snippet(ctid, snippet_info)
+                                                .into_datum(),
+                                            SnippetType::Fragments(num_fragments) => custom_state
+                                                .make_snippets(ctid, snippet_info, num_fragments)
+                                                .into_datum(),
+                                            SnippetType::Positions => custom_state
+                                                .make_snippet_positions(ctid, snippet_info)
+                                                .map(|(element, positions)| {
+                                                    let mut tuple =
+                                                        PgHeapTuple::new_composite_type(
+                                                            "paradedb.snippet_positions",
+                                                        )
+                                                        .expect(
+                                                            "snippet_positions type should exist",
+                                                        );
+                                                    tuple
+                                                        .set_by_name("element", element)
+                                                        .expect("element should be text");
+                                                    tuple
+                                                        .set_by_name("positions", positions)
+                                                        .expect("positions should be int4range[]");
+                                                    tuple
+                                                })
+                                                .into_datum(),
+                                        };
+                                        if let Some(snippet) = snippet {
+                                            (**const_snippet_node).constvalue = snippet;
                                             (**const_snippet_node).constisnull = false;
                                         } else {
                                             (**const_snippet_node).constvalue =
@@ -778,6 +875,26 @@ impl CustomScan for PdbScan {
                                 });
                             }
 
+                            if state.custom_state().need_matched_queries() {
+                                let const_matched_queries_node = state
+                                    .custom_state()
+                                    .const_matched_queries_node
+                                    .expect("const_matched_queries_node should be set");
+                                let custom_state = state.custom_state_mut();
+                                let names = custom_state
+                                    .matched_queries
+                                    .matched(
+                                        custom_state.search_reader.as_ref().unwrap(),
+                                        doc_address,
+                                    )
+                                    .expect("should be able to match the named queries");
+                                per_tuple_context.switch_to(|_| {
+                                    (*const_matched_queries_node).constvalue =
+                                        names.into_datum().unwrap();
+                                    (*const_matched_queries_node).constisnull = false;
+                                });
+                            }
+
                             // finally, do the projection
                             return per_tuple_context.switch_to(|_| {
                                 let planstate = state.planstate();
@@ -817,6 +934,9 @@ impl CustomScan for PdbScan {
             &mut state.custom_state_mut().snippet_generators,
         ));
         drop(std::mem::take(&mut state.custom_state_mut().search_results));
+        drop(std::mem::take(
+            &mut state.custom_state_mut().matched_queries,
+        ));
 
         if let Some(heaprel) = state.custom_state_mut().heaprel.take() {
             unsafe {
@@ -846,8 +966,11 @@ fn check_visibility(
 }
 
 unsafe fn inject_score_and_snippet_placeholders(state: &mut CustomScanStateWrapper<PdbScan>) {
-    if !state.custom_state().need_scores() && !state.custom_state().need_snippets() {
-        // scores/snippets aren't necessary so we use whatever we originally setup as our ProjectionInfo
+    if !state.custom_state().need_scores()
+        && !state.custom_state().need_snippets()
+        && !state.custom_state().need_matched_queries()
+    {
+        // scores/snippets/matched queries aren't necessary so we use whatever we originally setup as our ProjectionInfo
         return;
     }
 
@@ -856,18 +979,23 @@ unsafe fn inject_score_and_snippet_placeholders(state: &mut CustomScanStateWrapp
     // forced projection we must do later.
 
     let planstate = state.planstate();
-    let (targetlist, const_score_node, const_snippet_nodes) = inject_placeholders(
-        (*(*planstate).plan).targetlist,
-        state.custom_state().rti,
-        state.custom_state().score_funcoid,
-        state.custom_state().snippet_funcoid,
-        &state.custom_state().var_attname_lookup,
-        &state.custom_state().snippet_generators,
-    );
+    let (targetlist, const_score_node, const_snippet_nodes, const_matched_queries_node) =
+        inject_placeholders(
+            (*(*planstate).plan).targetlist,
+            state.custom_state().rti,
+            state.custom_state().score_funcoid,
+            state.custom_state().snippet_funcoid,
+            state.custom_state().snippets_funcoid,
+            state.custom_state().snippet_positions_funcoid,
+            state.custom_state().matched_queries_funcoid,
+            &state.custom_state().var_attname_lookup,
+            &state.custom_state().snippet_generators,
+        );
 
     state.custom_state_mut().placeholder_targetlist = Some(targetlist);
     state.custom_state_mut().const_score_node = Some(const_score_node);
     state.custom_state_mut().const_snippet_nodes = const_snippet_nodes;
+    state.custom_state_mut().const_matched_queries_node = Some(const_matched_queries_node);
 }
 
 unsafe fn pullup_orderby_pathkey<P: Into<*mut pg_sys::List> + Default>(
@@ -896,6 +1024,22 @@ unsafe fn pullup_orderby_pathkey<P: Into<*mut pg_sys::List> + Default>(
                         return Some(OrderByStyle::Field(first_pathkey, att.name().to_string()));
                     }
                 }
+            } else if let Some((var, origin)) = is_distance_func(expr.cast(), rti as _) {
+                let (heaprelid, attno, _) = find_var_relation(var, root);
+                if heaprelid == pg_sys::Oid::INVALID {
+                    return None;
+                }
+                let heaprel = PgRelation::with_lock(heaprelid, pg_sys::AccessShareLock as _);
+                let tupdesc = heaprel.tuple_desc();
+                if let Some(att) = tupdesc.get(attno as usize - 1) {
+                    if let Some(geo_field) = schema.geo_field(att.name()) {
+                        return Some(OrderByStyle::GeoDistance(
+                            first_pathkey,
+                            geo_field.name.0.clone(),
+                            origin,
+                        ));
+                    }
+                }
             } else if let Some(relabel) = nodecast!(RelabelType, T_RelabelType, expr) {
                 if let Some(var) = nodecast!(Var, T_Var, (*relabel).arg) {
                     let (heaprelid, attno, _) = find_var_relation(var, root);
@@ -954,6 +1098,32 @@ unsafe fn is_lower_func(node: *mut pg_sys::Node, rti: i32) -> Option<*mut pg_sys
     None
 }
 
+/// If `node` is a call to `paradedb.distance()` on a column of the relation `rti`, from a
+/// constant origin, returns the column's [`pg_sys::Var`] and the origin
+unsafe fn is_distance_func(
+    node: *mut pg_sys::Node,
+    rti: i32,
+) -> Option<(*mut pg_sys::Var, GeoPoint)> {
+    let funcexpr = nodecast!(FuncExpr, T_FuncExpr, node)?;
+    if !distance_funcoids().contains(&(*funcexpr).funcid) {
+        return None;
+    }
+
+    let args = PgList::<pg_sys::Node>::from_pg((*funcexpr).args);
+    let var = nodecast!(Var, T_Var, args.get_ptr(0)?)?;
+    if (*var).varno as i32 != rti {
+        return None;
+    }
+
+    let const_f64 = |node: *mut pg_sys::Node| {
+        let const_ = nodecast!(Const, T_Const, node)?;
+        f64::from_datum((*const_).constvalue, (*const_).constisnull)
+    };
+    let lat = const_f64(args.get_ptr(1)?)?;
+    let lon = const_f64(args.get_ptr(2)?)?;
+    Some((var, GeoPoint::new(lat, lon)?))
+}
+
 pub fn text_lower_funcoid() -> pg_sys::Oid {
     unsafe {
         direct_function_call::<pg_sys::Oid>(
@@ -19,6 +19,7 @@ use crate::api::Cardinality;
 use crate::postgres::customscan::builders::custom_path::OrderByStyle;
 use crate::postgres::customscan::builders::custom_path::SortDirection;
 use crate::postgres::customscan::pdbscan::qual_inspect::Qual;
+use crate::schema::geo::GeoPoint;
 use pgrx::{pg_sys, PgList};
 
 #[derive(Default, Debug)]
@@ -30,6 +31,7 @@ pub struct PrivateData {
     limit: Option<usize>,
     sort_field: Option<String>,
     sort_direction: Option<SortDirection>,
+    sort_geo_origin: Option<GeoPoint>,
     var_attname_lookup: Option<*mut pg_sys::List>,
     maybe_ff: bool,
     segment_count: usize,
@@ -82,6 +84,10 @@ impl PrivateData {
             match style {
                 OrderByStyle::Score(_) => {}
                 OrderByStyle::Field(_, name) => self.sort_field = Some(name.clone()),
+                OrderByStyle::GeoDistance(_, name, origin) => {
+                    self.sort_field = Some(name.clone());
+                    self.sort_geo_origin = Some(*origin);
+                }
             }
             self.sort_direction = Some(style.direction())
         }
@@ -134,6 +140,10 @@ impl PrivateData {
         self.sort_direction
     }
 
+    pub fn sort_geo_origin(&self) -> Option<GeoPoint> {
+        self.sort_geo_origin
+    }
+
     pub fn var_attname_lookup(&self) -> Option<PgList<pg_sys::Node>> {
         self.var_attname_lookup
             .map(|list| unsafe { PgList::from_pg(list) })
@@ -273,6 +283,7 @@ pub mod serialize {
         ));
         ser.push(makeBoolean(Some(privdat.maybe_ff)));
         ser.push(makeString(Some(privdat.segment_count)));
+        ser.push(makeString(privdat.sort_geo_origin));
         ser
     }
 }
@@ -322,6 +333,7 @@ pub mod deserialize {
                 .and_then(|n| decodeBoolean(n))
                 .unwrap_or_default(),
             segment_count: input.get_ptr(9).and_then(|n| decodeString(n)).unwrap_or(0),
+            sort_geo_origin: input.get_ptr(10).and_then(|n| decodeString(n)),
         }
     }
 }
@@ -0,0 +1,123 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::index::reader::index::SearchIndexReader;
+use crate::postgres::customscan::pdbscan::projections::score::uses_scores;
+use crate::query::SearchQueryInput;
+use pgrx::{direct_function_call, extension_sql, pg_extern, pg_sys, AnyElement, IntoDatum};
+use tantivy::query::{EnableScoring, Scorer, Weight};
+use tantivy::{DocAddress, DocSet, SegmentOrdinal};
+
+#[pg_extern(name = "matched_queries", stable, parallel_safe, cost = 1)]
+fn matched_queries_from_relation(_relation_reference: AnyElement) -> Option<Vec<String>> {
+    None
+}
+
+extension_sql!(
+    r#"
+ALTER FUNCTION matched_queries SUPPORT placeholder_support;
+"#,
+    name = "matched_queries_placeholder",
+    requires = [matched_queries_from_relation, placeholder_support]
+);
+
+pub fn matched_queries_funcoid() -> pg_sys::Oid {
+    unsafe {
+        direct_function_call::<pg_sys::Oid>(
+            pg_sys::regprocedurein,
+            &[c"paradedb.matched_queries(anyelement)".into_datum()],
+        )
+        .expect("the `paradedb.matched_queries(anyelement)` function should exist")
+    }
+}
+
+/// Does the given node reference a call to `paradedb.matched_queries()` for the relation at `rti`?
+pub unsafe fn uses_matched_queries(
+    node: *mut pg_sys::Node,
+    matched_queries_funcoid: pg_sys::Oid,
+    rti: pg_sys::Index,
+) -> bool {
+    uses_scores(node, matched_queries_funcoid, rti)
+}
+
+/// Answers which of the queries named with [`SearchQueryInput::Named`] match a document.
+///
+/// Each named query keeps a scorer over the segment it was last asked about, which is sought
+/// forward to each document it's asked about.  As the scan doesn't necessarily return documents
+/// in order, the scorer is started over whenever it's asked about a document it's already past.
+#[derive(Default)]
+pub struct MatchedQueries {
+    named_weights: Vec<(String, Box<dyn Weight>)>,
+    scorers: Vec<Option<(SegmentOrdinal, Box<dyn Scorer>)>>,
+}
+
+impl MatchedQueries {
+    pub fn new(
+        search_reader: &SearchIndexReader,
+        query: &SearchQueryInput,
+    ) -> tantivy::Result<Self> {
+        let named_weights =
+            query
+                .named_queries()
+                .into_iter()
+                .map(|(name, query)| {
+                    let weight = search_reader.query(&query).weight(
+                        EnableScoring::disabled_from_searcher(search_reader.searcher()),
+                    )?;
+                    Ok((name, weight))
+                })
+                .collect::<tantivy::Result<Vec<_>>>()?;
+
+        Ok(Self {
+            scorers: named_weights.iter().map(|_| None).collect(),
+            named_weights,
+        })
+    }
+
+    /// The names of the queries that match `doc_address`, in the order they appear in the query
+    pub fn matched(
+        &mut self,
+        search_reader: &SearchIndexReader,
+        doc_address: DocAddress,
+    ) -> tantivy::Result<Vec<String>> {
+        let DocAddress {
+            segment_ord,
+            doc_id,
+        } = doc_address;
+
+        let mut names = vec![];
+        for ((name, weight), scorer) in self.named_weights.iter().zip(&mut self.scorers) {
+            let scorer = match scorer {
+                Some((ord, scorer)) if *ord == segment_ord && scorer.doc() <= doc_id => scorer,
+                _ => {
+                    let segment_reader = search_reader.searcher().segment_reader(segment_ord);
+                    &mut scorer
+                        .insert((segment_ord, weight.scorer(segment_reader, 1.0)?))
+                        .1
+                }
+            };
+            if scorer.doc() < doc_id {
+                scorer.seek(doc_id);
+            }
+
+            if scorer.doc() == doc_id && !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+        Ok(names)
+    }
+}
@@ -15,13 +15,18 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+pub mod matched_queries;
 pub mod score;
 pub mod snippet;
 
 use crate::api::operator::{find_vars, ReturnedNodePointer};
 use crate::nodecast;
+use crate::postgres::customscan::pdbscan::projections::matched_queries::matched_queries_funcoid;
 use crate::postgres::customscan::pdbscan::projections::score::score_funcoid;
-use crate::postgres::customscan::pdbscan::projections::snippet::{snippet_funcoid, SnippetInfo};
+use crate::postgres::customscan::pdbscan::projections::snippet::{
+    snippet_field_name, snippet_field_var, snippet_funcoid, snippet_positions_funcoid,
+    snippets_funcoid, SnippetInfo, SnippetType,
+};
 use pgrx::pg_sys::expression_tree_walker;
 use pgrx::{pg_extern, pg_guard, pg_sys, Internal, PgList};
 use std::collections::HashMap;
@@ -91,6 +96,9 @@ pub unsafe fn maybe_needs_const_projections(node: *mut pg_sys::Node) -> bool {
             let data = &*data.cast::<Data>();
             if (*funcexpr).funcid == data.score_funcoid
                 || (*funcexpr).funcid == data.snipped_funcoid
+                || (*funcexpr).funcid == data.snippets_funcoid
+                || (*funcexpr).funcid == data.snippet_positions_funcoid
+                || (*funcexpr).funcid == data.matched_queries_funcoid
             {
                 return true;
             }
@@ -102,11 +110,17 @@ pub unsafe fn maybe_needs_const_projections(node: *mut pg_sys::Node) -> bool {
     struct Data {
         score_funcoid: pg_sys::Oid,
         snipped_funcoid: pg_sys::Oid,
+        snippets_funcoid: pg_sys::Oid,
+        snippet_positions_funcoid: pg_sys::Oid,
+        matched_queries_funcoid: pg_sys::Oid,
     }
 
     let mut data = Data {
         score_funcoid: score_funcoid(),
         snipped_funcoid: snippet_funcoid(),
+        snippets_funcoid: snippets_funcoid(),
+        snippet_positions_funcoid: snippet_positions_funcoid(),
+        matched_queries_funcoid: matched_queries_funcoid(),
     };
 
     let data = addr_of_mut!(data).cast();
@@ -114,7 +128,8 @@ pub unsafe fn maybe_needs_const_projections(node: *mut pg_sys::Node) -> bool {
 }
 
 /// find all [`pg_sys::FuncExpr`] nodes matching a set of known function Oids that also contain
-/// a [`pg_sys::Var`] as an argument that the specified `rti` level.
+/// a [`pg_sys::Var`] as an argument that the specified `rti` level, either directly or as the
+/// JSON column of a path expression like `metadata->>'color'`.
 ///
 /// Returns a [`Vec`] of the matching `FuncExpr`s and the argument `Var` that finally matched.  If
 /// the function has multiple arguments that match, it's returned multiple times.
@@ -134,7 +149,7 @@ pub unsafe fn pullout_funcexprs(
             if data.funcids.contains(&(*funcexpr).funcid) {
                 let args = PgList::<pg_sys::Node>::from_pg((*funcexpr).args);
                 for arg in args.iter_ptr() {
-                    if let Some(var) = nodecast!(Var, T_Var, arg) {
+                    if let Some((var, _)) = snippet_field_var(arg) {
                         if (*var).varno as i32 == data.rti as i32 {
                             data.matches.push((funcexpr, var));
                         }
@@ -170,12 +185,16 @@ pub unsafe fn inject_placeholders(
     rti: pg_sys::Index,
     score_funcoid: pg_sys::Oid,
     snippet_funcoid: pg_sys::Oid,
+    snippets_funcoid: pg_sys::Oid,
+    snippet_positions_funcoid: pg_sys::Oid,
+    matched_queries_funcoid: pg_sys::Oid,
     attname_lookup: &HashMap<(i32, pg_sys::AttrNumber), String>,
     snippet_infos: &HashMap<SnippetInfo, Option<(tantivy::schema::Field, SnippetGenerator)>>,
 ) -> (
     *mut pg_sys::List,
     *mut pg_sys::Const,
     HashMap<SnippetInfo, *mut pg_sys::Const>,
+    *mut pg_sys::Const,
 ) {
     #[pg_guard]
     unsafe extern "C" fn walker(
@@ -193,16 +212,27 @@ pub unsafe fn inject_placeholders(
 
             if (*funcexpr).funcid == data.score_funcoid {
                 return Some(data.const_score_node.cast());
-            } else if (*funcexpr).funcid == data.snippet_funcoid {
-                let var = nodecast!(Var, T_Var, args.get_ptr(0)?)?;
-                let key = (data.rti as i32, (*var).varattno);
-                if let Some(attname) = data.attname_lookup.get(&key) {
+            } else if (*funcexpr).funcid == data.matched_queries_funcoid {
+                return Some(data.const_matched_queries_node.cast());
+            } else if (*funcexpr).funcid == data.snippet_funcoid
+                || (*funcexpr).funcid == data.snippets_funcoid
+                || (*funcexpr).funcid == data.snippet_positions_funcoid
+            {
+                if let Some(field) =
+                    snippet_field_name(args.get_ptr(0)?, data.rti, data.attname_lookup)
+                {
                     for snippet_info in data.snippet_infos.keys() {
-                        if &snippet_info.field == attname {
+                        let snippet_funcoid = match snippet_info.snippet_type {
+                            SnippetType::Text => data.snippet_funcoid,
+                            SnippetType::Fragments(_) => data.snippets_funcoid,
+                            SnippetType::Positions => data.snippet_positions_funcoid,
+                        };
+                        if snippet_info.field == field && (*funcexpr).funcid == snippet_funcoid {
+                            // the functions return `text`, `text[]`, and `int4range[]`
                             let const_ = pg_sys::makeConst(
-                                pg_sys::TEXTOID,
+                                (*funcexpr).funcresulttype,
                                 -1,
-                                pg_sys::DEFAULT_COLLATION_OID,
+                                (*funcexpr).funccollid,
                                 -1,
                                 pg_sys::Datum::null(),
                                 true,
@@ -244,9 +274,14 @@ pub unsafe fn inject_placeholders(
         const_score_node: *mut pg_sys::Const,
 
         snippet_funcoid: pg_sys::Oid,
+        snippets_funcoid: pg_sys::Oid,
+        snippet_positions_funcoid: pg_sys::Oid,
         attname_lookup: &'a HashMap<(i32, pg_sys::AttrNumber), String>,
         snippet_infos: &'a HashMap<SnippetInfo, Option<(tantivy::schema::Field, SnippetGenerator)>>,
         const_snippet_nodes: HashMap<SnippetInfo, *mut pg_sys::Const>,
+
+        matched_queries_funcoid: pg_sys::Oid,
+        const_matched_queries_node: *mut pg_sys::Const,
     }
 
     let mut data = Data {
@@ -264,14 +299,28 @@ pub unsafe fn inject_placeholders(
         ),
 
         snippet_funcoid,
+        snippets_funcoid,
+        snippet_positions_funcoid,
         attname_lookup,
         snippet_infos,
         const_snippet_nodes: Default::default(),
+
+        matched_queries_funcoid,
+        const_matched_queries_node: pg_sys::makeConst(
+            pg_sys::TEXTARRAYOID,
+            -1,
+            pg_sys::DEFAULT_COLLATION_OID,
+            -1,
+            pg_sys::Datum::null(),
+            true,
+            false,
+        ),
     };
     let targetlist = walker(targetlist.cast(), addr_of_mut!(data).cast());
     (
         targetlist.cast(),
         data.const_score_node,
         data.const_snippet_nodes,
+        data.const_matched_queries_node,
     )
 }
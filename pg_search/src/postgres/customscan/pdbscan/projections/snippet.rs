@@ -22,10 +22,27 @@ use pgrx::{
     FromDatum, IntoDatum, PgList,
 };
 use std::collections::HashMap;
+use std::ffi::CStr;
+use std::ops::Range;
 use std::ptr::addr_of_mut;
+use tantivy::snippet::{Snippet, SnippetGenerator};
 
 const DEFAULT_SNIPPET_PREFIX: &str = "<b>";
 const DEFAULT_SNIPPET_POSTFIX: &str = "</b>";
+const DEFAULT_SNIPPET_MAX_NUM_CHARS: i32 = 150;
+const DEFAULT_SNIPPET_NUM_FRAGMENTS: i32 = 3;
+
+/// Which of the snippet functions a [`SnippetInfo`] is for
+#[derive(Debug, Clone, Copy, Eq, Hash, PartialEq)]
+pub enum SnippetType {
+    /// `paradedb.snippet()`: the best fragment, with its matches tagged
+    Text,
+    /// `paradedb.snippets()`: up to this many fragments, best first, with their matches tagged
+    Fragments(usize),
+    /// `paradedb.snippet_positions()`: the byte offsets of every match in the field, along with
+    /// the array element or JSON string they're in
+    Positions,
+}
 
 #[derive(Debug, Clone, Eq, Hash, PartialEq)]
 pub struct SnippetInfo {
@@ -33,6 +50,7 @@ pub struct SnippetInfo {
     pub start_tag: String,
     pub end_tag: String,
     pub max_num_chars: usize,
+    pub snippet_type: SnippetType,
 }
 
 #[pg_extern(name = "snippet", stable, parallel_safe)]
@@ -53,6 +71,54 @@ ALTER FUNCTION snippet SUPPORT placeholder_support;
     requires = [snippet_from_relation, placeholder_support]
 );
 
+#[pg_extern(name = "snippets", stable, parallel_safe)]
+fn snippets_from_relation(
+    field: AnyElement,
+    start_tag: default!(String, "'<b>'"),
+    end_tag: default!(String, "'</b>'"),
+    max_num_chars: default!(i32, "150"),
+    num_fragments: default!(i32, "3"),
+) -> Option<Vec<String>> {
+    None
+}
+
+extension_sql!(
+    r#"
+ALTER FUNCTION snippets SUPPORT placeholder_support;
+"#,
+    name = "snippets_placeholder",
+    requires = [snippets_from_relation, placeholder_support]
+);
+
+extension_sql!(
+    r#"
+CREATE TYPE snippet_positions AS (element text, positions int4range[]);
+"#,
+    name = "snippet_positions_type"
+);
+
+/// The byte offsets of the matches in a field.  `element` is the text the offsets are into, which
+/// for a text array or a JSON value is the one element, or string, whose matches are returned.
+#[pg_extern(
+    name = "snippet_positions",
+    stable,
+    parallel_safe,
+    requires = ["snippet_positions_type"]
+)]
+fn snippet_positions_from_relation(
+    field: AnyElement,
+) -> Option<pgrx::composite_type!('static, "snippet_positions")> {
+    None
+}
+
+extension_sql!(
+    r#"
+ALTER FUNCTION snippet_positions SUPPORT placeholder_support;
+"#,
+    name = "snippet_positions_placeholder",
+    requires = [snippet_positions_from_relation, placeholder_support]
+);
+
 pub fn snippet_funcoid() -> pg_sys::Oid {
     unsafe {
         direct_function_call::<pg_sys::Oid>(
@@ -63,16 +129,153 @@ pub fn snippet_funcoid() -> pg_sys::Oid {
     }
 }
 
+pub fn snippets_funcoid() -> pg_sys::Oid {
+    unsafe {
+        direct_function_call::<pg_sys::Oid>(
+            pg_sys::regprocedurein,
+            &[c"paradedb.snippets(anyelement, text, text, int, int)".into_datum()],
+        )
+        .expect("the `paradedb.snippets(anyelement, text, text, int, int)` function should exist")
+    }
+}
+
+pub fn snippet_positions_funcoid() -> pg_sys::Oid {
+    unsafe {
+        direct_function_call::<pg_sys::Oid>(
+            pg_sys::regprocedurein,
+            &[c"paradedb.snippet_positions(anyelement)".into_datum()],
+        )
+        .expect("the `paradedb.snippet_positions(anyelement)` function should exist")
+    }
+}
+
+/// The [`pg_sys::Var`] a snippet function's field argument refers to, and the path within it if
+/// the argument is a JSON path expression such as `metadata->'a'->>'b'`
+pub unsafe fn snippet_field_var(
+    node: *mut pg_sys::Node,
+) -> Option<(*mut pg_sys::Var, Vec<String>)> {
+    if let Some(var) = nodecast!(Var, T_Var, node) {
+        return Some((var, vec![]));
+    }
+
+    let opexpr = nodecast!(OpExpr, T_OpExpr, node)?;
+    let args = PgList::<pg_sys::Node>::from_pg((*opexpr).args);
+    let (lhs, rhs) = (args.get_ptr(0)?, args.get_ptr(1)?);
+    if !matches!(pg_sys::exprType(lhs), pg_sys::JSONOID | pg_sys::JSONBOID) {
+        return None;
+    }
+
+    let opname = pg_sys::get_opname((*opexpr).opno);
+    if opname.is_null() || !matches!(CStr::from_ptr(opname).to_bytes(), b"->" | b"->>") {
+        return None;
+    }
+
+    // `->` and `->>` also take an integer, to index into an array
+    let key = nodecast!(Const, T_Const, rhs)?;
+    if (*key).consttype != pg_sys::TEXTOID {
+        return None;
+    }
+    let key = String::from_datum((*key).constvalue, (*key).constisnull)?;
+
+    let (var, mut path) = snippet_field_var(lhs)?;
+    path.push(key);
+    Some((var, path))
+}
+
+/// The name of the field a snippet function's field argument refers to.  JSON paths are named
+/// the way queries name them, such as `metadata.a.b`.
+pub unsafe fn snippet_field_name(
+    node: *mut pg_sys::Node,
+    rti: pg_sys::Index,
+    attname_lookup: &HashMap<(i32, pg_sys::AttrNumber), String>,
+) -> Option<String> {
+    let (var, path) = snippet_field_var(node)?;
+    let attname = attname_lookup.get(&(rti as _, (*var).varattno as _))?;
+    Some(
+        std::iter::once(attname.clone())
+            .chain(path.into_iter().map(|key| key.replace('.', "\\.")))
+            .collect::<Vec<_>>()
+            .join("."),
+    )
+}
+
+/// The one of `texts` whose best snippet has the most matches.  A text array column, or a JSON
+/// value, can have more than one text to highlight.
+pub fn best_text<'a>(generator: &SnippetGenerator, texts: &'a [String]) -> &'a str {
+    texts
+        .iter()
+        .enumerate()
+        .max_by(|(a_idx, a), (b_idx, b)| {
+            let a = generator.snippet(a).highlighted().len();
+            let b = generator.snippet(b).highlighted().len();
+            a.cmp(&b).then_with(|| b_idx.cmp(a_idx))
+        })
+        .map(|(_, text)| text.as_str())
+        .unwrap_or_default()
+}
+
+/// The best `num_fragments` fragments of `text`, best first.
+///
+/// The [`SnippetGenerator`] only picks a single fragment, so we ask it for the best fragment of
+/// the text, and then again for the best fragment of what's left of the text on either side of
+/// the ones we've already picked, preferring the fragments with the most matches.
+pub fn best_fragments(
+    generator: &SnippetGenerator,
+    text: &str,
+    num_fragments: usize,
+) -> Vec<Snippet> {
+    let mut unused: Vec<Range<usize>> = vec![0..text.len()];
+    let mut fragments = vec![];
+    while fragments.len() < num_fragments {
+        let best = unused
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, range)| {
+                let snippet = generator.snippet(&text[range.clone()]);
+                (!snippet.is_empty()).then_some((idx, snippet))
+            })
+            .max_by(|(a_idx, a), (b_idx, b)| {
+                a.highlighted()
+                    .len()
+                    .cmp(&b.highlighted().len())
+                    .then_with(|| b_idx.cmp(a_idx))
+            });
+        let Some((idx, snippet)) = best else {
+            break;
+        };
+
+        // split what's left of the text around the fragment we just picked, leaving out the
+        // whitespace next to it so that later fragments don't start or end with it
+        let range = unused.remove(idx);
+        let start = range.start
+            + text[range.clone()]
+                .find(snippet.fragment())
+                .expect("fragment should be part of the text it came from");
+        let end = start + snippet.fragment().len();
+        let before = range.start + text[range.start..start].trim_end().len();
+        let after = range.end - text[end..range.end].trim_start().len();
+        unused.insert(idx, after..range.end);
+        unused.insert(idx, range.start..before);
+
+        fragments.push(snippet);
+    }
+    fragments
+}
+
 pub unsafe fn uses_snippets(
     rti: pg_sys::Index,
     attname_lookup: &HashMap<(i32, pg_sys::AttrNumber), String>,
     node: *mut pg_sys::Node,
     snippet_funcoid: pg_sys::Oid,
+    snippets_funcoid: pg_sys::Oid,
+    snippet_positions_funcoid: pg_sys::Oid,
 ) -> Vec<SnippetInfo> {
     struct Context<'a> {
         rti: pg_sys::Index,
         attname_lookup: &'a HashMap<(i32, pg_sys::AttrNumber), String>,
         snippet_funcoid: pg_sys::Oid,
+        snippets_funcoid: pg_sys::Oid,
+        snippet_positions_funcoid: pg_sys::Oid,
         snippet_info: Vec<SnippetInfo>,
     }
 
@@ -91,7 +294,11 @@ pub unsafe fn uses_snippets(
                 // this should be equal to the number of args in the `snippet()` function above
                 assert!(args.len() == 4);
 
-                let field_arg = nodecast!(Var, T_Var, args.get_ptr(0).unwrap());
+                let field_arg = snippet_field_name(
+                    args.get_ptr(0).unwrap(),
+                    (*context).rti,
+                    (*context).attname_lookup,
+                );
                 let start_arg = nodecast!(Const, T_Const, args.get_ptr(1).unwrap());
                 let end_arg = nodecast!(Const, T_Const, args.get_ptr(2).unwrap());
                 let max_num_chars_arg = nodecast!(Const, T_Const, args.get_ptr(3).unwrap());
@@ -99,11 +306,7 @@ pub unsafe fn uses_snippets(
                 if let (Some(field_arg), Some(start_arg), Some(end_arg), Some(max_num_chars_arg)) =
                     (field_arg, start_arg, end_arg, max_num_chars_arg)
                 {
-                    let attname = (*context)
-                        .attname_lookup
-                        .get(&((*context).rti as _, (*field_arg).varattno as _))
-                        .cloned()
-                        .expect("Var attname should be in lookup");
+                    let attname = field_arg;
                     let start_tag =
                         String::from_datum((*start_arg).constvalue, (*start_arg).constisnull);
                     let end_tag = String::from_datum((*end_arg).constvalue, (*end_arg).constisnull);
@@ -116,11 +319,97 @@ pub unsafe fn uses_snippets(
                         field: attname,
                         start_tag: start_tag.unwrap_or_else(|| DEFAULT_SNIPPET_PREFIX.to_string()),
                         end_tag: end_tag.unwrap_or_else(|| DEFAULT_SNIPPET_POSTFIX.to_string()),
-                        max_num_chars: max_num_chars_arg as usize,
+                        max_num_chars: max_num_chars.unwrap_or(DEFAULT_SNIPPET_MAX_NUM_CHARS)
+                            as usize,
+                        snippet_type: SnippetType::Text,
                     });
                 } else {
                     panic!("`paradedb.snippet()`'s arguments must be literals")
                 }
+            } else if (*funcexpr).funcid == (*context).snippets_funcoid {
+                let args = PgList::<pg_sys::Node>::from_pg((*funcexpr).args);
+
+                // this should be equal to the number of args in the `snippets()` function above
+                assert!(args.len() == 5);
+
+                let field_arg = snippet_field_name(
+                    args.get_ptr(0).unwrap(),
+                    (*context).rti,
+                    (*context).attname_lookup,
+                );
+                let start_arg = nodecast!(Const, T_Const, args.get_ptr(1).unwrap());
+                let end_arg = nodecast!(Const, T_Const, args.get_ptr(2).unwrap());
+                let max_num_chars_arg = nodecast!(Const, T_Const, args.get_ptr(3).unwrap());
+                let num_fragments_arg = nodecast!(Const, T_Const, args.get_ptr(4).unwrap());
+
+                if let (
+                    Some(field_arg),
+                    Some(start_arg),
+                    Some(end_arg),
+                    Some(max_num_chars_arg),
+                    Some(num_fragments_arg),
+                ) = (
+                    field_arg,
+                    start_arg,
+                    end_arg,
+                    max_num_chars_arg,
+                    num_fragments_arg,
+                ) {
+                    let attname = field_arg;
+                    let start_tag =
+                        String::from_datum((*start_arg).constvalue, (*start_arg).constisnull);
+                    let end_tag = String::from_datum((*end_arg).constvalue, (*end_arg).constisnull);
+                    let max_num_chars = i32::from_datum(
+                        (*max_num_chars_arg).constvalue,
+                        (*max_num_chars_arg).constisnull,
+                    )
+                    .unwrap_or(DEFAULT_SNIPPET_MAX_NUM_CHARS);
+                    let num_fragments = i32::from_datum(
+                        (*num_fragments_arg).constvalue,
+                        (*num_fragments_arg).constisnull,
+                    )
+                    .unwrap_or(DEFAULT_SNIPPET_NUM_FRAGMENTS);
+
+                    if max_num_chars < 1 {
+                        panic!("`paradedb.snippets()`'s max_num_chars must be at least 1");
+                    }
+                    if num_fragments < 1 {
+                        panic!("`paradedb.snippets()`'s num_fragments must be at least 1");
+                    }
+
+                    (*context).snippet_info.push(SnippetInfo {
+                        field: attname,
+                        start_tag: start_tag.unwrap_or_else(|| DEFAULT_SNIPPET_PREFIX.to_string()),
+                        end_tag: end_tag.unwrap_or_else(|| DEFAULT_SNIPPET_POSTFIX.to_string()),
+                        max_num_chars: max_num_chars as usize,
+                        snippet_type: SnippetType::Fragments(num_fragments as usize),
+                    });
+                } else {
+                    panic!("`paradedb.snippets()`'s arguments must be literals")
+                }
+            } else if (*funcexpr).funcid == (*context).snippet_positions_funcoid {
+                let args = PgList::<pg_sys::Node>::from_pg((*funcexpr).args);
+                assert!(args.len() == 1);
+
+                if let Some(attname) = snippet_field_name(
+                    args.get_ptr(0).unwrap(),
+                    (*context).rti,
+                    (*context).attname_lookup,
+                ) {
+                    // the whole field is a single "fragment", so the positions of its matches
+                    // are relative to the start of the field
+                    (*context).snippet_info.push(SnippetInfo {
+                        field: attname,
+                        start_tag: String::new(),
+                        end_tag: String::new(),
+                        max_num_chars: usize::MAX,
+                        snippet_type: SnippetType::Positions,
+                    });
+                } else {
+                    panic!(
+                        "`paradedb.snippet_positions()`'s argument must be a column or a JSON path"
+                    )
+                }
             }
         }
 
@@ -131,6 +420,8 @@ pub unsafe fn uses_snippets(
         rti,
         attname_lookup,
         snippet_funcoid,
+        snippets_funcoid,
+        snippet_positions_funcoid,
         snippet_info: vec![],
     };
 
@@ -16,12 +16,17 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use crate::nodecast;
-use crate::postgres::customscan::pdbscan::privdat::deserialize::decodeString;
+use crate::postgres::customscan::pdbscan::privdat::deserialize::{decodeBoolean, decodeString};
 use crate::postgres::customscan::pdbscan::privdat::serialize::{
-    makeInteger, makeString, AsValueNode,
+    makeBoolean, makeInteger, makeString, AsValueNode,
 };
+use crate::postgres::customscan::pdbscan::projections::score::is_score_func;
 use crate::query::SearchQueryInput;
-use pgrx::{node_to_string, pg_sys, FromDatum, PgList};
+use crate::schema::SearchIndexSchema;
+use pgrx::{
+    node_to_string, pg_sys, AnyNumeric, FromDatum, PgBuiltInOids, PgList, PgOid, PgRelation,
+};
+use std::ops::Bound;
 
 #[derive(Debug, Clone)]
 pub enum Qual {
@@ -31,11 +36,44 @@ pub enum Qual {
         opno: pg_sys::Oid,
         val: *mut pg_sys::Const,
     },
+    /// A `paradedb.score()` comparison that's only satisfied by scores above `min_score`.  These
+    /// only ever appear directly in the top-level [`Qual::And`], as they apply to the score of
+    /// the whole query.
+    ScoreExpression {
+        min_score: f64,
+        inclusive: bool,
+    },
+    /// An `IS NULL`, or `IS NOT NULL`, test of a column whose search field `field` indexes its
+    /// nulls
+    NullTest {
+        field: String,
+        is_null: bool,
+    },
     And(Vec<Qual>),
     Or(Vec<Qual>),
     Not(Box<Qual>),
 }
 
+impl Qual {
+    pub fn contains_score_expression(&self) -> bool {
+        match self {
+            Qual::ScoreExpression { .. } => true,
+            Qual::And(quals) => quals.iter().any(Qual::contains_score_expression),
+            _ => false,
+        }
+    }
+
+    /// Whether there's a search in this qual, rather than only tests for NULLs
+    pub fn contains_search(&self) -> bool {
+        match self {
+            Qual::Ignore | Qual::OperatorExpression { .. } => true,
+            Qual::ScoreExpression { .. } | Qual::NullTest { .. } => false,
+            Qual::And(quals) | Qual::Or(quals) => quals.iter().any(Qual::contains_search),
+            Qual::Not(qual) => qual.contains_search(),
+        }
+    }
+}
+
 impl From<&Qual> for SearchQueryInput {
     fn from(value: &Qual) -> Self {
         match value {
@@ -45,16 +83,69 @@ impl From<&Qual> for SearchQueryInput {
                     .expect("rhs of @@@ operator Qual must not be null")
             },
 
+            Qual::ScoreExpression { .. } => {
+                panic!("a paradedb.score() comparison must be combined with a search query")
+            }
+
+            Qual::NullTest { field, is_null } => {
+                let query = SearchQueryInput::IsNull {
+                    field: field.clone(),
+                };
+                if *is_null {
+                    query
+                } else {
+                    SearchQueryInput::Boolean {
+                        must: vec![SearchQueryInput::All],
+                        should: Default::default(),
+                        must_not: vec![query],
+                        minimum_should_match: None,
+                    }
+                }
+            }
+
             Qual::And(quals) => {
-                let must = quals.iter().map(SearchQueryInput::from).collect::<Vec<_>>();
+                let (score_quals, quals): (Vec<_>, Vec<_>) = quals
+                    .iter()
+                    .partition(|qual| matches!(qual, Qual::ScoreExpression { .. }));
+                let must = quals
+                    .into_iter()
+                    .map(SearchQueryInput::from)
+                    .collect::<Vec<_>>();
 
-                match must.len() {
+                let query = match must.len() {
                     0 => panic!("Qual::And should have at least one item"),
                     1 => must.into_iter().next().unwrap(),
                     _ => SearchQueryInput::Boolean {
                         must,
                         should: Default::default(),
                         must_not: Default::default(),
+                        minimum_should_match: None,
+                    },
+                };
+
+                // only the strictest of the score comparisons matters
+                let strictest = score_quals
+                    .into_iter()
+                    .filter_map(|qual| match qual {
+                        Qual::ScoreExpression {
+                            min_score,
+                            inclusive,
+                        } => Some((*min_score, *inclusive)),
+                        _ => None,
+                    })
+                    .max_by(|(a, a_inclusive), (b, b_inclusive)| {
+                        a.total_cmp(b).then_with(|| b_inclusive.cmp(a_inclusive))
+                    });
+
+                match strictest {
+                    None => query,
+                    Some((min_score, inclusive)) => SearchQueryInput::ScoreFilter {
+                        lower_bound: if inclusive {
+                            Bound::Included(min_score)
+                        } else {
+                            Bound::Excluded(min_score)
+                        },
+                        query: Box::new(query),
                     },
                 }
             }
@@ -68,6 +159,7 @@ impl From<&Qual> for SearchQueryInput {
                         must: Default::default(),
                         should,
                         must_not: Default::default(),
+                        minimum_should_match: None,
                     },
                 }
             }
@@ -78,6 +170,7 @@ impl From<&Qual> for SearchQueryInput {
                     must: Default::default(),
                     should: Default::default(),
                     must_not,
+                    minimum_should_match: None,
                 }
             }
         }
@@ -97,6 +190,19 @@ impl From<Qual> for PgList<pg_sys::Node> {
                     list.push(makeInteger(Some(opno)));
                     list.push(val.cast());
                 }
+                Qual::ScoreExpression {
+                    min_score,
+                    inclusive,
+                } => {
+                    list.push(makeString(Some("SCORE_EXPRESSION")));
+                    list.push(makeString(Some(min_score)));
+                    list.push(makeBoolean(Some(inclusive)));
+                }
+                Qual::NullTest { field, is_null } => {
+                    list.push(makeString(Some("NULL_TEST")));
+                    list.push(makeString(Some(field)));
+                    list.push(makeBoolean(Some(is_null)));
+                }
                 Qual::And(quals) => {
                     list.push(makeString(Some("AND")));
                     list.push(makeInteger(Some(quals.len())));
@@ -142,6 +248,14 @@ impl From<PgList<pg_sys::Node>> for Qual {
                             );
                             Some(Qual::OperatorExpression { var, opno, val })
                         }
+                        "SCORE_EXPRESSION" => Some(Qual::ScoreExpression {
+                            min_score: decodeString(value.get_ptr(1)?)?,
+                            inclusive: decodeBoolean(value.get_ptr(2)?)?,
+                        }),
+                        "NULL_TEST" => Some(Qual::NullTest {
+                            field: decodeString(value.get_ptr(1)?)?,
+                            is_null: decodeBoolean(value.get_ptr(2)?)?,
+                        }),
                         "AND" => {
                             let len = usize::from_value_node(value.get_ptr(1)?)?;
                             let mut quals = Vec::with_capacity(len);
@@ -192,10 +306,28 @@ pub unsafe fn extract_quals(
     rti: pg_sys::Index,
     node: *mut pg_sys::Node,
     pdbopoid: pg_sys::Oid,
+    heaprel: &PgRelation,
+    schema: &SearchIndexSchema,
 ) -> Option<Qual> {
     match (*node).type_ {
         pg_sys::NodeTag::T_List => {
-            let mut quals = list(rti, node.cast(), pdbopoid)?;
+            // this is the top-level list of restrictions, which is the only place we can apply
+            // a comparison against the score of the whole query
+            let mut quals = Vec::new();
+            let mut score_quals = Vec::new();
+            for child in PgList::<pg_sys::Node>::from_pg(node.cast()).iter_ptr() {
+                match score_expression(rti, child) {
+                    Some(qual) => score_quals.push(qual),
+                    None => quals.push(extract_quals(rti, child, pdbopoid, heaprel, schema)?),
+                }
+            }
+            if !quals.iter().any(Qual::contains_search) {
+                // there's no search for the scores to come from, and NULL tests alone are
+                // better left to Postgres
+                return None;
+            }
+
+            quals.extend(score_quals);
             if quals.len() == 1 {
                 quals.pop()
             } else {
@@ -213,15 +345,17 @@ pub unsafe fn extract_quals(
             } else {
                 (*ri).clause
             };
-            extract_quals(rti, clause.cast(), pdbopoid)
+            extract_quals(rti, clause.cast(), pdbopoid, heaprel, schema)
         }
 
         pg_sys::NodeTag::T_OpExpr => opexpr(rti, node, pdbopoid),
 
+        pg_sys::NodeTag::T_NullTest => nulltest(rti, node, heaprel, schema),
+
         pg_sys::NodeTag::T_BoolExpr => {
             let boolexpr = nodecast!(BoolExpr, T_BoolExpr, node)?;
             let args = PgList::<pg_sys::Node>::from_pg((*boolexpr).args);
-            let mut quals = list(rti, (*boolexpr).args, pdbopoid)?;
+            let mut quals = list(rti, (*boolexpr).args, pdbopoid, heaprel, schema)?;
 
             match (*boolexpr).boolop {
                 pg_sys::BoolExprType::AND_EXPR => Some(Qual::And(quals)),
@@ -243,11 +377,13 @@ unsafe fn list(
     rti: pg_sys::Index,
     list: *mut pg_sys::List,
     pdbopoid: pg_sys::Oid,
+    heaprel: &PgRelation,
+    schema: &SearchIndexSchema,
 ) -> Option<Vec<Qual>> {
     let args = PgList::<pg_sys::Node>::from_pg(list);
     let mut quals = Vec::new();
     for child in args.iter_ptr() {
-        quals.push(extract_quals(rti, child, pdbopoid)?)
+        quals.push(extract_quals(rti, child, pdbopoid, heaprel, schema)?)
     }
     Some(quals)
 }
@@ -287,3 +423,91 @@ unsafe fn opexpr(
         None
     }
 }
+
+/// Recognize `<column> IS [NOT] NULL` as a [`Qual::NullTest`], if the column is indexed into a
+/// field that records its nulls
+unsafe fn nulltest(
+    rti: pg_sys::Index,
+    node: *mut pg_sys::Node,
+    heaprel: &PgRelation,
+    schema: &SearchIndexSchema,
+) -> Option<Qual> {
+    let nulltest = nodecast!(NullTest, T_NullTest, node)?;
+    let var = nodecast!(Var, T_Var, (*nulltest).arg.cast())?;
+
+    // a row-valued test is about the row's columns, not the row itself
+    if (*nulltest).argisrow || (*var).varno as i32 != rti as i32 || (*var).varattno <= 0 {
+        return None;
+    }
+
+    let tupdesc = heaprel.tuple_desc();
+    let attribute = tupdesc.get((*var).varattno as usize - 1)?;
+    let field = schema.nulls_indexed_field(attribute.name())?;
+
+    Some(Qual::NullTest {
+        field: field.name.0.clone(),
+        is_null: (*nulltest).nulltesttype == pg_sys::NullTestType::IS_NULL,
+    })
+}
+
+/// Recognize a `paradedb.score(<our relation>) > <constant>` restriction (or `>=`, or either one
+/// with its operands swapped) as a [`Qual::ScoreExpression`]
+unsafe fn score_expression(rti: pg_sys::Index, node: *mut pg_sys::Node) -> Option<Qual> {
+    let node = match nodecast!(RestrictInfo, T_RestrictInfo, node) {
+        Some(ri) => (*ri).clause.cast(),
+        None => node,
+    };
+    let opexpr = nodecast!(OpExpr, T_OpExpr, node)?;
+    let args = PgList::<pg_sys::Node>::from_pg((*opexpr).args);
+    let (lhs, rhs) = (args.get_ptr(0)?, args.get_ptr(1)?);
+
+    let (inclusive, val) = match (
+        float_comparison_strategy((*opexpr).opno)?,
+        is_score_func(lhs, rti),
+    ) {
+        (pg_sys::BTGreaterStrategyNumber, true) => (false, rhs),
+        (pg_sys::BTGreaterEqualStrategyNumber, true) => (true, rhs),
+        (pg_sys::BTLessStrategyNumber, false) if is_score_func(rhs, rti) => (false, lhs),
+        (pg_sys::BTLessEqualStrategyNumber, false) if is_score_func(rhs, rti) => (true, lhs),
+        _ => return None,
+    };
+
+    let val = nodecast!(Const, T_Const, val)?;
+    if (*val).constisnull {
+        return None;
+    }
+    let datum = (*val).constvalue;
+    let min_score = match PgOid::from((*val).consttype) {
+        PgOid::BuiltIn(PgBuiltInOids::FLOAT4OID) => f32::from_datum(datum, false)? as f64,
+        PgOid::BuiltIn(PgBuiltInOids::FLOAT8OID) => f64::from_datum(datum, false)?,
+        PgOid::BuiltIn(PgBuiltInOids::INT2OID) => i16::from_datum(datum, false)? as f64,
+        PgOid::BuiltIn(PgBuiltInOids::INT4OID) => i32::from_datum(datum, false)? as f64,
+        PgOid::BuiltIn(PgBuiltInOids::INT8OID) => i64::from_datum(datum, false)? as f64,
+        PgOid::BuiltIn(PgBuiltInOids::NUMERICOID) => {
+            AnyNumeric::from_datum(datum, false)?.try_into().ok()?
+        }
+        _ => return None,
+    };
+
+    Some(Qual::ScoreExpression {
+        min_score,
+        inclusive,
+    })
+}
+
+/// The btree strategy of `opno` if it's one of the built-in comparisons between `real`s and
+/// `double precision`s, which is what `paradedb.score()` is compared with
+unsafe fn float_comparison_strategy(opno: pg_sys::Oid) -> Option<u32> {
+    let is_float = |typeoid: pg_sys::Oid| {
+        matches!(
+            PgOid::from(typeoid),
+            PgOid::BuiltIn(PgBuiltInOids::FLOAT4OID | PgBuiltInOids::FLOAT8OID)
+        )
+    };
+    PgList::<pg_sys::OpBtreeInterpretation>::from_pg(pg_sys::get_op_btree_interpretation(opno))
+        .iter_ptr()
+        .find(|interpretation| {
+            is_float((**interpretation).oplefttype) && is_float((**interpretation).oprighttype)
+        })
+        .map(|interpretation| (*interpretation).strategy as u32)
+}
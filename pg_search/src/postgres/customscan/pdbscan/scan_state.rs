@@ -20,17 +20,22 @@ use crate::index::reader::index::{SearchIndexReader, SearchResults};
 use crate::postgres::customscan::builders::custom_path::SortDirection;
 use crate::postgres::customscan::pdbscan::exec_methods::ExecMethod;
 use crate::postgres::customscan::pdbscan::parallel::PdbParallelScanState;
-use crate::postgres::customscan::pdbscan::projections::snippet::SnippetInfo;
+use crate::postgres::customscan::pdbscan::projections::matched_queries::MatchedQueries;
+use crate::postgres::customscan::pdbscan::projections::snippet::{
+    best_fragments, best_text, SnippetInfo,
+};
 use crate::postgres::customscan::CustomScanState;
 use crate::postgres::options::SearchIndexCreateOptions;
 use crate::postgres::utils::u64_to_item_pointer;
 use crate::postgres::visibility_checker::VisibilityChecker;
-use crate::query::SearchQueryInput;
+use crate::query::{split_field_and_path, SearchQueryInput};
+use crate::schema::geo::GeoPoint;
+use pgrx::datum::RangeBound;
 use pgrx::heap_tuple::PgHeapTuple;
-use pgrx::{name_data_to_str, pg_sys, PgRelation, PgTupleDesc};
+use pgrx::{name_data_to_str, pg_sys, Json, JsonB, PgRelation, PgTupleDesc};
 use std::cell::UnsafeCell;
 use std::collections::HashMap;
-use tantivy::schema::OwnedValue;
+use tantivy::json_utils::split_json_path;
 use tantivy::snippet::SnippetGenerator;
 
 #[derive(Default)]
@@ -49,6 +54,7 @@ pub struct PdbScanState {
     pub limit: Option<usize>,
     pub sort_field: Option<String>,
     pub sort_direction: Option<SortDirection>,
+    pub sort_geo_origin: Option<GeoPoint>,
     pub retry_count: usize,
     pub heap_tuple_check_count: usize,
     pub virtual_tuple_count: usize,
@@ -72,10 +78,17 @@ pub struct PdbScanState {
 
     pub const_snippet_nodes: HashMap<SnippetInfo, *mut pg_sys::Const>,
     pub snippet_funcoid: pg_sys::Oid,
+    pub snippets_funcoid: pg_sys::Oid,
+    pub snippet_positions_funcoid: pg_sys::Oid,
     pub snippet_generators:
         HashMap<SnippetInfo, Option<(tantivy::schema::Field, SnippetGenerator)>>,
     pub var_attname_lookup: HashMap<(i32, pg_sys::AttrNumber), String>,
 
+    pub need_matched_queries: bool,
+    pub const_matched_queries_node: Option<*mut pg_sys::Const>,
+    pub matched_queries_funcoid: pg_sys::Oid,
+    pub matched_queries: MatchedQueries,
+
     pub placeholder_targetlist: Option<*mut pg_sys::List>,
 
     exec_method: UnsafeCell<Box<dyn ExecMethod>>,
@@ -152,6 +165,11 @@ impl PdbScanState {
         !self.snippet_generators.is_empty()
     }
 
+    #[inline(always)]
+    pub fn need_matched_queries(&self) -> bool {
+        self.need_matched_queries
+    }
+
     #[track_caller]
     #[inline(always)]
     pub fn heaprel(&self) -> pg_sys::Relation {
@@ -189,13 +207,70 @@ impl PdbScanState {
     }
 
     pub fn make_snippet(&self, ctid: u64, snippet_info: &SnippetInfo) -> Option<String> {
+        let texts = self.snippet_texts(ctid, snippet_info)?;
+        let (_, generator) = self.snippet_generators.get(snippet_info)?.as_ref()?;
+        let mut snippet = generator.snippet(best_text(generator, &texts).trim());
+
+        snippet.set_snippet_prefix_postfix(&snippet_info.start_tag, &snippet_info.end_tag);
+        Some(snippet.to_html())
+    }
+
+    pub fn make_snippets(
+        &self,
+        ctid: u64,
+        snippet_info: &SnippetInfo,
+        num_fragments: usize,
+    ) -> Option<Vec<String>> {
+        let texts = self.snippet_texts(ctid, snippet_info)?;
+        let (_, generator) = self.snippet_generators.get(snippet_info)?.as_ref()?;
+        Some(
+            best_fragments(generator, best_text(generator, &texts), num_fragments)
+                .into_iter()
+                .map(|mut snippet| {
+                    snippet
+                        .set_snippet_prefix_postfix(&snippet_info.start_tag, &snippet_info.end_tag);
+                    snippet.to_html()
+                })
+                .collect(),
+        )
+    }
+
+    /// The byte offsets of the matches in the field, and the text they're offsets into.  A text
+    /// array column, or a JSON value, has more than one text, so the offsets are only meaningful
+    /// alongside the one they were found in.
+    pub fn make_snippet_positions(
+        &self,
+        ctid: u64,
+        snippet_info: &SnippetInfo,
+    ) -> Option<(String, Vec<pgrx::Range<i32>>)> {
+        let texts = self.snippet_texts(ctid, snippet_info)?;
+        let (_, generator) = self.snippet_generators.get(snippet_info)?.as_ref()?;
+        let text = best_text(generator, &texts);
+        let positions = generator
+            .snippet(text)
+            .highlighted()
+            .iter()
+            .map(|range| {
+                pgrx::Range::new(
+                    RangeBound::Inclusive(range.start as i32),
+                    RangeBound::Exclusive(range.end as i32),
+                )
+            })
+            .collect();
+        Some((text.to_string(), positions))
+    }
+
+    /// The text, from the heap, of the field `snippet_info` is for.  That's the value of a text
+    /// column, the elements of a text array column, or the strings at, or below, a path within a
+    /// JSON column.
+    fn snippet_texts(&self, ctid: u64, snippet_info: &SnippetInfo) -> Option<Vec<String>> {
         let heaprel = self
             .heaprel
             .expect("make_snippet: heaprel should be initialized");
         let mut ipd = pg_sys::ItemPointerData::default();
         u64_to_item_pointer(ctid, &mut ipd);
 
-        let text = unsafe {
+        let texts = unsafe {
             let mut heap_tuple = pg_sys::HeapTupleData {
                 t_self: ipd,
                 ..Default::default()
@@ -230,17 +305,64 @@ impl PdbScanState {
             pg_sys::ReleaseBuffer(buffer);
 
             let tuple_desc = PgTupleDesc::from_pg_unchecked((*heaprel).rd_att);
-            PgHeapTuple::from_heap_tuple(tuple_desc, &mut heap_tuple)
-                .get_by_name(&snippet_info.field)
-                .expect("{snippet_info.field} should exist in the heap tuple")
-                .unwrap_or_default()
+            let heap_tuple = PgHeapTuple::from_heap_tuple(tuple_desc, &mut heap_tuple);
+            let (column, path) = match heap_tuple.get_attribute_by_name(&snippet_info.field) {
+                Some(_) => (snippet_info.field.clone(), None),
+                None => split_field_and_path(&snippet_info.field),
+            };
+            let (_, attribute) = heap_tuple
+                .get_attribute_by_name(&column)
+                .unwrap_or_else(|| panic!("{column} should exist in the heap tuple"));
+
+            match attribute.atttypid {
+                pg_sys::TEXTARRAYOID => heap_tuple
+                    .get_by_name::<Vec<Option<String>>>(&column)
+                    .unwrap_or_else(|e| panic!("{column} should be a text array: {e}"))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flatten()
+                    .collect(),
+                pg_sys::JSONBOID => heap_tuple
+                    .get_by_name::<JsonB>(&column)
+                    .unwrap_or_else(|e| panic!("{column} should be jsonb: {e}"))
+                    .map(|JsonB(value)| json_texts(&value, path.as_deref()))
+                    .unwrap_or_default(),
+                pg_sys::JSONOID => heap_tuple
+                    .get_by_name::<Json>(&column)
+                    .unwrap_or_else(|e| panic!("{column} should be json: {e}"))
+                    .map(|Json(value)| json_texts(&value, path.as_deref()))
+                    .unwrap_or_default(),
+                _ => vec![heap_tuple
+                    .get_by_name::<String>(&column)
+                    .unwrap_or_else(|e| panic!("{column} should be text: {e}"))
+                    .unwrap_or_default()],
+            }
         };
 
-        let (field, generator) = self.snippet_generators.get(snippet_info)?.as_ref()?;
-        let doc = HashMap::from([(*field, OwnedValue::Str(text))]);
-        let mut snippet = generator.snippet_from_doc(&doc);
+        Some(texts)
+    }
+}
 
-        snippet.set_snippet_prefix_postfix(&snippet_info.start_tag, &snippet_info.end_tag);
-        Some(snippet.to_html())
+/// The strings at, or below, `path` in `value`
+fn json_texts(value: &serde_json::Value, path: Option<&str>) -> Vec<String> {
+    fn collect(value: &serde_json::Value, texts: &mut Vec<String>) {
+        match value {
+            serde_json::Value::String(text) => texts.push(text.clone()),
+            serde_json::Value::Array(values) => values.iter().for_each(|v| collect(v, texts)),
+            serde_json::Value::Object(map) => map.values().for_each(|v| collect(v, texts)),
+            _ => {}
+        }
+    }
+
+    let mut value = value;
+    for key in path.map(split_json_path).unwrap_or_default() {
+        match value.get(&key) {
+            Some(inner) => value = inner,
+            None => return vec![],
+        }
     }
+
+    let mut texts = vec![];
+    collect(value, &mut texts);
+    texts
 }
@@ -0,0 +1,66 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! numeric values indexed as exact decimals are stored as `i64`s, scaled by `10^scale` for the
+//! scale declared by the field, so that they keep every digit a `float8` would lose.  They're
+//! converted through the text Postgres prints for a numeric, so no arithmetic on the value itself
+//! can round it.
+
+/// The `i64` the decimal `text` is stored as with `scale` digits after the point, or `None` if
+/// it isn't a finite number or can't be stored exactly: it either has non-zero digits beyond the
+/// scale or, once scaled, doesn't fit in an `i64`
+pub fn scale_decimal(text: &str, scale: u32) -> Option<i64> {
+    let text = text.trim();
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, text.strip_prefix('+').unwrap_or(text)),
+    };
+    let (integer, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if (integer.is_empty() && fraction.is_empty())
+        || !integer.bytes().all(|b| b.is_ascii_digit())
+        || !fraction.bytes().all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let (kept, dropped) = fraction.split_at(fraction.len().min(scale as usize));
+    if dropped.bytes().any(|b| b != b'0') {
+        return None;
+    }
+
+    let padding = std::iter::repeat(b'0').take(scale as usize - kept.len());
+    let mut scaled: i128 = 0;
+    for digit in integer.bytes().chain(kept.bytes()).chain(padding) {
+        scaled = scaled
+            .checked_mul(10)?
+            .checked_add((digit - b'0') as i128)?;
+    }
+    i64::try_from(if negative { -scaled } else { scaled }).ok()
+}
+
+/// The decimal text of a value stored by [`scale_decimal`] with `scale` digits after the point
+pub fn unscale_decimal(value: i64, scale: u32) -> String {
+    let divisor = 10_u64.pow(scale);
+    let sign = if value < 0 { "-" } else { "" };
+    let magnitude = value.unsigned_abs();
+    let (integer, fraction) = (magnitude / divisor, magnitude % divisor);
+    if scale == 0 {
+        format!("{sign}{integer}")
+    } else {
+        format!("{sign}{integer}.{fraction:0width$}", width = scale as usize)
+    }
+}
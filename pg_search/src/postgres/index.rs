@@ -16,7 +16,9 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use crate::postgres::options::SearchIndexCreateOptions;
-use crate::schema::{IndexRecordOption, SearchFieldConfig, SearchFieldName, SearchFieldType};
+use crate::schema::{
+    IndexRecordOption, SearchFieldConfig, SearchFieldName, SearchFieldType, SearchSimilarity,
+};
 use pgrx::{pg_sys, PgBox, PgOid, PgRelation};
 use std::collections::HashMap;
 use tokenizers::manager::SearchTokenizerFilters;
@@ -102,6 +104,82 @@ pub unsafe fn get_fields(index_relation: &PgRelation) -> (Fields, KeyFieldIndex)
         }
     }
 
+    for (name, config) in rdopts.get_inet_fields() {
+        let name = SearchFieldName(config.column().unwrap_or(&name.0).into());
+        if !matches!(name_type_map.get(&name), Some(SearchFieldType::Inet)) {
+            panic!("'{name}' cannot be indexed as an inet field");
+        }
+    }
+
+    for (name, config) in rdopts.get_bytes_fields() {
+        let name = SearchFieldName(config.column().unwrap_or(&name.0).into());
+        if !matches!(name_type_map.get(&name), Some(SearchFieldType::Bytes)) {
+            panic!("'{name}' cannot be indexed as a bytes field");
+        }
+    }
+
+    for (name, config) in rdopts.get_completion_fields() {
+        let column = SearchFieldName(config.column().unwrap_or(&name.0).into());
+        if !matches!(name_type_map.get(&column), Some(SearchFieldType::Text)) {
+            panic!("'{column}' cannot be indexed as a completion field");
+        }
+
+        // completions are ranked by the weight of their row, from a numeric fast field
+        if let SearchFieldConfig::Completion {
+            weight: Some(weight),
+            ..
+        } = &config
+        {
+            let is_fast_numeric =
+                rdopts
+                    .get_numeric_fields()
+                    .into_iter()
+                    .any(|(numeric_name, numeric_config)| {
+                        numeric_name.0 == *weight
+                            && matches!(
+                                numeric_config,
+                                SearchFieldConfig::Numeric { fast: true, .. }
+                            )
+                    });
+            if !is_fast_numeric {
+                panic!(
+                    "weight '{weight}' of completion field '{name}' must be a numeric field with fast: true"
+                );
+            }
+        }
+    }
+
+    for (name, config) in rdopts.get_vector_fields() {
+        let name = SearchFieldName(config.column().unwrap_or(&name.0).into());
+        if !matches!(name_type_map.get(&name), Some(SearchFieldType::F64)) {
+            panic!("'{name}' cannot be indexed as a vector field");
+        }
+    }
+
+    for (name, config) in rdopts.get_sparse_vector_fields() {
+        let name = SearchFieldName(config.column().unwrap_or(&name.0).into());
+        if !matches!(name_type_map.get(&name), Some(SearchFieldType::Json)) {
+            panic!("'{name}' cannot be indexed as a sparse vector field");
+        }
+    }
+
+    for (name, config) in rdopts.get_geo_fields() {
+        let name = SearchFieldName(config.column().unwrap_or(&name.0).into());
+        if !matches!(
+            name_type_map.get(&name),
+            Some(SearchFieldType::Geo | SearchFieldType::Json)
+        ) {
+            panic!("'{name}' cannot be indexed as a geo field");
+        }
+    }
+
+    for (name, config) in rdopts.get_uuid_fields() {
+        let name = SearchFieldName(config.column().unwrap_or(&name.0).into());
+        if !matches!(name_type_map.get(&name), Some(SearchFieldType::Text)) {
+            panic!("'{name}' cannot be indexed as a uuid field");
+        }
+    }
+
     let key_field = rdopts.get_key_field().expect("must specify key_field");
     let key_field_type = match name_type_map.get(&key_field) {
         Some(field_type) => field_type,
@@ -113,6 +191,8 @@ pub unsafe fn get_fields(index_relation: &PgRelation) -> (Fields, KeyFieldIndex)
                 indexed: true,
                 fast: true,
                 stored: false,
+                index_nulls: false,
+                scale: None,
                 column: None,
             }
         }
@@ -124,6 +204,8 @@ pub unsafe fn get_fields(index_relation: &PgRelation) -> (Fields, KeyFieldIndex)
             tokenizer: SearchTokenizer::Raw(SearchTokenizerFilters::default()),
             record: IndexRecordOption::Basic,
             normalizer: SearchNormalizer::Raw,
+            similarity: SearchSimilarity::default(),
+            index_nulls: false,
             column: None,
         },
         SearchFieldType::Json => SearchFieldConfig::Json {
@@ -135,24 +217,32 @@ pub unsafe fn get_fields(index_relation: &PgRelation) -> (Fields, KeyFieldIndex)
             tokenizer: SearchTokenizer::Raw(SearchTokenizerFilters::default()),
             record: IndexRecordOption::Basic,
             normalizer: SearchNormalizer::Raw,
+            similarity: SearchSimilarity::default(),
+            index_nulls: false,
             column: None,
         },
         SearchFieldType::Range => SearchFieldConfig::Range {
             stored: false,
+            index_nulls: false,
             column: None,
         },
         SearchFieldType::Bool => SearchFieldConfig::Boolean {
             indexed: true,
             fast: true,
             stored: false,
+            index_nulls: false,
             column: None,
         },
         SearchFieldType::Date => SearchFieldConfig::Date {
             indexed: true,
             fast: true,
             stored: false,
+            index_nulls: false,
             column: None,
         },
+        SearchFieldType::Geo => panic!("key_field cannot be a point column"),
+        SearchFieldType::Inet => panic!("key_field cannot be an inet or cidr column"),
+        SearchFieldType::Bytes => panic!("key_field cannot be a bytea column"),
     };
 
     // Concatenate the separate lists of fields.
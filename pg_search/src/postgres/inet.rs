@@ -0,0 +1,87 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! inet and cidr values are indexed as tantivy IP address fields, which only hold IPv6
+//! addresses, so IPv4 addresses are stored as IPv4-mapped IPv6 addresses.  The netmask of a
+//! value isn't indexed.
+
+use std::net::{IpAddr, Ipv6Addr};
+
+/// The number of bits of an IPv6 address
+const IPV6_BITS: u32 = 128;
+
+/// The number of leading bits of an IPv4-mapped IPv6 address that aren't part of the IPv4 address
+const IPV4_MAPPED_PREFIX_BITS: u32 = 96;
+
+/// An address and its netmask, as Postgres prints an inet or cidr value
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct InetValue {
+    /// The address, IPv4 addresses being mapped to IPv6
+    pub addr: Ipv6Addr,
+    /// The number of bits of `addr`'s network, counted as an IPv6 address
+    pub prefix_len: u32,
+}
+
+impl InetValue {
+    /// Parse the `address[/prefix]` text of an inet or cidr value
+    pub fn parse(text: &str) -> Option<Self> {
+        let (addr, prefix_len) = match text.split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len.parse::<u32>().ok()?)),
+            None => (text, None),
+        };
+
+        match addr.parse::<IpAddr>().ok()? {
+            IpAddr::V4(v4) => {
+                let prefix_len = prefix_len.unwrap_or(IPV6_BITS - IPV4_MAPPED_PREFIX_BITS);
+                (prefix_len <= IPV6_BITS - IPV4_MAPPED_PREFIX_BITS).then_some(Self {
+                    addr: v4.to_ipv6_mapped(),
+                    prefix_len: prefix_len + IPV4_MAPPED_PREFIX_BITS,
+                })
+            }
+            IpAddr::V6(v6) => {
+                let prefix_len = prefix_len.unwrap_or(IPV6_BITS);
+                (prefix_len <= IPV6_BITS).then_some(Self {
+                    addr: v6,
+                    prefix_len,
+                })
+            }
+        }
+    }
+
+    /// Is this a single address, rather than a network of them?
+    pub fn is_host(&self) -> bool {
+        self.prefix_len == IPV6_BITS
+    }
+
+    /// The first and last addresses of this value's network
+    pub fn network_bounds(&self) -> (Ipv6Addr, Ipv6Addr) {
+        let host_mask = u128::MAX.checked_shr(self.prefix_len).unwrap_or(0);
+        let addr = u128::from(self.addr);
+        (
+            Ipv6Addr::from(addr & !host_mask),
+            Ipv6Addr::from(addr | host_mask),
+        )
+    }
+}
+
+/// The text Postgres would print for the indexed address `addr`
+pub fn ip_addr_to_string(addr: Ipv6Addr) -> String {
+    match addr.to_ipv4_mapped() {
+        Some(v4) => v4.to_string(),
+        None => addr.to_string(),
+    }
+}
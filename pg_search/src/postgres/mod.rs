@@ -29,11 +29,14 @@ mod validate;
 
 pub mod customscan;
 pub mod datetime;
+pub mod decimal;
 #[cfg(not(feature = "pg17"))]
 pub mod fake_aminsertcleanup;
 pub mod index;
+pub mod inet;
 mod parallel;
 pub mod storage;
+pub mod tsearch;
 pub mod types;
 pub mod utils;
 pub mod visibility_checker;
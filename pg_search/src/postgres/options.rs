@@ -24,7 +24,9 @@ use std::collections::HashMap;
 use std::ffi::CStr;
 use tokenizers::{manager::SearchTokenizerFilters, SearchNormalizer, SearchTokenizer};
 
-use crate::schema::{IndexRecordOption, SearchFieldConfig, SearchFieldName, SearchFieldType};
+use crate::schema::{
+    IndexRecordOption, SearchFieldConfig, SearchFieldName, SearchFieldType, SearchSimilarity,
+};
 
 /* ADDING OPTIONS
  * in init(), call pg_sys::add_{type}_reloption (check postgres docs for what args you need)
@@ -54,6 +56,13 @@ pub struct SearchIndexCreateOptions {
     json_fields_offset: i32,
     range_fields_offset: i32,
     datetime_fields_offset: i32,
+    completion_fields_offset: i32,
+    vector_fields_offset: i32,
+    sparse_vector_fields_offset: i32,
+    geo_fields_offset: i32,
+    inet_fields_offset: i32,
+    bytes_fields_offset: i32,
+    uuid_fields_offset: i32,
     key_field_offset: i32,
 }
 
@@ -129,6 +138,90 @@ extern "C" fn validate_datetime_fields(value: *const std::os::raw::c_char) {
     );
 }
 
+#[pg_guard]
+extern "C" fn validate_completion_fields(value: *const std::os::raw::c_char) {
+    let json_str = cstr_to_rust_str(value);
+    if json_str.is_empty() {
+        return;
+    }
+    SearchIndexCreateOptions::deserialize_config_fields(
+        json_str,
+        &SearchFieldConfig::completion_from_json,
+    );
+}
+
+#[pg_guard]
+extern "C" fn validate_vector_fields(value: *const std::os::raw::c_char) {
+    let json_str = cstr_to_rust_str(value);
+    if json_str.is_empty() {
+        return;
+    }
+    SearchIndexCreateOptions::deserialize_config_fields(
+        json_str,
+        &SearchFieldConfig::vector_from_json,
+    );
+}
+
+#[pg_guard]
+extern "C" fn validate_sparse_vector_fields(value: *const std::os::raw::c_char) {
+    let json_str = cstr_to_rust_str(value);
+    if json_str.is_empty() {
+        return;
+    }
+    SearchIndexCreateOptions::deserialize_config_fields(
+        json_str,
+        &SearchFieldConfig::sparse_vector_from_json,
+    );
+}
+
+#[pg_guard]
+extern "C" fn validate_geo_fields(value: *const std::os::raw::c_char) {
+    let json_str = cstr_to_rust_str(value);
+    if json_str.is_empty() {
+        return;
+    }
+    SearchIndexCreateOptions::deserialize_config_fields(
+        json_str,
+        &SearchFieldConfig::geo_from_json,
+    );
+}
+
+#[pg_guard]
+extern "C" fn validate_inet_fields(value: *const std::os::raw::c_char) {
+    let json_str = cstr_to_rust_str(value);
+    if json_str.is_empty() {
+        return;
+    }
+    SearchIndexCreateOptions::deserialize_config_fields(
+        json_str,
+        &SearchFieldConfig::inet_from_json,
+    );
+}
+
+#[pg_guard]
+extern "C" fn validate_bytes_fields(value: *const std::os::raw::c_char) {
+    let json_str = cstr_to_rust_str(value);
+    if json_str.is_empty() {
+        return;
+    }
+    SearchIndexCreateOptions::deserialize_config_fields(
+        json_str,
+        &SearchFieldConfig::bytes_from_json,
+    );
+}
+
+#[pg_guard]
+extern "C" fn validate_uuid_fields(value: *const std::os::raw::c_char) {
+    let json_str = cstr_to_rust_str(value);
+    if json_str.is_empty() {
+        return;
+    }
+    SearchIndexCreateOptions::deserialize_config_fields(
+        json_str,
+        &SearchFieldConfig::uuid_from_json,
+    );
+}
+
 #[pg_guard]
 extern "C" fn validate_fields(value: *const std::os::raw::c_char) {
     let json_str = cstr_to_rust_str(value);
@@ -158,7 +251,22 @@ fn cstr_to_rust_str(value: *const std::os::raw::c_char) -> String {
         .to_string()
 }
 
-const NUM_REL_OPTS: usize = 7;
+/// The kind of a completion, (sparse) vector or geo field config, and the types of column it can
+/// be built from
+fn special_field_kind(config: &SearchFieldConfig) -> (&'static str, &'static [SearchFieldType]) {
+    match config {
+        SearchFieldConfig::Completion { .. } => ("completion", &[SearchFieldType::Text]),
+        SearchFieldConfig::Vector { .. } => ("vector", &[SearchFieldType::F64]),
+        SearchFieldConfig::SparseVector { .. } => ("sparse vector", &[SearchFieldType::Json]),
+        SearchFieldConfig::Geo { .. } => ("geo", &[SearchFieldType::Geo, SearchFieldType::Json]),
+        SearchFieldConfig::Uuid { .. } => ("uuid", &[SearchFieldType::Text]),
+        _ => unreachable!(
+            "only completion, (sparse) vector, geo and uuid fields are configured separately"
+        ),
+    }
+}
+
+const NUM_REL_OPTS: usize = 14;
 #[pg_guard]
 pub unsafe extern "C" fn amoptions(
     reloptions: pg_sys::Datum,
@@ -195,6 +303,41 @@ pub unsafe extern "C" fn amoptions(
             opttype: pg_sys::relopt_type::RELOPT_TYPE_STRING,
             offset: offset_of!(SearchIndexCreateOptions, datetime_fields_offset) as i32,
         },
+        pg_sys::relopt_parse_elt {
+            optname: "completion_fields".as_pg_cstr(),
+            opttype: pg_sys::relopt_type::RELOPT_TYPE_STRING,
+            offset: offset_of!(SearchIndexCreateOptions, completion_fields_offset) as i32,
+        },
+        pg_sys::relopt_parse_elt {
+            optname: "vector_fields".as_pg_cstr(),
+            opttype: pg_sys::relopt_type::RELOPT_TYPE_STRING,
+            offset: offset_of!(SearchIndexCreateOptions, vector_fields_offset) as i32,
+        },
+        pg_sys::relopt_parse_elt {
+            optname: "sparse_vector_fields".as_pg_cstr(),
+            opttype: pg_sys::relopt_type::RELOPT_TYPE_STRING,
+            offset: offset_of!(SearchIndexCreateOptions, sparse_vector_fields_offset) as i32,
+        },
+        pg_sys::relopt_parse_elt {
+            optname: "geo_fields".as_pg_cstr(),
+            opttype: pg_sys::relopt_type::RELOPT_TYPE_STRING,
+            offset: offset_of!(SearchIndexCreateOptions, geo_fields_offset) as i32,
+        },
+        pg_sys::relopt_parse_elt {
+            optname: "inet_fields".as_pg_cstr(),
+            opttype: pg_sys::relopt_type::RELOPT_TYPE_STRING,
+            offset: offset_of!(SearchIndexCreateOptions, inet_fields_offset) as i32,
+        },
+        pg_sys::relopt_parse_elt {
+            optname: "bytes_fields".as_pg_cstr(),
+            opttype: pg_sys::relopt_type::RELOPT_TYPE_STRING,
+            offset: offset_of!(SearchIndexCreateOptions, bytes_fields_offset) as i32,
+        },
+        pg_sys::relopt_parse_elt {
+            optname: "uuid_fields".as_pg_cstr(),
+            opttype: pg_sys::relopt_type::RELOPT_TYPE_STRING,
+            offset: offset_of!(SearchIndexCreateOptions, uuid_fields_offset) as i32,
+        },
         pg_sys::relopt_parse_elt {
             optname: "key_field".as_pg_cstr(),
             opttype: pg_sys::relopt_type::RELOPT_TYPE_STRING,
@@ -296,6 +439,62 @@ impl SearchIndexCreateOptions {
         Self::deserialize_config_fields(config, &SearchFieldConfig::date_from_json)
     }
 
+    pub fn get_completion_fields(&self) -> Vec<(SearchFieldName, SearchFieldConfig)> {
+        let config = self.get_str(self.completion_fields_offset, "".to_string());
+        if config.is_empty() {
+            return Vec::new();
+        }
+        Self::deserialize_config_fields(config, &SearchFieldConfig::completion_from_json)
+    }
+
+    pub fn get_vector_fields(&self) -> Vec<(SearchFieldName, SearchFieldConfig)> {
+        let config = self.get_str(self.vector_fields_offset, "".to_string());
+        if config.is_empty() {
+            return Vec::new();
+        }
+        Self::deserialize_config_fields(config, &SearchFieldConfig::vector_from_json)
+    }
+
+    pub fn get_sparse_vector_fields(&self) -> Vec<(SearchFieldName, SearchFieldConfig)> {
+        let config = self.get_str(self.sparse_vector_fields_offset, "".to_string());
+        if config.is_empty() {
+            return Vec::new();
+        }
+        Self::deserialize_config_fields(config, &SearchFieldConfig::sparse_vector_from_json)
+    }
+
+    pub fn get_geo_fields(&self) -> Vec<(SearchFieldName, SearchFieldConfig)> {
+        let config = self.get_str(self.geo_fields_offset, "".to_string());
+        if config.is_empty() {
+            return Vec::new();
+        }
+        Self::deserialize_config_fields(config, &SearchFieldConfig::geo_from_json)
+    }
+
+    pub fn get_inet_fields(&self) -> Vec<(SearchFieldName, SearchFieldConfig)> {
+        let config = self.get_str(self.inet_fields_offset, "".to_string());
+        if config.is_empty() {
+            return Vec::new();
+        }
+        Self::deserialize_config_fields(config, &SearchFieldConfig::inet_from_json)
+    }
+
+    pub fn get_bytes_fields(&self) -> Vec<(SearchFieldName, SearchFieldConfig)> {
+        let config = self.get_str(self.bytes_fields_offset, "".to_string());
+        if config.is_empty() {
+            return Vec::new();
+        }
+        Self::deserialize_config_fields(config, &SearchFieldConfig::bytes_from_json)
+    }
+
+    pub fn get_uuid_fields(&self) -> Vec<(SearchFieldName, SearchFieldConfig)> {
+        let config = self.get_str(self.uuid_fields_offset, "".to_string());
+        if config.is_empty() {
+            return Vec::new();
+        }
+        Self::deserialize_config_fields(config, &SearchFieldConfig::uuid_from_json)
+    }
+
     fn json_value_to_search_field_config(
         field_type: &SearchFieldType,
         field_config: serde_json::Value,
@@ -309,6 +508,9 @@ impl SearchIndexCreateOptions {
             SearchFieldType::Json => SearchFieldConfig::json_from_json(field_config),
             SearchFieldType::Date => SearchFieldConfig::date_from_json(field_config),
             SearchFieldType::Range => SearchFieldConfig::range_from_json(field_config),
+            SearchFieldType::Geo => SearchFieldConfig::geo_from_json(field_config),
+            SearchFieldType::Inet => SearchFieldConfig::inet_from_json(field_config),
+            SearchFieldType::Bytes => SearchFieldConfig::bytes_from_json(field_config),
         }
         .expect("field config should be valid for SearchFieldConfig::{field_name}")
     }
@@ -358,6 +560,8 @@ impl SearchIndexCreateOptions {
                     indexed: true,
                     fast: true,
                     stored: true,
+                    index_nulls: false,
+                    scale: None,
                     column: None,
                 }
             }
@@ -369,6 +573,8 @@ impl SearchIndexCreateOptions {
                 tokenizer: SearchTokenizer::Raw(SearchTokenizerFilters::default()),
                 record: IndexRecordOption::Basic,
                 normalizer: SearchNormalizer::Raw,
+                similarity: SearchSimilarity::default(),
+                index_nulls: false,
                 column: None,
             },
             SearchFieldType::Json => SearchFieldConfig::Json {
@@ -380,24 +586,32 @@ impl SearchIndexCreateOptions {
                 tokenizer: SearchTokenizer::Raw(SearchTokenizerFilters::default()),
                 record: IndexRecordOption::Basic,
                 normalizer: SearchNormalizer::Raw,
+                similarity: SearchSimilarity::default(),
+                index_nulls: false,
                 column: None,
             },
             SearchFieldType::Range => SearchFieldConfig::Range {
                 stored: true,
+                index_nulls: false,
                 column: None,
             },
             SearchFieldType::Bool => SearchFieldConfig::Boolean {
                 indexed: true,
                 fast: true,
                 stored: true,
+                index_nulls: false,
                 column: None,
             },
             SearchFieldType::Date => SearchFieldConfig::Date {
                 indexed: true,
                 fast: true,
                 stored: true,
+                index_nulls: false,
                 column: None,
             },
+            SearchFieldType::Geo => panic!("key_field cannot be a point column"),
+            SearchFieldType::Inet => panic!("key_field cannot be an inet or cidr column"),
+            SearchFieldType::Bytes => panic!("key_field cannot be a bytea column"),
         };
 
         (key_field_name, key_field_config, *key_field_type)
@@ -418,6 +632,8 @@ impl SearchIndexCreateOptions {
             self.json_fields_offset,
             self.range_fields_offset,
             self.datetime_fields_offset,
+            self.inet_fields_offset,
+            self.bytes_fields_offset,
         ]
         .into_iter()
         .map(|offset| self.get_str(offset, "".to_string()))
@@ -429,6 +645,19 @@ impl SearchIndexCreateOptions {
         })
         .collect::<HashMap<_, _>>();
 
+        // completion and uuid fields are text columns, vector fields are float array columns,
+        // sparse vector fields are json columns, and geo fields are point or json columns, but
+        // they're all configured and indexed differently
+        let mut special_by_name = self
+            .get_completion_fields()
+            .into_iter()
+            .chain(self.get_vector_fields())
+            .chain(self.get_sparse_vector_fields())
+            .chain(self.get_geo_fields())
+            .chain(self.get_uuid_fields())
+            .map(|(name, config)| (name.0, config))
+            .collect::<HashMap<_, _>>();
+
         let num_index_attrs = unsafe { (*index_info).ii_NumIndexAttrs };
         let mut fields_by_name = (0..num_index_attrs)
             .map(|i| {
@@ -454,18 +683,33 @@ impl SearchIndexCreateOptions {
                     panic!("cannot override BM25 configuration for key_field '{column_name}', you must use an aliased field name and 'column' configuration key");
                 }
 
-                let json_config = config_by_name
-                    .remove(column_name)
-                    .unwrap_or_else(|| json!({}));
+                let config = match special_by_name.remove(column_name) {
+                    Some(config) => {
+                        let (kind, expected_types) = special_field_kind(&config);
+                        let is_uuid_column =
+                            base_oid == PgOid::BuiltIn(pg_sys::BuiltinOid::UUIDOID);
+                        if !expected_types.contains(&field_type)
+                            || (matches!(config, SearchFieldConfig::Uuid { .. }) && !is_uuid_column)
+                        {
+                            panic!("'{column_name}' cannot be indexed as a {kind} field");
+                        }
+                        config
+                    }
+                    None => {
+                        let json_config = config_by_name
+                            .remove(column_name)
+                            .unwrap_or_else(|| json!({}));
+                        Self::json_value_to_search_field_config(&field_type, json_config)
+                    }
+                };
+
+                if config.decimal_scale().is_some()
+                    && base_oid != PgOid::BuiltIn(pg_sys::BuiltinOid::NUMERICOID)
+                {
+                    panic!("'{column_name}' must be a numeric column to be indexed with a scale");
+                }
 
-                (
-                    column_name.to_string(),
-                    (
-                        column_name.into(),
-                        Self::json_value_to_search_field_config(&field_type, json_config),
-                        field_type,
-                    ),
-                )
+                (column_name.to_string(), (column_name.into(), config, field_type))
             })
             .collect::<HashMap<_, _>>();
 
@@ -498,6 +742,21 @@ impl SearchIndexCreateOptions {
             }
         }
 
+        for (name, config) in special_by_name {
+            let Some(column) = config.column().cloned() else {
+                panic!("Field '{name}' does not match any column, and has no 'column' key")
+            };
+            let (kind, expected_types) = special_field_kind(&config);
+            match fields_by_name.get(&column) {
+                Some((_, _, field_type)) if expected_types.contains(field_type) => {
+                    let field_type = *field_type;
+                    fields_by_name
+                        .insert(name.clone(), (SearchFieldName(name), config, field_type));
+                }
+                _ => panic!("'{column}' cannot be indexed as a {kind} field"),
+            }
+        }
+
         fields_by_name.into_values().collect()
     }
 
@@ -569,6 +828,63 @@ pub unsafe fn init() {
         Some(validate_datetime_fields),
         pg_sys::AccessExclusiveLock as pg_sys::LOCKMODE,
     );
+    pg_sys::add_string_reloption(
+        RELOPT_KIND_PDB,
+        "completion_fields".as_pg_cstr(),
+        "JSON string specifying which text fields should be indexed for completion".as_pg_cstr(),
+        std::ptr::null(),
+        Some(validate_completion_fields),
+        pg_sys::AccessExclusiveLock as pg_sys::LOCKMODE,
+    );
+    pg_sys::add_string_reloption(
+        RELOPT_KIND_PDB,
+        "vector_fields".as_pg_cstr(),
+        "JSON string specifying which float array fields should be indexed as vectors".as_pg_cstr(),
+        std::ptr::null(),
+        Some(validate_vector_fields),
+        pg_sys::AccessExclusiveLock as pg_sys::LOCKMODE,
+    );
+    pg_sys::add_string_reloption(
+        RELOPT_KIND_PDB,
+        "sparse_vector_fields".as_pg_cstr(),
+        "JSON string specifying which json fields should be indexed as sparse vectors".as_pg_cstr(),
+        std::ptr::null(),
+        Some(validate_sparse_vector_fields),
+        pg_sys::AccessExclusiveLock as pg_sys::LOCKMODE,
+    );
+    pg_sys::add_string_reloption(
+        RELOPT_KIND_PDB,
+        "geo_fields".as_pg_cstr(),
+        "JSON string specifying which point or json fields should be indexed as geo points"
+            .as_pg_cstr(),
+        std::ptr::null(),
+        Some(validate_geo_fields),
+        pg_sys::AccessExclusiveLock as pg_sys::LOCKMODE,
+    );
+    pg_sys::add_string_reloption(
+        RELOPT_KIND_PDB,
+        "inet_fields".as_pg_cstr(),
+        "JSON string specifying how inet and cidr fields should be indexed".as_pg_cstr(),
+        std::ptr::null(),
+        Some(validate_inet_fields),
+        pg_sys::AccessExclusiveLock as pg_sys::LOCKMODE,
+    );
+    pg_sys::add_string_reloption(
+        RELOPT_KIND_PDB,
+        "bytes_fields".as_pg_cstr(),
+        "JSON string specifying how bytea fields should be indexed".as_pg_cstr(),
+        std::ptr::null(),
+        Some(validate_bytes_fields),
+        pg_sys::AccessExclusiveLock as pg_sys::LOCKMODE,
+    );
+    pg_sys::add_string_reloption(
+        RELOPT_KIND_PDB,
+        "uuid_fields".as_pg_cstr(),
+        "JSON string specifying which uuid fields should be indexed as 128-bit values".as_pg_cstr(),
+        std::ptr::null(),
+        Some(validate_uuid_fields),
+        pg_sys::AccessExclusiveLock as pg_sys::LOCKMODE,
+    );
     pg_sys::add_string_reloption(
         RELOPT_KIND_PDB,
         "key_field".as_pg_cstr(),
@@ -1,6 +1,7 @@
 use crate::postgres::types::{TantivyValue, TantivyValueError};
 use crate::schema::range::TantivyRangeBuilder;
 use pgrx::datum::{Date, DateTimeConversionError, RangeBound, Timestamp, TimestampWithTimeZone};
+use pgrx::{pg_sys, PgOid};
 use serde::{Deserialize, Serialize};
 
 // When Tantivy reads JSON objects, it only recognizes RFC 3339 formatted strings as DateTime values.
@@ -100,3 +101,55 @@ impl RangeToTantivyValue<pgrx::AnyNumeric, f64> for TantivyValue {}
 impl RangeToTantivyValue<Date, TimestampWithTimeZoneUtc> for TantivyValue {}
 impl RangeToTantivyValue<Timestamp, TimestampWithTimeZoneUtc> for TantivyValue {}
 impl RangeToTantivyValue<TimestampWithTimeZone, TimestampWithTimeZone> for TantivyValue {}
+
+/// The type of the ranges contained in a builtin multirange type, or `None` if `typeoid` isn't one
+pub fn multirange_range_typeoid(typeoid: PgOid) -> Option<PgOid> {
+    #[cfg(not(feature = "pg13"))]
+    {
+        use pgrx::PgBuiltInOids;
+
+        let range = match typeoid {
+            PgOid::BuiltIn(PgBuiltInOids::INT4MULTIRANGEOID) => PgBuiltInOids::INT4RANGEOID,
+            PgOid::BuiltIn(PgBuiltInOids::INT8MULTIRANGEOID) => PgBuiltInOids::INT8RANGEOID,
+            PgOid::BuiltIn(PgBuiltInOids::NUMMULTIRANGEOID) => PgBuiltInOids::NUMRANGEOID,
+            PgOid::BuiltIn(PgBuiltInOids::DATEMULTIRANGEOID) => PgBuiltInOids::DATERANGEOID,
+            PgOid::BuiltIn(PgBuiltInOids::TSMULTIRANGEOID) => PgBuiltInOids::TSRANGEOID,
+            PgOid::BuiltIn(PgBuiltInOids::TSTZMULTIRANGEOID) => PgBuiltInOids::TSTZRANGEOID,
+            _ => return None,
+        };
+        Some(PgOid::BuiltIn(range))
+    }
+
+    #[cfg(feature = "pg13")]
+    {
+        let _ = typeoid;
+        None
+    }
+}
+
+/// Split a multirange datum of type `typeoid` into the datums of the ranges it contains
+///
+/// # Safety
+///
+/// `datum` must be a non-NULL multirange of type `typeoid`
+pub unsafe fn multirange_ranges(datum: pg_sys::Datum, typeoid: pg_sys::Oid) -> Vec<pg_sys::Datum> {
+    #[cfg(not(feature = "pg13"))]
+    {
+        let multirange =
+            pg_sys::pg_detoast_datum(datum.cast_mut_ptr()).cast::<pg_sys::MultirangeType>();
+        let typcache = pg_sys::lookup_type_cache(typeoid, pg_sys::TYPECACHE_MULTIRANGE_INFO as i32);
+        let mut count = 0;
+        let mut ranges = std::ptr::null_mut();
+        pg_sys::multirange_deserialize((*typcache).rngtype, multirange, &mut count, &mut ranges);
+
+        (0..count as usize)
+            .map(|i| pg_sys::Datum::from(*ranges.add(i)))
+            .collect()
+    }
+
+    #[cfg(feature = "pg13")]
+    {
+        let _ = (datum, typeoid);
+        unreachable!("multiranges are not supported before Postgres 14")
+    }
+}
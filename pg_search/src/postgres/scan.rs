@@ -69,6 +69,7 @@ pub extern "C" fn amrescan(
                     query_string,
                     lenient: None,
                     conjunction_mode: None,
+                    minimum_should_match: None,
                 }
             },
             ScanStrategy::SearchQueryInput => unsafe {
@@ -100,6 +101,7 @@ pub extern "C" fn amrescan(
             must: vec![search_query_input, key],
             should: vec![],
             must_not: vec![],
+            minimum_should_match: None,
         };
     }
 
@@ -0,0 +1,410 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Interoperability with Postgres' own full text search types.
+//!
+//! A `tsvector` is indexed as pre-tokenized text, its lexemes becoming the field's terms at their
+//! original positions, and a `tsquery` is translated into the equivalent [`SearchQueryInput`] over
+//! those terms.  Lexemes are matched exactly, without being tokenized again, and the weights of
+//! both are ignored as they aren't indexed.
+
+use crate::query::SearchQueryInput;
+use pgrx::{direct_function_call, pg_sys};
+use std::ffi::CStr;
+use std::iter::Peekable;
+use std::str::Chars;
+use tantivy::schema::OwnedValue;
+use tantivy::tokenizer::{PreTokenizedString, Token};
+use thiserror::Error;
+
+/// The text Postgres prints for the `tsvector` datum `datum`
+pub unsafe fn tsvector_text(datum: pg_sys::Datum) -> String {
+    direct_function_call::<&CStr>(pg_sys::tsvectorout, &[Some(datum)])
+        .expect("tsvectorout should not return NULL")
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// The text Postgres prints for the `tsquery` datum `datum`
+pub unsafe fn tsquery_text(datum: pg_sys::Datum) -> String {
+    direct_function_call::<&CStr>(pg_sys::tsqueryout, &[Some(datum)])
+        .expect("tsqueryout should not return NULL")
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Convert the printed form of a `tsvector`, like `'cat':3 'fat':2,4A`, into the tokens it's
+/// indexed as.  Postgres positions start at 1, so each is indexed one lower.  Lexemes without
+/// positions are indexed after all the others, two positions apart from each other, so that
+/// they're never adjacent to another lexeme.  Returns [`None`] if `text` isn't a valid `tsvector`.
+pub fn tsvector_value(text: &str) -> Option<OwnedValue> {
+    let mut chars = text.chars().peekable();
+    let mut tokens = vec![];
+    let mut unpositioned = vec![];
+
+    loop {
+        skip_whitespace(&mut chars);
+        if chars.peek().is_none() {
+            break;
+        }
+
+        let lexeme = read_quoted(&mut chars)?;
+        if chars.next_if_eq(&':').is_none() {
+            unpositioned.push(lexeme);
+            continue;
+        }
+        loop {
+            let position = read_number(&mut chars)?;
+            chars.next_if(|c| matches!(c, 'A'..='D'));
+            tokens.push(lexeme_token(
+                lexeme.clone(),
+                position.checked_sub(1)? as usize,
+            ));
+            if chars.next_if_eq(&',').is_none() {
+                break;
+            }
+        }
+    }
+
+    let first_unpositioned = tokens
+        .iter()
+        .map(|token| token.position + 2)
+        .max()
+        .unwrap_or(0);
+    tokens.extend(
+        unpositioned
+            .into_iter()
+            .enumerate()
+            .map(|(i, lexeme)| lexeme_token(lexeme, first_unpositioned + 2 * i)),
+    );
+
+    // tantivy expects each term's positions in increasing order
+    tokens.sort_by_key(|token| token.position);
+
+    Some(OwnedValue::PreTokStr(PreTokenizedString {
+        text: text.to_string(),
+        tokens,
+    }))
+}
+
+fn lexeme_token(lexeme: String, position: usize) -> Token {
+    Token {
+        offset_from: 0,
+        offset_to: 0,
+        position,
+        text: lexeme,
+        position_length: 1,
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum TsQueryError {
+    #[error("'{0}' is not a valid tsquery")]
+    Syntax(String),
+
+    #[error("the operands of a tsquery phrase must be lexemes")]
+    PhraseOperand,
+
+    #[error("the lexemes of a tsquery phrase must be adjacent, with <->, but found <{0}>")]
+    PhraseDistance(u32),
+
+    #[error("only the last lexeme of a tsquery phrase can be a prefix, but found '{0}'")]
+    PhrasePrefix(String),
+}
+
+/// A parsed `tsquery`
+#[derive(Debug, Clone, PartialEq)]
+enum TsQuery {
+    /// A lexeme, matching every lexeme it's a prefix of if `prefix` is set, as with `'supern':*`
+    Lexeme { lexeme: String, prefix: bool },
+    /// `!query`
+    Not(Box<TsQuery>),
+    /// `left & right`
+    And(Box<TsQuery>, Box<TsQuery>),
+    /// `left | right`
+    Or(Box<TsQuery>, Box<TsQuery>),
+    /// `left <distance> right`, which `<->` is the same as with a distance of 1
+    Phrase {
+        left: Box<TsQuery>,
+        right: Box<TsQuery>,
+        distance: u32,
+    },
+}
+
+/// Translate the printed form of a `tsquery`, like `'fat' & ( 'rat' | 'cat' )`, into the query of
+/// the field `field` that matches the same documents.
+///
+/// `&` and `|` become [`SearchQueryInput::Boolean`] queries, `!` a `must_not` clause, and a phrase
+/// of adjacent lexemes a [`SearchQueryInput::Phrase`].  Phrases whose lexemes are some other
+/// distance apart, like `'fat' <2> 'cat'`, are rejected.  A prefix lexeme becomes a
+/// [`SearchQueryInput::PhrasePrefix`], either on its own or ending a phrase.
+pub fn tsquery_to_search_query_input(
+    field: &str,
+    text: &str,
+) -> Result<SearchQueryInput, TsQueryError> {
+    let mut parser = TsQueryParser {
+        chars: text.chars().peekable(),
+    };
+
+    parser.skip_whitespace();
+    if parser.chars.peek().is_none() {
+        // Postgres prints a tsquery without lexemes, like one of only stopwords, as nothing
+        return Ok(SearchQueryInput::Empty);
+    }
+
+    let query = parser
+        .parse_or()
+        .ok_or_else(|| TsQueryError::Syntax(text.to_string()))?;
+    parser.skip_whitespace();
+    if parser.chars.peek().is_some() {
+        return Err(TsQueryError::Syntax(text.to_string()));
+    }
+
+    query.into_search_query_input(field)
+}
+
+impl TsQuery {
+    fn into_search_query_input(self, field: &str) -> Result<SearchQueryInput, TsQueryError> {
+        match self {
+            TsQuery::Lexeme {
+                lexeme,
+                prefix: false,
+            } => Ok(SearchQueryInput::Term {
+                field: Some(field.to_string()),
+                value: OwnedValue::Str(lexeme),
+                is_datetime: false,
+                is_numeric: false,
+            }),
+            TsQuery::Lexeme {
+                lexeme,
+                prefix: true,
+            } => Ok(SearchQueryInput::PhrasePrefix {
+                field: field.to_string(),
+                phrases: vec![lexeme],
+                max_expansions: None,
+            }),
+            TsQuery::Not(query) => Ok(SearchQueryInput::Boolean {
+                must: vec![SearchQueryInput::All],
+                should: vec![],
+                must_not: vec![query.into_search_query_input(field)?],
+                minimum_should_match: None,
+            }),
+            query @ TsQuery::And(..) => {
+                let mut must = vec![];
+                let mut must_not = vec![];
+                for operand in query.flatten_and() {
+                    match operand {
+                        TsQuery::Not(operand) => {
+                            must_not.push(operand.into_search_query_input(field)?)
+                        }
+                        operand => must.push(operand.into_search_query_input(field)?),
+                    }
+                }
+                if must.is_empty() {
+                    must.push(SearchQueryInput::All);
+                }
+                Ok(SearchQueryInput::Boolean {
+                    must,
+                    should: vec![],
+                    must_not,
+                    minimum_should_match: None,
+                })
+            }
+            query @ TsQuery::Or(..) => Ok(SearchQueryInput::Boolean {
+                must: vec![],
+                should: query
+                    .flatten_or()
+                    .into_iter()
+                    .map(|operand| operand.into_search_query_input(field))
+                    .collect::<Result<_, _>>()?,
+                must_not: vec![],
+                minimum_should_match: None,
+            }),
+            query @ TsQuery::Phrase { .. } => {
+                let mut lexemes = vec![];
+                query.flatten_phrase(&mut lexemes)?;
+
+                let (last, rest) = lexemes.split_last().expect("a phrase should have lexemes");
+                if let Some((lexeme, _)) = rest.iter().find(|(_, prefix)| *prefix) {
+                    return Err(TsQueryError::PhrasePrefix(format!("{lexeme}:*")));
+                }
+                let phrases = lexemes.iter().map(|(lexeme, _)| lexeme.clone()).collect();
+
+                if last.1 {
+                    Ok(SearchQueryInput::PhrasePrefix {
+                        field: field.to_string(),
+                        phrases,
+                        max_expansions: None,
+                    })
+                } else {
+                    Ok(SearchQueryInput::Phrase {
+                        field: field.to_string(),
+                        phrases,
+                        slop: None,
+                    })
+                }
+            }
+        }
+    }
+
+    fn flatten_and(self) -> Vec<TsQuery> {
+        match self {
+            TsQuery::And(left, right) => {
+                let mut operands = left.flatten_and();
+                operands.extend(right.flatten_and());
+                operands
+            }
+            query => vec![query],
+        }
+    }
+
+    fn flatten_or(self) -> Vec<TsQuery> {
+        match self {
+            TsQuery::Or(left, right) => {
+                let mut operands = left.flatten_or();
+                operands.extend(right.flatten_or());
+                operands
+            }
+            query => vec![query],
+        }
+    }
+
+    fn flatten_phrase(self, lexemes: &mut Vec<(String, bool)>) -> Result<(), TsQueryError> {
+        match self {
+            TsQuery::Lexeme { lexeme, prefix } => lexemes.push((lexeme, prefix)),
+            TsQuery::Phrase {
+                left,
+                right,
+                distance,
+            } => {
+                if distance != 1 {
+                    return Err(TsQueryError::PhraseDistance(distance));
+                }
+                left.flatten_phrase(lexemes)?;
+                right.flatten_phrase(lexemes)?;
+            }
+            _ => return Err(TsQueryError::PhraseOperand),
+        }
+        Ok(())
+    }
+}
+
+/// Parses `tsquery` text, whose operators bind tightest to loosest in the order `!`, `<->`, `&`
+/// and `|`
+struct TsQueryParser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl TsQueryParser<'_> {
+    fn skip_whitespace(&mut self) {
+        skip_whitespace(&mut self.chars);
+    }
+
+    fn parse_or(&mut self) -> Option<TsQuery> {
+        let mut query = self.parse_and()?;
+        loop {
+            self.skip_whitespace();
+            if self.chars.next_if_eq(&'|').is_none() {
+                return Some(query);
+            }
+            query = TsQuery::Or(Box::new(query), Box::new(self.parse_and()?));
+        }
+    }
+
+    fn parse_and(&mut self) -> Option<TsQuery> {
+        let mut query = self.parse_phrase()?;
+        loop {
+            self.skip_whitespace();
+            if self.chars.next_if_eq(&'&').is_none() {
+                return Some(query);
+            }
+            query = TsQuery::And(Box::new(query), Box::new(self.parse_phrase()?));
+        }
+    }
+
+    fn parse_phrase(&mut self) -> Option<TsQuery> {
+        let mut query = self.parse_unary()?;
+        loop {
+            self.skip_whitespace();
+            if self.chars.next_if_eq(&'<').is_none() {
+                return Some(query);
+            }
+            let distance = if self.chars.next_if_eq(&'-').is_some() {
+                1
+            } else {
+                read_number(&mut self.chars)?
+            };
+            self.chars.next_if_eq(&'>')?;
+            query = TsQuery::Phrase {
+                left: Box::new(query),
+                right: Box::new(self.parse_unary()?),
+                distance,
+            };
+        }
+    }
+
+    fn parse_unary(&mut self) -> Option<TsQuery> {
+        self.skip_whitespace();
+        match self.chars.peek()? {
+            '!' => {
+                self.chars.next();
+                Some(TsQuery::Not(Box::new(self.parse_unary()?)))
+            }
+            '(' => {
+                self.chars.next();
+                let query = self.parse_or()?;
+                self.skip_whitespace();
+                self.chars.next_if_eq(&')')?;
+                Some(query)
+            }
+            _ => {
+                let lexeme = read_quoted(&mut self.chars)?;
+                let mut prefix = false;
+                if self.chars.next_if_eq(&':').is_some() {
+                    prefix = self.chars.next_if_eq(&'*').is_some();
+                    while self.chars.next_if(|c| matches!(c, 'A'..='D')).is_some() {}
+                }
+                Some(TsQuery::Lexeme { lexeme, prefix })
+            }
+        }
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+/// Read a single-quoted lexeme, in which Postgres doubles both quotes and backslashes
+fn read_quoted(chars: &mut Peekable<Chars>) -> Option<String> {
+    chars.next_if_eq(&'\'')?;
+    let mut lexeme = String::new();
+    loop {
+        match chars.next()? {
+            '\'' if chars.next_if_eq(&'\'').is_none() => return Some(lexeme),
+            '\\' => lexeme.push(chars.next()?),
+            c => lexeme.push(c),
+        }
+    }
+}
+
+fn read_number(chars: &mut Peekable<Chars>) -> Option<u32> {
+    let mut digits = String::new();
+    while let Some(digit) = chars.next_if(|c| c.is_ascii_digit()) {
+        digits.push(digit);
+    }
+    digits.parse().ok()
+}
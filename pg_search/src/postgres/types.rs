@@ -16,7 +16,11 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use crate::postgres::datetime::{datetime_components_to_tantivy_date, MICROSECONDS_IN_SECOND};
-use crate::postgres::range::RangeToTantivyValue;
+use crate::postgres::decimal::{scale_decimal, unscale_decimal};
+use crate::postgres::inet::{ip_addr_to_string, InetValue};
+use crate::postgres::range::{multirange_range_typeoid, multirange_ranges, RangeToTantivyValue};
+use crate::postgres::tsearch::{tsvector_text, tsvector_value};
+use crate::schema::range::MULTIRANGE_ENTRY_KEY;
 use crate::schema::AnyEnum;
 use ordered_float::OrderedFloat;
 use pgrx::datum::datetime_support::DateTimeConversionError;
@@ -83,6 +87,12 @@ impl TantivyValue {
                         pgrx::datum::TimeWithTimeZone::try_from(self)?.into_datum()
                     }
                     PgBuiltInOids::UUIDOID => pgrx::datum::Uuid::try_from(self)?.into_datum(),
+                    // inet and cidr datums share a representation, so an inet datum can be
+                    // returned for a cidr column
+                    PgBuiltInOids::INETOID | PgBuiltInOids::CIDROID => {
+                        pgrx::Inet::try_from(self)?.into_datum()
+                    }
+                    PgBuiltInOids::BYTEAOID => Vec::<u8>::try_from(self)?.into_datum(),
                     _ => return Err(TantivyValueError::UnsupportedOid(oid.value())),
                 };
                 Ok(datum)
@@ -127,7 +137,10 @@ impl TantivyValue {
                 | PgBuiltInOids::TIMESTAMPTZOID
                 | PgBuiltInOids::TIMEOID
                 | PgBuiltInOids::TIMETZOID
-                | PgBuiltInOids::UUIDOID => {
+                | PgBuiltInOids::UUIDOID
+                | PgBuiltInOids::INETOID
+                | PgBuiltInOids::CIDROID
+                | PgBuiltInOids::BYTEAOID => {
                     let array: pgrx::Array<Datum> = pgrx::Array::from_datum(datum, false)
                         .ok_or(TantivyValueError::DatumDeref)?;
                     array
@@ -142,6 +155,97 @@ impl TantivyValue {
         }
     }
 
+    /// Convert a `numeric` datum into the `i64` of an exact decimal field with `scale` digits
+    /// after the point
+    pub unsafe fn try_from_datum_decimal(
+        datum: Datum,
+        oid: PgOid,
+        scale: u32,
+    ) -> Result<Self, TantivyValueError> {
+        match &oid {
+            PgOid::BuiltIn(PgBuiltInOids::NUMERICOID) => Self::try_from_decimal(
+                pgrx::AnyNumeric::from_datum(datum, false).ok_or(TantivyValueError::DatumDeref)?,
+                scale,
+            ),
+            _ => Err(TantivyValueError::UnsupportedOid(oid.value())),
+        }
+    }
+
+    pub unsafe fn try_from_datum_decimal_array(
+        datum: Datum,
+        oid: PgOid,
+        scale: u32,
+    ) -> Result<Vec<Self>, TantivyValueError> {
+        match &oid {
+            PgOid::BuiltIn(PgBuiltInOids::NUMERICOID) => {
+                let array: pgrx::Array<Datum> =
+                    pgrx::Array::from_datum(datum, false).ok_or(TantivyValueError::DatumDeref)?;
+                array
+                    .iter()
+                    .flatten()
+                    .map(|element_datum| Self::try_from_datum_decimal(element_datum, oid, scale))
+                    .collect()
+            }
+            _ => Err(TantivyValueError::UnsupportedArrayOid(oid.value())),
+        }
+    }
+
+    pub fn try_from_decimal(val: pgrx::AnyNumeric, scale: u32) -> Result<Self, TantivyValueError> {
+        let text = val.to_string();
+        match scale_decimal(&text, scale) {
+            Some(scaled) => Ok(TantivyValue(OwnedValue::I64(scaled))),
+            None => Err(TantivyValueError::InexactDecimal(text, scale)),
+        }
+    }
+
+    /// Convert the `i64` of an exact decimal field with `scale` digits after the point back into
+    /// the `numeric` it was indexed from
+    pub fn try_into_decimal(self, scale: u32) -> Result<pgrx::AnyNumeric, TantivyValueError> {
+        match self.0 {
+            OwnedValue::I64(scaled) => {
+                Ok(pgrx::AnyNumeric::from_str(&unscale_decimal(scaled, scale))?)
+            }
+            _ => Err(TantivyValueError::UnsupportedIntoConversion(
+                "numeric".to_string(),
+            )),
+        }
+    }
+
+    pub unsafe fn try_from_datum_uuid(datum: Datum, oid: PgOid) -> Result<Self, TantivyValueError> {
+        match &oid {
+            PgOid::BuiltIn(PgBuiltInOids::UUIDOID) => Ok(Self::from_uuid(
+                pgrx::Uuid::from_datum(datum, false).ok_or(TantivyValueError::DatumDeref)?,
+            )),
+            _ => Err(TantivyValueError::UnsupportedOid(oid.value())),
+        }
+    }
+
+    pub unsafe fn try_from_datum_uuid_array(
+        datum: Datum,
+        oid: PgOid,
+    ) -> Result<Vec<Self>, TantivyValueError> {
+        match &oid {
+            PgOid::BuiltIn(PgBuiltInOids::UUIDOID) => {
+                let array: pgrx::Array<Datum> =
+                    pgrx::Array::from_datum(datum, false).ok_or(TantivyValueError::DatumDeref)?;
+                array
+                    .iter()
+                    .flatten()
+                    .map(|element_datum| Self::try_from_datum_uuid(element_datum, oid))
+                    .collect()
+            }
+            _ => Err(TantivyValueError::UnsupportedArrayOid(oid.value())),
+        }
+    }
+
+    /// A uuid field stores each uuid as the 128-bit value of an IP address, so that it's compact
+    /// and sorts in the same order as postgres sorts uuids
+    pub fn from_uuid(val: pgrx::Uuid) -> Self {
+        TantivyValue(OwnedValue::IpAddr(std::net::Ipv6Addr::from(
+            *val.as_bytes(),
+        )))
+    }
+
     pub unsafe fn try_from_datum_json(
         datum: Datum,
         oid: PgOid,
@@ -226,6 +330,18 @@ impl TantivyValue {
                     pgrx::datum::Uuid::from_datum(datum, false)
                         .ok_or(TantivyValueError::DatumDeref)?,
                 ),
+                PgBuiltInOids::INETOID | PgBuiltInOids::CIDROID => TantivyValue::try_from(
+                    pgrx::Inet::from_datum(datum, false).ok_or(TantivyValueError::DatumDeref)?,
+                ),
+                PgBuiltInOids::BYTEAOID => TantivyValue::try_from(
+                    Vec::<u8>::from_datum(datum, false).ok_or(TantivyValueError::DatumDeref)?,
+                ),
+                PgBuiltInOids::TSVECTOROID => {
+                    let text = tsvector_text(datum);
+                    tsvector_value(&text)
+                        .map(TantivyValue)
+                        .ok_or(TantivyValueError::InvalidTsvector(text))
+                }
                 PgBuiltInOids::INT4RANGEOID => TantivyValue::from_range(
                     pgrx::datum::Range::<i32>::from_datum(datum, false)
                         .ok_or(TantivyValueError::DatumDeref)?,
@@ -269,6 +385,31 @@ impl TantivyValue {
         }
     }
 
+    /// Convert each range of a multirange into the same value [`TantivyValue::try_from_datum`]
+    /// produces for a range, keyed with its own JSON text under [`MULTIRANGE_ENTRY_KEY`]
+    pub unsafe fn try_from_datum_multirange(
+        datum: Datum,
+        oid: PgOid,
+    ) -> Result<Vec<Self>, TantivyValueError> {
+        let range_oid =
+            multirange_range_typeoid(oid).ok_or(TantivyValueError::UnsupportedOid(oid.value()))?;
+
+        multirange_ranges(datum, oid.value())
+            .into_iter()
+            .map(|range| {
+                let value = TantivyValue::try_from_datum(range, range_oid)?;
+                let serde_json::Value::Object(mut entry) = serde_json::to_value(value.0)? else {
+                    return Err(TantivyValueError::JsonDeserializeError);
+                };
+                let text = serde_json::to_string(&entry)?;
+                entry.insert(MULTIRANGE_ENTRY_KEY.into(), serde_json::Value::String(text));
+                Ok(TantivyValue(tantivy::schema::OwnedValue::from(
+                    serde_json::Value::Object(entry),
+                )))
+            })
+            .collect()
+    }
+
     pub unsafe fn try_from_anyelement(
         any_element: pgrx::AnyElement,
     ) -> Result<Self, TantivyValueError> {
@@ -295,6 +436,7 @@ impl fmt::Display for TantivyValue {
                 )
             }
             tantivy::schema::OwnedValue::Object(_) => write!(f, "json object"),
+            tantivy::schema::OwnedValue::IpAddr(ip) => write!(f, "{}", ip_addr_to_string(ip)),
             _ => panic!("tantivy owned value not supported"),
         }
     }
@@ -310,6 +452,7 @@ impl Hash for TantivyValue {
             tantivy::schema::OwnedValue::Bool(bool) => bool.hash(state),
             tantivy::schema::OwnedValue::Date(datetime) => datetime.hash(state),
             tantivy::schema::OwnedValue::Bytes(bytes) => bytes.hash(state),
+            tantivy::schema::OwnedValue::IpAddr(ip) => ip.hash(state),
             _ => panic!("tantivy owned value not supported"),
         }
     }
@@ -364,6 +507,14 @@ impl PartialOrd for TantivyValue {
                     None
                 }
             }
+            tantivy::schema::OwnedValue::IpAddr(ip) => {
+                if let tantivy::schema::OwnedValue::IpAddr(other_ip) = other.tantivy_schema_value()
+                {
+                    ip.partial_cmp(&other_ip)
+                } else {
+                    None
+                }
+            }
             _ => None,
         }
     }
@@ -898,6 +1049,8 @@ impl TryFrom<TantivyValue> for pgrx::Uuid {
             let uuid = uuid::Uuid::parse_str(&val)?;
             Ok(pgrx::Uuid::from_slice(uuid.as_bytes())
                 .map_err(TantivyValueError::UuidConversionError)?)
+        } else if let tantivy::schema::OwnedValue::IpAddr(val) = value.0 {
+            Ok(pgrx::Uuid::from_bytes(val.octets()))
         } else {
             Err(TantivyValueError::UnsupportedIntoConversion(
                 "uuid".to_string(),
@@ -962,10 +1115,23 @@ impl TryFrom<pgrx::pg_sys::ItemPointerData> for TantivyValue {
 impl TryFrom<pgrx::Inet> for TantivyValue {
     type Error = TantivyValueError;
 
-    fn try_from(_val: pgrx::Inet) -> Result<Self, Self::Error> {
-        Err(TantivyValueError::UnsupportedFromConversion(
-            "inet".to_string(),
-        ))
+    fn try_from(val: pgrx::Inet) -> Result<Self, Self::Error> {
+        let inet = InetValue::parse(&val.0).ok_or(TantivyValueError::InvalidInet(val.0))?;
+        Ok(TantivyValue(tantivy::schema::OwnedValue::IpAddr(inet.addr)))
+    }
+}
+
+impl TryFrom<TantivyValue> for pgrx::Inet {
+    type Error = TantivyValueError;
+
+    fn try_from(value: TantivyValue) -> Result<Self, Self::Error> {
+        if let tantivy::schema::OwnedValue::IpAddr(val) = value.0 {
+            Ok(pgrx::Inet(ip_addr_to_string(val)))
+        } else {
+            Err(TantivyValueError::UnsupportedIntoConversion(
+                "inet".to_string(),
+            ))
+        }
     }
 }
 
@@ -983,6 +1149,15 @@ pub enum TantivyValueError {
     #[error("Failed UUID conversion: {0}")]
     UuidConversionError(String),
 
+    #[error("'{0}' cannot be indexed exactly with a scale of {1}")]
+    InexactDecimal(String, u32),
+
+    #[error("'{0}' is not a valid inet or cidr value")]
+    InvalidInet(String),
+
+    #[error("'{0}' is not a valid tsvector value")]
+    InvalidTsvector(String),
+
     #[error("Could not dereference postgres datum")]
     DatumDeref,
 
@@ -16,13 +16,21 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 use crate::index::writer::index::IndexError;
+use crate::postgres::range::multirange_range_typeoid;
 use crate::postgres::types::TantivyValue;
-use crate::schema::{SearchDocument, SearchField, SearchIndexSchema};
+use crate::schema::completion::completion_value;
+use crate::schema::geo::{geo_value, GeoPoint};
+use crate::schema::sparse::sparse_vector_value;
+use crate::schema::vector::vector_value;
+use crate::schema::{
+    SearchDocument, SearchField, SearchFieldConfig, SearchFieldId, SearchIndexSchema,
+};
 use anyhow::{anyhow, Result};
 use chrono::{NaiveDate, NaiveTime};
 use pgrx::itemptr::{item_pointer_get_both, item_pointer_set_all};
 use pgrx::*;
 use std::str::FromStr;
+use tantivy::schema::OwnedValue;
 
 /// Finds and returns the `USING bm25` index on the specified relation with the
 /// highest OID, or [`None`] if there aren't any.
@@ -77,6 +85,18 @@ pub struct CategorizedFieldData {
     pub base_oid: PgOid,
     pub is_array: bool,
     pub is_json: bool,
+    pub is_multirange: bool,
+    pub is_completion: bool,
+    /// The column, and its type, that a completion field's weight is read from
+    pub completion_weight: Option<(usize, PgOid)>,
+    pub vector_dimensions: Option<usize>,
+    pub is_sparse_vector: bool,
+    pub is_geo: bool,
+    /// The scale of a field that indexes a `numeric` column as an exact decimal
+    pub decimal_scale: Option<u32>,
+    pub is_uuid: bool,
+    /// Where to record that the column is NULL, if the field indexes its nulls
+    pub nulls_field: Option<SearchFieldId>,
 }
 
 pub fn categorize_fields(
@@ -112,6 +132,39 @@ pub fn categorize_fields(
                 base_oid,
                 PgOid::BuiltIn(pg_sys::BuiltinOid::JSONBOID | pg_sys::BuiltinOid::JSONOID)
             );
+            let is_multirange = multirange_range_typeoid(base_oid).is_some();
+
+            let is_completion = matches!(search_field.config, SearchFieldConfig::Completion { .. });
+            let completion_weight = match &search_field.config {
+                SearchFieldConfig::Completion {
+                    weight: Some(weight),
+                    ..
+                } => {
+                    let column = schema
+                        .get_search_field(&weight.clone().into())
+                        .and_then(|weight_field| weight_field.config.column().cloned())
+                        .unwrap_or_else(|| weight.clone());
+                    tupdesc
+                        .iter()
+                        .enumerate()
+                        .find(|(_, attribute)| attribute.name() == column)
+                        .map(|(attno, attribute)| (attno, attribute.type_oid()))
+                }
+                _ => None,
+            };
+            let vector_dimensions = match search_field.config {
+                SearchFieldConfig::Vector { dimensions, .. } => Some(dimensions),
+                _ => None,
+            };
+            let is_sparse_vector =
+                matches!(search_field.config, SearchFieldConfig::SparseVector { .. });
+            let is_geo = matches!(search_field.config, SearchFieldConfig::Geo { .. });
+            let decimal_scale = search_field.config.decimal_scale();
+            let is_uuid = matches!(search_field.config, SearchFieldConfig::Uuid { .. });
+            let nulls_field = schema
+                .nulls_field()
+                .filter(|_| search_field.config.index_nulls())
+                .map(SearchFieldId);
 
             categorized_fields.push((
                 search_field.clone(),
@@ -120,6 +173,15 @@ pub fn categorize_fields(
                     base_oid,
                     is_array,
                     is_json,
+                    is_multirange,
+                    is_completion,
+                    completion_weight,
+                    vector_dimensions,
+                    is_sparse_vector,
+                    is_geo,
+                    decimal_scale,
+                    is_uuid,
+                    nulls_field,
                 },
             ));
         }
@@ -142,9 +204,20 @@ pub unsafe fn row_to_search_document(
             base_oid,
             is_array,
             is_json,
+            is_multirange,
+            is_completion,
+            completion_weight,
+            vector_dimensions,
+            is_sparse_vector,
+            is_geo,
+            decimal_scale,
+            is_uuid,
+            nulls_field,
         },
     ) in categorized_fields
     {
+        let weight_datum = completion_weight
+            .and_then(|(attno, oid)| (!*isnull.add(attno)).then(|| (*values.add(attno), oid)));
         let datum = *values.add(*attno);
         let isnull = *isnull.add(*attno);
 
@@ -153,10 +226,110 @@ pub unsafe fn row_to_search_document(
         }
 
         if isnull {
+            if let Some(nulls_field) = nulls_field {
+                document.insert(*nulls_field, OwnedValue::Str(search_field.name.0.clone()));
+            }
             continue;
         }
 
-        if *is_array {
+        if let Some(dimensions) = vector_dimensions {
+            let vector = if *is_array {
+                TantivyValue::try_from_datum_array(datum, *base_oid)?
+            } else {
+                vec![TantivyValue::try_from_datum(datum, *base_oid)?]
+            }
+            .into_iter()
+            .map(|value| match value.0 {
+                OwnedValue::F64(element) => Some(element as f32),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>();
+            match vector {
+                Some(vector) if vector.len() == *dimensions => {
+                    document.insert(search_field.id, vector_value(&vector));
+                }
+                _ => {
+                    return Err(IndexError::InvalidVector(
+                        search_field.name.0.clone(),
+                        *dimensions,
+                    ))
+                }
+            }
+        } else if *is_sparse_vector {
+            let vector = match base_oid {
+                PgOid::BuiltIn(pg_sys::BuiltinOid::JSONBOID) => {
+                    JsonB::from_datum(datum, false).map(|json| json.0)
+                }
+                _ => Json::from_datum(datum, false).map(|json| json.0),
+            };
+            match vector.as_ref().and_then(sparse_vector_value) {
+                Some(value) => document.insert(search_field.id, value),
+                None => return Err(IndexError::InvalidSparseVector(search_field.name.0.clone())),
+            }
+        } else if *is_geo {
+            // a geo point is either a `point`, whose x is its longitude and y its latitude, or a
+            // `{"lat": ..., "lon": ...}` json object
+            let point = match base_oid {
+                _ if *is_array => None,
+                PgOid::BuiltIn(pg_sys::BuiltinOid::POINTOID) => {
+                    pg_sys::Point::from_datum(datum, false)
+                        .and_then(|point| GeoPoint::new(point.y, point.x))
+                }
+                PgOid::BuiltIn(pg_sys::BuiltinOid::JSONBOID) => {
+                    JsonB::from_datum(datum, false).and_then(|json| GeoPoint::from_json(&json.0))
+                }
+                _ => Json::from_datum(datum, false).and_then(|json| GeoPoint::from_json(&json.0)),
+            };
+            match point {
+                Some(point) => document.insert(search_field.id, geo_value(point)),
+                None => return Err(IndexError::InvalidGeoPoint(search_field.name.0.clone())),
+            }
+        } else if *is_completion {
+            // each completion input is indexed as a single term, whether it's one of many
+            // array elements or the entire column
+            let values = if *is_array {
+                TantivyValue::try_from_datum_array(datum, *base_oid)?
+            } else {
+                vec![TantivyValue::try_from_datum(datum, *base_oid)?]
+            };
+            let weight = match weight_datum {
+                Some((weight_datum, weight_oid)) => {
+                    match TantivyValue::try_from_datum(weight_datum, weight_oid)?.0 {
+                        OwnedValue::I64(weight) => Some(weight as f64),
+                        OwnedValue::U64(weight) => Some(weight as f64),
+                        OwnedValue::F64(weight) => Some(weight),
+                        _ => None,
+                    }
+                }
+                None => None,
+            };
+            for value in values {
+                document.insert(
+                    search_field.id,
+                    completion_value(value.tantivy_schema_value(), weight),
+                );
+            }
+        } else if let Some(scale) = decimal_scale {
+            let values = if *is_array {
+                TantivyValue::try_from_datum_decimal_array(datum, *base_oid, *scale)?
+            } else {
+                vec![TantivyValue::try_from_datum_decimal(
+                    datum, *base_oid, *scale,
+                )?]
+            };
+            for value in values {
+                document.insert(search_field.id, value.tantivy_schema_value());
+            }
+        } else if *is_uuid {
+            let values = if *is_array {
+                TantivyValue::try_from_datum_uuid_array(datum, *base_oid)?
+            } else {
+                vec![TantivyValue::try_from_datum_uuid(datum, *base_oid)?]
+            };
+            for value in values {
+                document.insert(search_field.id, value.tantivy_schema_value());
+            }
+        } else if *is_array {
             for value in TantivyValue::try_from_datum_array(datum, *base_oid)? {
                 document.insert(search_field.id, value.tantivy_schema_value());
             }
@@ -164,6 +337,10 @@ pub unsafe fn row_to_search_document(
             for value in TantivyValue::try_from_datum_json(datum, *base_oid)? {
                 document.insert(search_field.id, value.tantivy_schema_value());
             }
+        } else if *is_multirange {
+            for value in TantivyValue::try_from_datum_multirange(datum, *base_oid)? {
+                document.insert(search_field.id, value.tantivy_schema_value());
+            }
         } else {
             document.insert(
                 search_field.id,
@@ -197,7 +374,7 @@ fn convert_pgrx_seconds_to_chrono(orig: f64) -> Result<(u32, u32, u32)> {
 }
 
 pub fn convert_pg_date_string(typeoid: PgOid, date_string: &str) -> tantivy::DateTime {
-    match typeoid {
+    match multirange_range_typeoid(typeoid).unwrap_or(typeoid) {
         PgOid::BuiltIn(PgBuiltInOids::DATEOID | PgBuiltInOids::DATERANGEOID) => {
             let d = pgrx::datum::Date::from_str(date_string)
                 .expect("must be valid postgres date format");
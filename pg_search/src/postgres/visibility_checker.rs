@@ -82,3 +82,47 @@ impl VisibilityChecker {
         }
     }
 }
+
+/// A [`VisibilityChecker`] that opens the heap relation itself and owns the slot it fetches
+/// tuples into, for code that only needs to know whether a ctid is visible to the active
+/// snapshot, ie while a query's [`tantivy::query::Weight`] is being built
+pub struct HeapVisibility {
+    checker: VisibilityChecker,
+    slot: *mut pg_sys::TupleTableSlot,
+    // held open for as long as we're fetching from it
+    _heaprel: pgrx::PgRelation,
+}
+
+impl Drop for HeapVisibility {
+    fn drop(&mut self) {
+        unsafe {
+            if !pg_sys::IsTransactionState() {
+                return;
+            }
+
+            pg_sys::ExecDropSingleTupleTableSlot(self.slot);
+        }
+    }
+}
+
+impl HeapVisibility {
+    pub fn open(heaprelid: pg_sys::Oid) -> Self {
+        unsafe {
+            let heaprel = pgrx::PgRelation::open(heaprelid);
+            Self {
+                checker: VisibilityChecker::with_rel_and_snap(
+                    heaprel.as_ptr(),
+                    pg_sys::GetActiveSnapshot(),
+                ),
+                slot: pg_sys::table_slot_create(heaprel.as_ptr(), std::ptr::null_mut()),
+                _heaprel: heaprel,
+            }
+        }
+    }
+
+    pub fn is_visible(&mut self, ctid: u64) -> bool {
+        self.checker
+            .exec_if_visible(ctid, self.slot, |_| ())
+            .is_some()
+    }
+}
@@ -0,0 +1,152 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::index::fast_fields_helper::FFType;
+use crate::postgres::types::TantivyValue;
+use crate::postgres::visibility_checker::HeapVisibility;
+use pgrx::pg_sys;
+use std::collections::HashMap;
+use tantivy::collector::{Collector, SegmentCollector};
+use tantivy::schema::OwnedValue;
+use tantivy::{DocAddress, DocId, Score, SegmentOrdinal, SegmentReader};
+
+/// The hits kept for each distinct value of the collapse field
+type Groups = HashMap<TantivyValue, Vec<(Score, DocAddress)>>;
+
+/// Collects the documents of a query that are among the `max_per_group` highest scoring ones
+/// with the same value in the fast field `field`, highest scoring first.  They keep their score,
+/// and documents without a value in `field` are grouped together.
+///
+/// Each segment keeps its own best hits per group as it's collected, and they're merged across
+/// segments at the end, so the query is only run once.  Only documents whose row in the heap
+/// relation `heaprelid` is visible to the active snapshot are grouped, so that dead rows can't
+/// take the place of live ones.
+pub struct CollapseCollector {
+    field: String,
+    max_per_group: usize,
+    heaprelid: pg_sys::Oid,
+}
+
+impl CollapseCollector {
+    pub fn new(field: String, max_per_group: usize, heaprelid: pg_sys::Oid) -> Self {
+        Self {
+            field,
+            max_per_group,
+            heaprelid,
+        }
+    }
+}
+
+/// Add `hit` to a group's `hits`, keeping only the `max_per_group` best ones.  Ties are broken by
+/// doc address, so what's kept doesn't depend on the order hits are added in.
+fn keep(hits: &mut Vec<(Score, DocAddress)>, hit: (Score, DocAddress), max_per_group: usize) {
+    let position = hits.partition_point(|kept| {
+        kept.0
+            .total_cmp(&hit.0)
+            .reverse()
+            .then_with(|| kept.1.cmp(&hit.1))
+            .is_lt()
+    });
+    if position < max_per_group {
+        hits.insert(position, hit);
+        hits.truncate(max_per_group);
+    }
+}
+
+impl Collector for CollapseCollector {
+    type Fruit = Vec<(Score, DocAddress)>;
+    type Child = CollapseSegmentCollector;
+
+    fn for_segment(
+        &self,
+        segment_ord: SegmentOrdinal,
+        segment_reader: &SegmentReader,
+    ) -> tantivy::Result<Self::Child> {
+        Ok(CollapseSegmentCollector {
+            max_per_group: self.max_per_group,
+            segment_ord,
+            ff: FFType::new(segment_reader.fast_fields(), &self.field),
+            ctids: FFType::new(segment_reader.fast_fields(), "ctid"),
+            visibility: HeapVisibility::open(self.heaprelid),
+            groups: Default::default(),
+            text: String::new(),
+        })
+    }
+
+    // the hits we keep for each group are the highest scoring ones
+    fn requires_scoring(&self) -> bool {
+        true
+    }
+
+    fn merge_fruits(&self, segment_fruits: Vec<Groups>) -> tantivy::Result<Self::Fruit> {
+        let mut groups = Groups::new();
+        for segment_groups in segment_fruits {
+            for (group, hits) in segment_groups {
+                let kept = groups.entry(group).or_default();
+                for hit in hits {
+                    keep(kept, hit, self.max_per_group);
+                }
+            }
+        }
+
+        let mut hits = groups.into_values().flatten().collect::<Vec<_>>();
+        hits.sort_unstable_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+        Ok(hits)
+    }
+}
+
+pub struct CollapseSegmentCollector {
+    max_per_group: usize,
+    segment_ord: SegmentOrdinal,
+    ff: FFType,
+    ctids: FFType,
+    visibility: HeapVisibility,
+    groups: Groups,
+    text: String,
+}
+
+impl SegmentCollector for CollapseSegmentCollector {
+    type Fruit = Groups;
+
+    fn collect(&mut self, doc: DocId, score: Score) {
+        if !self
+            .ctids
+            .as_u64(doc)
+            .is_some_and(|ctid| self.visibility.is_visible(ctid))
+        {
+            return;
+        }
+
+        let group = match &self.ff {
+            FFType::Text(_) => match self.ff.string(doc, &mut self.text) {
+                Some(()) => TantivyValue(OwnedValue::Str(self.text.clone())),
+                None => TantivyValue(OwnedValue::Null),
+            },
+            other => other.value(doc),
+        };
+        let hits = self.groups.entry(group).or_default();
+        keep(
+            hits,
+            (score, DocAddress::new(self.segment_ord, doc)),
+            self.max_per_group,
+        );
+    }
+
+    fn harvest(self) -> Self::Fruit {
+        self.groups
+    }
+}
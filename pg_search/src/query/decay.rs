@@ -0,0 +1,202 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::index::fast_fields_helper::FFType;
+use pgrx::PostgresEnum;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tantivy::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use tantivy::{DocId, DocSet, Score, SegmentReader, Term, COLLECT_BLOCK_BUFFER_LEN};
+
+/// The default multiplier applied to documents that are exactly `scale` away from the origin
+pub const DEFAULT_DECAY: f64 = 0.5;
+
+/// The shape of the curve used to decay a document's score as its value moves away from
+/// the origin.  These follow the same definitions as Elasticsearch's decay functions.
+///
+/// They're spelled in snake case in JSON, such as `"gauss"`, but the way SQL spells them, such as
+/// `"Gauss"`, is accepted too.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize, PostgresEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum DecayFunction {
+    #[default]
+    #[serde(alias = "Gauss")]
+    Gauss,
+    #[serde(alias = "Exp")]
+    Exp,
+    #[serde(alias = "Linear")]
+    Linear,
+}
+
+/// The parameters of a decay function, with `origin`, `scale`, and `offset` already converted
+/// into the units of the underlying fast field (microseconds for dates).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DecayParams {
+    pub function: DecayFunction,
+    pub origin: f64,
+    pub scale: f64,
+    pub offset: f64,
+    pub decay: f64,
+}
+
+impl DecayParams {
+    /// Compute the multiplier for a document whose fast field value is `value`
+    #[inline(always)]
+    pub fn multiplier(&self, value: f64) -> f64 {
+        let distance = ((value - self.origin).abs() - self.offset).max(0.0);
+        match self.function {
+            DecayFunction::Gauss => (self.decay.ln() * distance.powi(2) / self.scale.powi(2)).exp(),
+            DecayFunction::Exp => (self.decay.ln() * distance / self.scale).exp(),
+            DecayFunction::Linear => {
+                let s = self.scale / (1.0 - self.decay);
+                ((s - distance) / s).max(0.0)
+            }
+        }
+    }
+}
+
+/// Wraps a query and multiplies the score of each matching document by a decay function
+/// evaluated against the value of a numeric or date fast field.
+///
+/// Documents without a value for the field keep their original score.
+pub struct DecayQuery {
+    query: Box<dyn Query>,
+    field: String,
+    params: DecayParams,
+}
+
+impl DecayQuery {
+    pub fn new(query: Box<dyn Query>, field: String, params: DecayParams) -> Self {
+        Self {
+            query,
+            field,
+            params,
+        }
+    }
+}
+
+impl Clone for DecayQuery {
+    fn clone(&self) -> Self {
+        Self {
+            query: self.query.box_clone(),
+            field: self.field.clone(),
+            params: self.params,
+        }
+    }
+}
+
+impl fmt::Debug for DecayQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Decay(query={:?}, field={}, params={:?})",
+            self.query, self.field, self.params
+        )
+    }
+}
+
+impl Query for DecayQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        let weight = self.query.weight(enable_scoring)?;
+        if enable_scoring.is_scoring_enabled() {
+            Ok(Box::new(DecayWeight {
+                weight,
+                field: self.field.clone(),
+                params: self.params,
+            }))
+        } else {
+            Ok(weight)
+        }
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        self.query.query_terms(visitor)
+    }
+}
+
+struct DecayWeight {
+    weight: Box<dyn Weight>,
+    field: String,
+    params: DecayParams,
+}
+
+impl Weight for DecayWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        Ok(Box::new(DecayScorer {
+            scorer: self.weight.scorer(reader, boost)?,
+            ff: FFType::new(reader.fast_fields(), &self.field),
+            params: self.params,
+        }))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let underlying = self.weight.explain(reader, doc)?;
+        let ff = FFType::new(reader.fast_fields(), &self.field);
+        let multiplier = ff
+            .as_f64(doc)
+            .map(|value| self.params.multiplier(value))
+            .unwrap_or(1.0);
+        let mut explanation = Explanation::new_with_string(
+            format!("{:?} decay x{multiplier} of ...", self.params.function),
+            underlying.value() * multiplier as Score,
+        );
+        explanation.add_detail(underlying);
+        Ok(explanation)
+    }
+
+    fn count(&self, reader: &SegmentReader) -> tantivy::Result<u32> {
+        self.weight.count(reader)
+    }
+}
+
+struct DecayScorer {
+    scorer: Box<dyn Scorer>,
+    ff: FFType,
+    params: DecayParams,
+}
+
+impl DocSet for DecayScorer {
+    fn advance(&mut self) -> DocId {
+        self.scorer.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.scorer.seek(target)
+    }
+
+    fn fill_buffer(&mut self, buffer: &mut [DocId; COLLECT_BLOCK_BUFFER_LEN]) -> usize {
+        self.scorer.fill_buffer(buffer)
+    }
+
+    fn doc(&self) -> DocId {
+        self.scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorer.size_hint()
+    }
+}
+
+impl Scorer for DecayScorer {
+    fn score(&mut self) -> Score {
+        let score = self.scorer.score();
+        match self.ff.as_f64(self.scorer.doc()) {
+            Some(value) => score * self.params.multiplier(value) as Score,
+            None => score,
+        }
+    }
+}
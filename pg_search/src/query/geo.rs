@@ -0,0 +1,202 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::schema::geo::{read_geo, GeoPoint};
+use std::sync::Arc;
+use tantivy::columnar::BytesColumn;
+use tantivy::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, TERMINATED};
+
+/// The area a [`GeoQuery`] matches the points inside of
+#[derive(Debug, Clone, PartialEq)]
+pub enum GeoShape {
+    /// Within `distance` meters of `origin`
+    Distance { origin: GeoPoint, distance: f64 },
+    /// Between the latitudes of `top_left` and `bottom_right`, and from the longitude of
+    /// `top_left` east to the longitude of `bottom_right`, which wraps around the antimeridian
+    /// if `top_left` is further east
+    BoundingBox {
+        top_left: GeoPoint,
+        bottom_right: GeoPoint,
+    },
+    /// Inside the polygon whose vertices are `points`, in order
+    Polygon(Vec<GeoPoint>),
+}
+
+impl GeoShape {
+    pub fn contains(&self, point: &GeoPoint) -> bool {
+        match self {
+            GeoShape::Distance { origin, distance } => origin.distance(point) <= *distance,
+            GeoShape::BoundingBox {
+                top_left,
+                bottom_right,
+            } => {
+                let lat_matches = (bottom_right.lat..=top_left.lat).contains(&point.lat);
+                let lon_matches = if top_left.lon <= bottom_right.lon {
+                    (top_left.lon..=bottom_right.lon).contains(&point.lon)
+                } else {
+                    point.lon >= top_left.lon || point.lon <= bottom_right.lon
+                };
+                lat_matches && lon_matches
+            }
+            GeoShape::Polygon(points) => {
+                // count how many edges a ray cast east from the point crosses
+                let mut inside = false;
+                for (a, b) in points.iter().zip(points.iter().cycle().skip(1)) {
+                    if (a.lat > point.lat) != (b.lat > point.lat)
+                        && point.lon
+                            < (b.lon - a.lon) * (point.lat - a.lat) / (b.lat - a.lat) + a.lon
+                    {
+                        inside = !inside;
+                    }
+                }
+                inside
+            }
+        }
+    }
+}
+
+/// Matches the documents whose geo point in the geo field `field` is inside `shape`.  They all
+/// get the same score.
+///
+/// Every document of a segment is checked by reading its point back from the field's fast field,
+/// so this is best combined with more selective queries.
+#[derive(Debug, Clone)]
+pub struct GeoQuery {
+    field: String,
+    shape: Arc<GeoShape>,
+}
+
+impl GeoQuery {
+    pub fn new(field: String, shape: GeoShape) -> Self {
+        Self {
+            field,
+            shape: Arc::new(shape),
+        }
+    }
+}
+
+impl Query for GeoQuery {
+    fn weight(&self, _enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        Ok(Box::new(GeoWeight {
+            field: self.field.clone(),
+            shape: self.shape.clone(),
+        }))
+    }
+}
+
+struct GeoWeight {
+    field: String,
+    shape: Arc<GeoShape>,
+}
+
+impl GeoWeight {
+    fn geo_scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<GeoScorer> {
+        let mut scorer = GeoScorer {
+            points: reader.fast_fields().bytes(&self.field)?,
+            shape: self.shape.clone(),
+            bytes: vec![],
+            doc: 0,
+            max_doc: reader.max_doc(),
+            boost,
+        };
+        scorer.skip_unmatched();
+        Ok(scorer)
+    }
+}
+
+impl Weight for GeoWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        Ok(Box::new(self.geo_scorer(reader, boost)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer = self.geo_scorer(reader, 1.0)?;
+        if scorer.seek(doc) == doc {
+            Ok(Explanation::new("geo point in shape", scorer.score()))
+        } else {
+            Err(TantivyError::InvalidArgument(format!(
+                "Document #({doc}) does not match"
+            )))
+        }
+    }
+}
+
+struct GeoScorer {
+    points: Option<BytesColumn>,
+    shape: Arc<GeoShape>,
+    bytes: Vec<u8>,
+    doc: DocId,
+    max_doc: DocId,
+    boost: Score,
+}
+
+impl GeoScorer {
+    /// Advance to the first document, starting at the current one, whose point is in the shape
+    fn skip_unmatched(&mut self) -> DocId {
+        let Some(points) = &self.points else {
+            self.doc = TERMINATED;
+            return self.doc;
+        };
+
+        while self.doc < self.max_doc {
+            if let Some(ord) = points.term_ords(self.doc).next() {
+                self.bytes.clear();
+                if points.ord_to_bytes(ord, &mut self.bytes).is_ok()
+                    && read_geo(&self.bytes).is_some_and(|point| self.shape.contains(&point))
+                {
+                    return self.doc;
+                }
+            }
+            self.doc += 1;
+        }
+        self.doc = TERMINATED;
+        self.doc
+    }
+}
+
+impl DocSet for GeoScorer {
+    fn advance(&mut self) -> DocId {
+        if self.doc == TERMINATED {
+            return TERMINATED;
+        }
+        self.doc += 1;
+        self.skip_unmatched()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        if self.doc < target {
+            self.doc = target.min(self.max_doc);
+            self.skip_unmatched();
+        }
+        self.doc
+    }
+
+    fn doc(&self) -> DocId {
+        self.doc
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.max_doc
+    }
+}
+
+impl Scorer for GeoScorer {
+    fn score(&mut self) -> Score {
+        self.boost
+    }
+}
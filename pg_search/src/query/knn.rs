@@ -0,0 +1,253 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::index::fast_fields_helper::FFType;
+use crate::postgres::visibility_checker::HeapVisibility;
+use crate::schema::vector::read_vector;
+use pgrx::{pg_sys, PostgresEnum};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use tantivy::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, TERMINATED};
+
+/// How the similarity between the query vector and a document's vector is measured.  These
+/// follow the same definitions as Elasticsearch's dense vector similarities, so higher is
+/// always more similar.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize, PostgresEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorMetric {
+    /// The cosine of the angle between the vectors, rescaled from `[-1, 1]` to `[0, 1]`
+    #[default]
+    Cosine,
+    /// The dot product of the vectors, which is only meaningful for normalized vectors
+    Dot,
+    /// `1 / (1 + d²)`, where `d` is the euclidean distance between the vectors
+    L2,
+}
+
+impl VectorMetric {
+    pub fn similarity(&self, a: &[f32], b: &[f32]) -> f32 {
+        let dot = || a.iter().zip(b).map(|(a, b)| a * b).sum::<f32>();
+        match self {
+            VectorMetric::Cosine => {
+                let norms = a.iter().map(|a| a * a).sum::<f32>().sqrt()
+                    * b.iter().map(|b| b * b).sum::<f32>().sqrt();
+                if norms == 0.0 {
+                    0.0
+                } else {
+                    (1.0 + dot() / norms) / 2.0
+                }
+            }
+            VectorMetric::Dot => dot(),
+            VectorMetric::L2 => {
+                let distance = a.iter().zip(b).map(|(a, b)| (a - b).powi(2)).sum::<f32>();
+                1.0 / (1.0 + distance)
+            }
+        }
+    }
+}
+
+/// Matches the `k` documents, out of those matching `filter`, whose vector in the vector
+/// field `field` is the most similar to `vector`.  They're scored by that similarity.
+///
+/// Each segment's documents matching `filter` are compared against `vector` when the segment is
+/// searched, and its `k` nearest ones match.  Only documents whose row in the heap relation
+/// `heaprelid` is visible to the active snapshot are candidates, so that dead rows can't take
+/// the place of live ones.  Documents whose vector has a different number of dimensions than
+/// `vector` never match.
+///
+/// Narrowing each segment's nearest documents down to the `k` nearest of all is up to the
+/// collector, see [`crate::query::SearchQueryInput::knn`].
+pub struct KnnQuery {
+    field: String,
+    vector: Vec<f32>,
+    metric: VectorMetric,
+    k: usize,
+    filter: Box<dyn Query>,
+    heaprelid: pg_sys::Oid,
+}
+
+impl KnnQuery {
+    pub fn new(
+        field: String,
+        vector: Vec<f32>,
+        metric: VectorMetric,
+        k: usize,
+        filter: Box<dyn Query>,
+        heaprelid: pg_sys::Oid,
+    ) -> Self {
+        Self {
+            field,
+            vector,
+            metric,
+            k,
+            filter,
+            heaprelid,
+        }
+    }
+
+    /// The similarity of every visible document of `segment_reader` that matches `filter`
+    fn segment_candidates(
+        &self,
+        filter: &dyn Weight,
+        segment_reader: &SegmentReader,
+        visibility: &mut HeapVisibility,
+    ) -> tantivy::Result<Vec<(Score, DocId)>> {
+        let mut candidates = vec![];
+        let Some(vectors) = segment_reader.fast_fields().bytes(&self.field)? else {
+            return Ok(candidates);
+        };
+        let ctids = FFType::new(segment_reader.fast_fields(), "ctid");
+        let alive_bitset = segment_reader.alive_bitset();
+        let mut bytes = vec![];
+        let mut scorer = filter.scorer(segment_reader, 1.0)?;
+        let mut doc = scorer.doc();
+        while doc != TERMINATED {
+            let is_alive = alive_bitset.map_or(true, |bitset| bitset.is_alive(doc));
+            if let Some(ord) = vectors.term_ords(doc).next().filter(|_| is_alive) {
+                bytes.clear();
+                vectors.ord_to_bytes(ord, &mut bytes)?;
+                let vector = read_vector(&bytes);
+                if vector.len() == self.vector.len()
+                    && ctids
+                        .as_u64(doc)
+                        .is_some_and(|ctid| visibility.is_visible(ctid))
+                {
+                    let similarity = self.metric.similarity(&self.vector, &vector);
+                    candidates.push((similarity, doc));
+                }
+            }
+            doc = scorer.advance();
+        }
+        Ok(candidates)
+    }
+}
+
+impl Clone for KnnQuery {
+    fn clone(&self) -> Self {
+        Self {
+            field: self.field.clone(),
+            vector: self.vector.clone(),
+            metric: self.metric,
+            k: self.k,
+            filter: self.filter.box_clone(),
+            heaprelid: self.heaprelid,
+        }
+    }
+}
+
+impl fmt::Debug for KnnQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Knn(field={}, vector={:?}, metric={:?}, k={}, filter={:?})",
+            self.field, self.vector, self.metric, self.k, self.filter
+        )
+    }
+}
+
+impl Query for KnnQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        // the filter only picks the candidates, it doesn't add to their score
+        let filter = self.filter.weight(EnableScoring::Disabled {
+            schema: enable_scoring.schema(),
+            searcher_opt: enable_scoring.searcher(),
+        })?;
+        Ok(Box::new(KnnWeight {
+            query: self.clone(),
+            filter,
+        }))
+    }
+}
+
+struct KnnWeight {
+    query: KnnQuery,
+    filter: Box<dyn Weight>,
+}
+
+impl KnnWeight {
+    /// The `k` nearest documents of `reader`, in doc order
+    fn knn_scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<KnnScorer> {
+        let mut visibility = HeapVisibility::open(self.query.heaprelid);
+        let mut candidates =
+            self.query
+                .segment_candidates(self.filter.as_ref(), reader, &mut visibility)?;
+        candidates.sort_unstable_by(|a, b| b.0.total_cmp(&a.0));
+        candidates.truncate(self.query.k);
+
+        // scorers have to return their docs in order
+        let mut docs = candidates
+            .into_iter()
+            .map(|(similarity, doc)| (doc, similarity))
+            .collect::<Vec<_>>();
+        docs.sort_unstable_by_key(|(doc, _)| *doc);
+
+        Ok(KnnScorer {
+            docs,
+            cursor: 0,
+            boost,
+        })
+    }
+}
+
+impl Weight for KnnWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        Ok(Box::new(self.knn_scorer(reader, boost)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer = self.knn_scorer(reader, 1.0)?;
+        if scorer.seek(doc) == doc {
+            Ok(Explanation::new("vector similarity", scorer.score()))
+        } else {
+            Err(TantivyError::InvalidArgument(format!(
+                "Document #({doc}) does not match"
+            )))
+        }
+    }
+}
+
+struct KnnScorer {
+    docs: Vec<(DocId, Score)>,
+    cursor: usize,
+    boost: Score,
+}
+
+impl DocSet for KnnScorer {
+    fn advance(&mut self) -> DocId {
+        self.cursor += 1;
+        self.doc()
+    }
+
+    fn doc(&self) -> DocId {
+        self.docs
+            .get(self.cursor)
+            .map_or(TERMINATED, |(doc, _)| *doc)
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.docs.len() as u32
+    }
+}
+
+impl Scorer for KnnScorer {
+    fn score(&mut self) -> Score {
+        self.docs
+            .get(self.cursor)
+            .map_or(0.0, |(_, similarity)| similarity * self.boost)
+    }
+}
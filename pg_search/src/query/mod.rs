@@ -15,17 +15,38 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod collapse;
+mod decay;
+mod geo;
+mod knn;
+mod multi_match;
+mod multirange;
 mod range;
+mod score_filter;
+mod similarity;
+mod sparse;
 
+use crate::postgres::decimal::scale_decimal;
+use crate::postgres::range::multirange_range_typeoid;
 use crate::postgres::utils::convert_pg_date_string;
+use crate::query::decay::{DecayParams, DecayQuery, DEFAULT_DECAY};
+use crate::query::geo::{GeoQuery, GeoShape};
+use crate::query::knn::KnnQuery;
+use crate::query::multi_match::{multi_match_query, split_field_and_boost, MultiMatchField};
+use crate::query::multirange::{MultirangeQuery, RangeKey, RangePredicate};
 use crate::query::range::{Comparison, RangeField};
-use crate::schema::IndexRecordOption;
+use crate::query::score_filter::ScoreFilterQuery;
+use crate::query::sparse::SparseTermQuery;
+use crate::schema::geo::GeoPoint;
+use crate::schema::{IndexRecordOption, SearchFieldConfig, SearchSimilarity};
 use anyhow::Result;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use core::panic;
 use pgrx::{pg_sys, PgBuiltInOids, PgOid, PostgresType};
 use range::{deserialize_bound, serialize_bound};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, ops::Bound};
+use std::{collections::HashMap, net::Ipv6Addr, ops::Bound, str::FromStr};
 use tantivy::DateTime;
 use tantivy::{
     collector::DocSetCollector,
@@ -41,6 +62,13 @@ use tantivy::{
     Searcher, Term,
 };
 use thiserror::Error;
+use tokenizers::SearchTokenizer;
+
+pub use collapse::CollapseCollector;
+pub use decay::DecayFunction;
+pub use knn::VectorMetric;
+pub use multi_match::MultiMatchType;
+use similarity::{apply_similarity, parse_with_similarity};
 
 #[derive(Debug, PostgresType, Deserialize, Serialize, Clone, PartialEq, Default)]
 #[serde(rename_all = "snake_case")]
@@ -58,15 +86,39 @@ pub enum SearchQueryInput {
         #[serde(default)]
         #[serde(skip_serializing_if = "Vec::is_empty")]
         must_not: Vec<SearchQueryInput>,
+
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        minimum_should_match: Option<MinimumShouldMatch>,
     },
     Boost {
         query: Box<SearchQueryInput>,
         factor: f32,
     },
+    /// Keeps only the `max_per_group` highest scoring documents of `query` for each distinct
+    /// value of the fast field `field`.  Collapsing applies to all of a search's results, and is
+    /// done by a [`CollapseCollector`] as they're collected, rather than by the query itself.
+    Collapse {
+        field: String,
+        query: Box<SearchQueryInput>,
+        max_per_group: usize,
+    },
     ConstScore {
         query: Box<SearchQueryInput>,
         score: f32,
     },
+    /// Multiplies the score of `query` by a decay function of the distance between a numeric or
+    /// date fast field's value and `origin`.  For date fields, `scale` and `offset` are in seconds.
+    Decay {
+        field: String,
+        query: Box<SearchQueryInput>,
+        #[serde(default)]
+        function: DecayFunction,
+        origin: tantivy::schema::OwnedValue,
+        scale: f64,
+        offset: Option<f64>,
+        decay: Option<f64>,
+    },
     DisjunctionMax {
         disjuncts: Vec<SearchQueryInput>,
         tie_breaker: Option<f32>,
@@ -104,6 +156,50 @@ pub enum SearchQueryInput {
         prefix: Option<bool>,
         match_all_terms: Option<bool>,
     },
+    /// Matches the documents whose geo point in `field` is within `distance` meters of `origin`
+    GeoDistance {
+        field: String,
+        origin: GeoPoint,
+        distance: f64,
+    },
+    /// Matches the documents whose geo point in `field` is inside the box with the corners
+    /// `top_left` and `bottom_right`, which crosses the antimeridian if `top_left` is east of
+    /// `bottom_right`
+    GeoBoundingBox {
+        field: String,
+        top_left: GeoPoint,
+        bottom_right: GeoPoint,
+    },
+    /// Matches the documents whose geo point in `field` is inside the polygon with the vertices
+    /// `points`
+    GeoPolygon {
+        field: String,
+        points: Vec<GeoPoint>,
+    },
+    /// Matches the documents whose column for `field` is NULL.  `field` must be configured
+    /// with `index_nulls`.
+    IsNull {
+        field: String,
+    },
+    Knn {
+        field: String,
+        vector: Vec<f32>,
+        #[serde(default)]
+        metric: VectorMetric,
+        k: usize,
+        /// Only consider the documents matching this query, or every document if it's [`None`]
+        filter: Option<Box<SearchQueryInput>>,
+    },
+    Match {
+        field: String,
+        value: String,
+        /// Analyze `value` with this tokenizer instead of the field's own
+        tokenizer: Option<serde_json::Value>,
+        fuzziness: Option<Fuzziness>,
+        transposition_cost_one: Option<bool>,
+        prefix: Option<bool>,
+        conjunction_mode: Option<bool>,
+    },
     MoreLikeThis {
         min_doc_frequency: Option<u64>,
         max_doc_frequency: Option<u64>,
@@ -116,16 +212,35 @@ pub enum SearchQueryInput {
         document_fields: Option<Vec<(String, tantivy::schema::OwnedValue)>>,
         document_id: Option<tantivy::schema::OwnedValue>,
     },
+    MultiMatch {
+        fields: Vec<String>,
+        value: String,
+        #[serde(default)]
+        match_type: MultiMatchType,
+        tie_breaker: Option<f32>,
+        conjunction_mode: Option<bool>,
+    },
+    /// Gives `query` a name, so that `paradedb.matched_queries()` can report whether it matched
+    Named {
+        name: String,
+        query: Box<SearchQueryInput>,
+    },
     Parse {
         query_string: String,
         lenient: Option<bool>,
         conjunction_mode: Option<bool>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        minimum_should_match: Option<MinimumShouldMatch>,
     },
     ParseWithField {
         field: String,
         query_string: String,
         lenient: Option<bool>,
         conjunction_mode: Option<bool>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        minimum_should_match: Option<MinimumShouldMatch>,
     },
     Phrase {
         field: String,
@@ -151,6 +266,8 @@ pub enum SearchQueryInput {
         upper_bound: std::ops::Bound<tantivy::schema::OwnedValue>,
         #[serde(default)]
         is_datetime: bool,
+        #[serde(default)]
+        is_numeric: bool,
     },
     RangeContains {
         field: String,
@@ -213,11 +330,27 @@ pub enum SearchQueryInput {
         slop: Option<u32>,
         max_expansions: Option<u32>,
     },
+    /// Matches the documents of `query` whose score is above `lower_bound`
+    ScoreFilter {
+        #[serde(
+            serialize_with = "serialize_bound",
+            deserialize_with = "deserialize_bound"
+        )]
+        lower_bound: std::ops::Bound<f64>,
+        query: Box<SearchQueryInput>,
+    },
+    SparseVector {
+        field: String,
+        /// The weight of each token of the query's sparse vector
+        vector: Vec<(String, f32)>,
+    },
     Term {
         field: Option<String>,
         value: tantivy::schema::OwnedValue,
         #[serde(default)]
         is_datetime: bool,
+        #[serde(default)]
+        is_numeric: bool,
     },
     TermSet {
         terms: Vec<TermInput>,
@@ -235,21 +368,271 @@ impl SearchQueryInput {
                 must,
                 should,
                 must_not,
+                ..
             } => must
                 .iter()
                 .chain(should.iter())
                 .chain(must_not.iter())
                 .any(Self::contains_more_like_this),
             SearchQueryInput::Boost { query, .. } => Self::contains_more_like_this(query),
+            SearchQueryInput::Collapse { query, .. } => Self::contains_more_like_this(query),
             SearchQueryInput::ConstScore { query, .. } => Self::contains_more_like_this(query),
+            SearchQueryInput::Decay { query, .. } => Self::contains_more_like_this(query),
+            SearchQueryInput::ScoreFilter { query, .. } => Self::contains_more_like_this(query),
             SearchQueryInput::DisjunctionMax { disjuncts, .. } => {
                 disjuncts.iter().any(Self::contains_more_like_this)
             }
             SearchQueryInput::WithIndex { query, .. } => Self::contains_more_like_this(query),
             SearchQueryInput::MoreLikeThis { .. } => true,
+            SearchQueryInput::Named { query, .. } => Self::contains_more_like_this(query),
+            _ => false,
+        }
+    }
+
+    /// The field and `max_per_group` of the [`SearchQueryInput::Collapse`] in the query, if there
+    /// is one, which the search's results have to be collapsed by
+    pub fn collapse(&self) -> Option<(&str, usize)> {
+        match self {
+            SearchQueryInput::Collapse {
+                field,
+                max_per_group,
+                ..
+            } => Some((field, *max_per_group)),
+            SearchQueryInput::Boolean {
+                must,
+                should,
+                must_not,
+                ..
+            } => must
+                .iter()
+                .chain(should.iter())
+                .chain(must_not.iter())
+                .find_map(Self::collapse),
+            SearchQueryInput::Boost { query, .. } => query.collapse(),
+            SearchQueryInput::ConstScore { query, .. } => query.collapse(),
+            SearchQueryInput::Decay { query, .. } => query.collapse(),
+            SearchQueryInput::ScoreFilter { query, .. } => query.collapse(),
+            SearchQueryInput::DisjunctionMax { disjuncts, .. } => {
+                disjuncts.iter().find_map(Self::collapse)
+            }
+            SearchQueryInput::WithIndex { query, .. } => query.collapse(),
+            SearchQueryInput::Named { query, .. } => query.collapse(),
+            _ => None,
+        }
+    }
+
+    /// The `k` of the query if it's a [`SearchQueryInput::Knn`].  Each segment matches its own `k`
+    /// nearest documents, so the search's results have to be narrowed down to the `k` best.
+    pub fn knn(&self) -> Option<usize> {
+        match self {
+            SearchQueryInput::Knn { k, .. } => Some(*k),
+            SearchQueryInput::Boost { query, .. } => query.knn(),
+            SearchQueryInput::ConstScore { query, .. } => query.knn(),
+            SearchQueryInput::WithIndex { query, .. } => query.knn(),
+            SearchQueryInput::Named { query, .. } => query.knn(),
+            _ => None,
+        }
+    }
+
+    /// Whether the query checks the visibility of the heap's rows, and so can only run against
+    /// documents that were indexed from the heap
+    pub fn needs_heap(&self) -> bool {
+        match self {
+            SearchQueryInput::Boolean {
+                must,
+                should,
+                must_not,
+                ..
+            } => must
+                .iter()
+                .chain(should.iter())
+                .chain(must_not.iter())
+                .any(Self::needs_heap),
+            SearchQueryInput::Boost { query, .. } => Self::needs_heap(query),
+            SearchQueryInput::ConstScore { query, .. } => Self::needs_heap(query),
+            SearchQueryInput::Decay { query, .. } => Self::needs_heap(query),
+            SearchQueryInput::ScoreFilter { query, .. } => Self::needs_heap(query),
+            SearchQueryInput::DisjunctionMax { disjuncts, .. } => {
+                disjuncts.iter().any(Self::needs_heap)
+            }
+            SearchQueryInput::WithIndex { query, .. } => Self::needs_heap(query),
+            SearchQueryInput::Named { query, .. } => Self::needs_heap(query),
+            SearchQueryInput::Collapse { .. } | SearchQueryInput::Knn { .. } => true,
             _ => false,
         }
     }
+
+    /// The queries given a name with [`SearchQueryInput::Named`], in the order they appear
+    pub fn named_queries(&self) -> Vec<(String, SearchQueryInput)> {
+        let mut named = vec![];
+        self.collect_named_queries(&mut named);
+        named
+    }
+
+    fn collect_named_queries(&self, named: &mut Vec<(String, SearchQueryInput)>) {
+        match self {
+            SearchQueryInput::Named { name, query } => {
+                named.push((name.clone(), (**query).clone()));
+                query.collect_named_queries(named);
+            }
+            SearchQueryInput::Boolean {
+                must,
+                should,
+                must_not,
+                ..
+            } => {
+                for query in must.iter().chain(should.iter()).chain(must_not.iter()) {
+                    query.collect_named_queries(named);
+                }
+            }
+            SearchQueryInput::DisjunctionMax { disjuncts, .. } => {
+                for query in disjuncts {
+                    query.collect_named_queries(named);
+                }
+            }
+            SearchQueryInput::Boost { query, .. }
+            | SearchQueryInput::Collapse { query, .. }
+            | SearchQueryInput::ConstScore { query, .. }
+            | SearchQueryInput::Decay { query, .. }
+            | SearchQueryInput::ScoreFilter { query, .. }
+            | SearchQueryInput::WithIndex { query, .. } => query.collect_named_queries(named),
+            SearchQueryInput::Knn {
+                filter: Some(filter),
+                ..
+            } => filter.collect_named_queries(named),
+            _ => {}
+        }
+    }
+}
+
+/// The minimum number of `should` clauses that must match, either as an absolute count (`3`) or
+/// as a percentage of the number of `should` clauses (`"75%"`).  Negative values specify how
+/// many clauses are allowed to be missing instead.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(try_from = "serde_json::Value", into = "serde_json::Value")]
+pub enum MinimumShouldMatch {
+    Count(i64),
+    Percentage(f64),
+}
+
+impl MinimumShouldMatch {
+    /// Resolve to the number of clauses, out of `num_clauses`, that must match
+    pub fn resolve(&self, num_clauses: usize) -> usize {
+        let total = num_clauses as i64;
+        let required = match *self {
+            MinimumShouldMatch::Count(count) if count < 0 => total + count,
+            MinimumShouldMatch::Count(count) => count,
+            MinimumShouldMatch::Percentage(pct) if pct < 0.0 => {
+                total - (total as f64 * -pct / 100.0).floor() as i64
+            }
+            MinimumShouldMatch::Percentage(pct) => (total as f64 * pct / 100.0).floor() as i64,
+        };
+        required.clamp(0, total) as usize
+    }
+}
+
+impl FromStr for MinimumShouldMatch {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let parsed = match s.strip_suffix('%') {
+            Some(pct) => pct.trim().parse().map(MinimumShouldMatch::Percentage).ok(),
+            None => s.parse().map(MinimumShouldMatch::Count).ok(),
+        };
+        parsed.ok_or_else(|| {
+            anyhow::anyhow!("minimum_should_match must be an integer or a percentage, got '{s}'")
+        })
+    }
+}
+
+impl TryFrom<serde_json::Value> for MinimumShouldMatch {
+    type Error = anyhow::Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        match value {
+            serde_json::Value::Number(n) if n.is_i64() => {
+                Ok(MinimumShouldMatch::Count(n.as_i64().unwrap()))
+            }
+            serde_json::Value::String(s) => s.parse(),
+            other => Err(anyhow::anyhow!(
+                "minimum_should_match must be an integer or a percentage, got '{other}'"
+            )),
+        }
+    }
+}
+
+impl From<MinimumShouldMatch> for serde_json::Value {
+    fn from(value: MinimumShouldMatch) -> Self {
+        match value {
+            MinimumShouldMatch::Count(count) => count.into(),
+            MinimumShouldMatch::Percentage(pct) => format!("{pct}%").into(),
+        }
+    }
+}
+
+/// How many edits a term of a match query may be away from the terms it matches.  `Auto` picks
+/// the distance from the length of each term:  0 for up to 2 characters, 1 for up to 5, and 2
+/// for anything longer.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(try_from = "serde_json::Value", into = "serde_json::Value")]
+pub enum Fuzziness {
+    Auto,
+    Distance(u8),
+}
+
+impl Fuzziness {
+    /// The edit distance to use for `term`
+    pub fn distance(&self, term: &str) -> u8 {
+        match *self {
+            Fuzziness::Auto => match term.chars().count() {
+                0..=2 => 0,
+                3..=5 => 1,
+                _ => 2,
+            },
+            Fuzziness::Distance(distance) => distance,
+        }
+    }
+}
+
+impl FromStr for Fuzziness {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("auto") {
+            return Ok(Fuzziness::Auto);
+        }
+        match s.parse::<u8>() {
+            Ok(distance) if distance <= 2 => Ok(Fuzziness::Distance(distance)),
+            _ => Err(anyhow::anyhow!(
+                "fuzziness must be 'auto' or a distance between 0 and 2, got '{s}'"
+            )),
+        }
+    }
+}
+
+impl TryFrom<serde_json::Value> for Fuzziness {
+    type Error = anyhow::Error;
+
+    fn try_from(value: serde_json::Value) -> Result<Self, Self::Error> {
+        match value {
+            serde_json::Value::Number(n) => n.to_string().parse(),
+            serde_json::Value::String(s) => s.parse(),
+            other => Err(anyhow::anyhow!(
+                "fuzziness must be 'auto' or a distance between 0 and 2, got '{other}'"
+            )),
+        }
+    }
+}
+
+impl From<Fuzziness> for serde_json::Value {
+    fn from(value: Fuzziness) -> Self {
+        match value {
+            Fuzziness::Auto => "auto".into(),
+            Fuzziness::Distance(distance) => distance.into(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -258,6 +641,8 @@ pub struct TermInput {
     pub value: tantivy::schema::OwnedValue,
     #[serde(default)]
     pub is_datetime: bool,
+    #[serde(default)]
+    pub is_numeric: bool,
 }
 
 impl TryFrom<SearchQueryInput> for TermInput {
@@ -269,10 +654,12 @@ impl TryFrom<SearchQueryInput> for TermInput {
                 field,
                 value,
                 is_datetime,
+                is_numeric,
             } => Ok(TermInput {
                 field: field.expect("field string must not be empty"),
                 value,
                 is_datetime,
+                is_numeric,
             }),
             _ => Err("Only Term variants can be converted to TermInput"),
         }
@@ -287,6 +674,21 @@ pub trait AsFieldType<T> {
 
     fn as_field_type(&self, from: &T) -> Option<(FieldType, PgOid, Field)>;
 
+    /// The field that records whether the column of `from` is NULL, if `from` indexes its nulls
+    fn as_nulls_field(&self, from: &T) -> Option<Field>;
+
+    /// The scale of `from`, if it indexes a `numeric` column as an exact decimal
+    fn as_decimal_scale(&self, from: &T) -> Option<u32>;
+
+    /// The fields that are scored with a similarity other than tantivy's BM25
+    fn similarities(&self) -> HashMap<Field, SearchSimilarity>;
+
+    /// How `from` is configured in the index
+    fn as_field_config(&self, from: &T) -> Option<SearchFieldConfig>;
+
+    /// The table the index belongs to, for queries that check the visibility of its rows
+    fn heap_relation_oid(&self) -> pg_sys::Oid;
+
     fn is_field_type(&self, from: &T, value: &OwnedValue) -> bool {
         matches!(
             (self.as_field_type(from), value),
@@ -380,7 +782,7 @@ pub trait AsFieldType<T> {
 
 fn is_datetime_typeoid(typeoid: PgOid) -> bool {
     matches!(
-        typeoid,
+        multirange_range_typeoid(typeoid).unwrap_or(typeoid),
         PgOid::BuiltIn(
             PgBuiltInOids::DATEOID
                 | PgBuiltInOids::DATERANGEOID
@@ -394,11 +796,52 @@ fn is_datetime_typeoid(typeoid: PgOid) -> bool {
     )
 }
 
+/// The value a query looks for in a field.  A `numeric` is passed as its exact decimal text, so
+/// it's only an `f64` for fields that aren't exact decimals, while an exact decimal field looks
+/// for any number, or decimal text, as the integer it indexes it as.
+fn numeric_value(
+    value: OwnedValue,
+    is_numeric: bool,
+    decimal_scale: Option<u32>,
+) -> Result<OwnedValue> {
+    let text = match (&value, decimal_scale) {
+        (OwnedValue::Str(text), Some(_)) => text.clone(),
+        (OwnedValue::Str(text), None) if is_numeric => {
+            return Ok(OwnedValue::F64(text.parse()?));
+        }
+        (OwnedValue::I64(n), Some(_)) => n.to_string(),
+        (OwnedValue::U64(n), Some(_)) => n.to_string(),
+        (OwnedValue::F64(n), Some(_)) => n.to_string(),
+        _ => return Ok(value),
+    };
+
+    let scale = decimal_scale.expect("only decimal values should be scaled");
+    match scale_decimal(&text, scale) {
+        Some(scaled) => Ok(OwnedValue::I64(scaled)),
+        None => Err(QueryError::InexactDecimal(text, scale).into()),
+    }
+}
+
+/// A uuid field indexes each uuid as the 128-bit value of an IP address, so a uuid that's passed
+/// as text is looked for as that address instead.
+fn uuid_value(value: OwnedValue, field_type: &FieldType, typeoid: PgOid) -> Result<OwnedValue> {
+    match (&value, field_type, typeoid) {
+        (OwnedValue::Str(text), FieldType::IpAddr(_), PgOid::BuiltIn(PgBuiltInOids::UUIDOID)) => {
+            Ok(OwnedValue::IpAddr(Ipv6Addr::from(
+                uuid::Uuid::parse_str(text)?.as_u128(),
+            )))
+        }
+        _ => Ok(value),
+    }
+}
+
 fn check_range_bounds(
     typeoid: PgOid,
     lower_bound: Bound<OwnedValue>,
     upper_bound: Bound<OwnedValue>,
 ) -> Result<(Bound<OwnedValue>, Bound<OwnedValue>)> {
+    // a multirange's bounds are those of the ranges it contains
+    let typeoid = multirange_range_typeoid(typeoid).unwrap_or(typeoid);
     let one_day_nanos: i64 = 86_400_000_000_000;
     let lower_bound = match (typeoid, lower_bound.clone()) {
         // Excluded U64 needs to be canonicalized
@@ -498,6 +941,127 @@ impl SearchQueryInput {
         field_lookup: &impl AsFieldType<String>,
         parser: &mut QueryParser,
         searcher: &Searcher,
+    ) -> Result<Box<dyn Query>, Box<dyn std::error::Error>> {
+        // fields can be configured with their own similarity.  it's applied once the whole query
+        // is built, and to the queries we hide inside tantivy's wrappers before we wrap them
+        let similarities = field_lookup.similarities();
+        let query = self.build_tantivy_query(field_lookup, parser, searcher, &similarities)?;
+        Ok(apply_similarity(query, &similarities))
+    }
+
+    fn build_tantivy_query(
+        self,
+        field_lookup: &impl AsFieldType<String>,
+        parser: &mut QueryParser,
+        searcher: &Searcher,
+        similarities: &HashMap<Field, SearchSimilarity>,
+    ) -> Result<Box<dyn Query>, Box<dyn std::error::Error>> {
+        // the range queries of a multirange field match any combination of the bounds of its
+        // ranges, so the matches are checked against each range on its own
+        match self.multirange_predicate(field_lookup)? {
+            Some((field, predicate)) => Ok(Box::new(MultirangeQuery::new(
+                field,
+                self.into_unfiltered_tantivy_query(field_lookup, parser, searcher, similarities)?,
+                predicate,
+            ))),
+            None => {
+                self.into_unfiltered_tantivy_query(field_lookup, parser, searcher, similarities)
+            }
+        }
+    }
+
+    /// Build a query that's about to be wrapped in a query that [`apply_similarity`] can't see
+    /// into, such as a boost or a dis-max query
+    fn build_wrapped_tantivy_query(
+        self,
+        field_lookup: &impl AsFieldType<String>,
+        parser: &mut QueryParser,
+        searcher: &Searcher,
+        similarities: &HashMap<Field, SearchSimilarity>,
+    ) -> Result<Box<dyn Query>, Box<dyn std::error::Error>> {
+        let query = self.build_tantivy_query(field_lookup, parser, searcher, similarities)?;
+        Ok(apply_similarity(query, similarities))
+    }
+
+    /// The [`RangePredicate`] a range query of a multirange field requires of one of its ranges
+    fn multirange_predicate(
+        &self,
+        field_lookup: &impl AsFieldType<String>,
+    ) -> Result<Option<(String, RangePredicate)>, Box<dyn std::error::Error>> {
+        let (field, is_datetime) = match self {
+            Self::RangeTerm {
+                field, is_datetime, ..
+            }
+            | Self::RangeIntersects {
+                field, is_datetime, ..
+            }
+            | Self::RangeContains {
+                field, is_datetime, ..
+            }
+            | Self::RangeWithin {
+                field, is_datetime, ..
+            } => (field, *is_datetime),
+            _ => return Ok(None),
+        };
+        let (_, typeoid, _) = field_lookup
+            .as_field_type(field)
+            .ok_or_else(|| QueryError::NonIndexedField(field.clone()))?;
+        if multirange_range_typeoid(typeoid).is_none() {
+            return Ok(None);
+        }
+
+        let is_datetime = is_datetime_typeoid(typeoid) || is_datetime;
+        let key = |value: &OwnedValue| {
+            RangeKey::from_query_value(value, is_datetime).ok_or(QueryError::FieldTypeMismatch)
+        };
+        let bounds = |lower_bound: &Bound<OwnedValue>, upper_bound: &Bound<OwnedValue>| {
+            let (lower_bound, upper_bound) =
+                check_range_bounds(typeoid, lower_bound.clone(), upper_bound.clone())?;
+            let key_bound = |bound: Bound<OwnedValue>| match bound {
+                Bound::Included(value) => key(&value).map(Bound::Included),
+                Bound::Excluded(value) => key(&value).map(Bound::Excluded),
+                Bound::Unbounded => Ok(Bound::Unbounded),
+            };
+            Ok::<_, Box<dyn std::error::Error>>((key_bound(lower_bound)?, key_bound(upper_bound)?))
+        };
+
+        let predicate = match self {
+            Self::RangeTerm { value, .. } => RangePredicate::Term(key(value)?),
+            Self::RangeIntersects {
+                lower_bound,
+                upper_bound,
+                ..
+            } => {
+                let (lower, upper) = bounds(lower_bound, upper_bound)?;
+                RangePredicate::Intersects(lower, upper)
+            }
+            Self::RangeContains {
+                lower_bound,
+                upper_bound,
+                ..
+            } => {
+                let (lower, upper) = bounds(lower_bound, upper_bound)?;
+                RangePredicate::Contains(lower, upper)
+            }
+            Self::RangeWithin {
+                lower_bound,
+                upper_bound,
+                ..
+            } => {
+                let (lower, upper) = bounds(lower_bound, upper_bound)?;
+                RangePredicate::Within(lower, upper)
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some((field.clone(), predicate)))
+    }
+
+    fn into_unfiltered_tantivy_query(
+        self,
+        field_lookup: &impl AsFieldType<String>,
+        parser: &mut QueryParser,
+        searcher: &Searcher,
+        similarities: &HashMap<Field, SearchSimilarity>,
     ) -> Result<Box<dyn Query>, Box<dyn std::error::Error>> {
         match self {
             Self::All => Ok(Box::new(AllQuery)),
@@ -505,43 +1069,157 @@ impl SearchQueryInput {
                 must,
                 should,
                 must_not,
+                minimum_should_match,
             } => {
+                let minimum_should_match = minimum_should_match
+                    .map(|minimum_should_match| minimum_should_match.resolve(should.len()));
                 let mut subqueries = vec![];
                 for input in must {
                     subqueries.push((
                         Occur::Must,
-                        input.into_tantivy_query(field_lookup, parser, searcher)?,
+                        input.build_tantivy_query(field_lookup, parser, searcher, similarities)?,
                     ));
                 }
                 for input in should {
                     subqueries.push((
                         Occur::Should,
-                        input.into_tantivy_query(field_lookup, parser, searcher)?,
+                        input.build_tantivy_query(field_lookup, parser, searcher, similarities)?,
                     ));
                 }
                 for input in must_not {
                     subqueries.push((
                         Occur::MustNot,
-                        input.into_tantivy_query(field_lookup, parser, searcher)?,
+                        input.build_tantivy_query(field_lookup, parser, searcher, similarities)?,
                     ));
                 }
-                Ok(Box::new(BooleanQuery::new(subqueries)))
+                match minimum_should_match {
+                    Some(minimum_should_match) => {
+                        Ok(Box::new(BooleanQuery::with_minimum_required_clauses(
+                            subqueries,
+                            minimum_should_match,
+                        )))
+                    }
+                    None => Ok(Box::new(BooleanQuery::new(subqueries))),
+                }
             }
             Self::Boost { query, factor } => Ok(Box::new(BoostQuery::new(
-                query.into_tantivy_query(field_lookup, parser, searcher)?,
+                query.build_wrapped_tantivy_query(field_lookup, parser, searcher, similarities)?,
                 factor,
             ))),
+            Self::Collapse { field, query, .. } => {
+                let (field_type, _, _) = field_lookup
+                    .as_field_type(&field)
+                    .ok_or_else(|| QueryError::NonIndexedField(field.clone()))?;
+                if !field_type.is_fast() {
+                    return Err(Box::new(QueryError::NonFastField(field)));
+                }
+
+                // the hits are collapsed as they're collected, see `SearchQueryInput::collapse`
+                query.build_tantivy_query(field_lookup, parser, searcher, similarities)
+            }
             Self::ConstScore { query, score } => Ok(Box::new(ConstScoreQuery::new(
-                query.into_tantivy_query(field_lookup, parser, searcher)?,
+                query.build_wrapped_tantivy_query(field_lookup, parser, searcher, similarities)?,
                 score,
             ))),
+            Self::Decay {
+                field,
+                query,
+                function,
+                origin,
+                scale,
+                offset,
+                decay,
+            } => {
+                let (field_type, _, _) = field_lookup
+                    .as_field_type(&field)
+                    .ok_or_else(|| QueryError::NonIndexedField(field.clone()))?;
+                if !field_type.is_fast() {
+                    return Err(Box::new(QueryError::NonFastField(field)));
+                }
+
+                // dates are stored as microseconds in their fast field, so the origin needs to
+                // be converted to micros and the scale/offset from seconds to micros
+                let decimal_scale = field_lookup.as_decimal_scale(&field);
+                let (origin, unit) = match field_type {
+                    FieldType::Date(_) => {
+                        let origin = match origin {
+                            OwnedValue::Date(date) => date,
+                            OwnedValue::Str(text) => TantivyDateTime::try_from(text.as_str())?.0,
+                            _ => return Err(Box::new(QueryError::FieldTypeMismatch)),
+                        };
+                        (origin.into_timestamp_micros() as f64, 1_000_000.0)
+                    }
+                    // an exact decimal field holds its values as integers with `decimal_scale`
+                    // digits after the point, so the distances are scaled the same way
+                    FieldType::I64(_) if decimal_scale.is_some() => {
+                        let origin = match numeric_value(origin, true, decimal_scale)? {
+                            OwnedValue::I64(value) => value as f64,
+                            _ => return Err(Box::new(QueryError::FieldTypeMismatch)),
+                        };
+                        let digits = decimal_scale.expect("decimal field should have a scale");
+                        (origin, 10_f64.powi(digits as i32))
+                    }
+                    FieldType::I64(_) | FieldType::U64(_) | FieldType::F64(_) => {
+                        let origin = match origin {
+                            OwnedValue::I64(value) => value as f64,
+                            OwnedValue::U64(value) => value as f64,
+                            OwnedValue::F64(value) => value,
+                            _ => return Err(Box::new(QueryError::FieldTypeMismatch)),
+                        };
+                        (origin, 1.0)
+                    }
+                    _ => return Err(Box::new(QueryError::WrongFieldType(field))),
+                };
+
+                let offset = offset.unwrap_or_default();
+                let decay = decay.unwrap_or(DEFAULT_DECAY);
+                if scale <= 0.0 {
+                    return Err(Box::new(QueryError::InvalidDecay(
+                        "scale must be greater than zero".into(),
+                    )));
+                }
+                if offset < 0.0 {
+                    return Err(Box::new(QueryError::InvalidDecay(
+                        "offset must not be negative".into(),
+                    )));
+                }
+                if decay <= 0.0 || decay >= 1.0 {
+                    return Err(Box::new(QueryError::InvalidDecay(
+                        "decay must be between 0 and 1, exclusive".into(),
+                    )));
+                }
+
+                Ok(Box::new(DecayQuery::new(
+                    query.build_wrapped_tantivy_query(
+                        field_lookup,
+                        parser,
+                        searcher,
+                        similarities,
+                    )?,
+                    field,
+                    DecayParams {
+                        function,
+                        origin,
+                        scale: scale * unit,
+                        offset: offset * unit,
+                        decay,
+                    },
+                )))
+            }
             Self::DisjunctionMax {
                 disjuncts,
                 tie_breaker,
             } => {
                 let disjuncts = disjuncts
                     .into_iter()
-                    .map(|query| query.into_tantivy_query(field_lookup, parser, searcher))
+                    .map(|query| {
+                        query.build_wrapped_tantivy_query(
+                            field_lookup,
+                            parser,
+                            searcher,
+                            similarities,
+                        )
+                    })
                     .collect::<Result<_, _>>()?;
                 if let Some(tie_breaker) = tie_breaker {
                     Ok(Box::new(DisjunctionMaxQuery::with_tie_breaker(
@@ -666,6 +1344,142 @@ impl SearchQueryInput {
 
                 Ok(Box::new(BooleanQuery::new(terms)))
             }
+            Self::GeoDistance {
+                field,
+                origin,
+                distance,
+            } => geo_query(field, GeoShape::Distance { origin, distance }, field_lookup),
+            Self::GeoBoundingBox {
+                field,
+                top_left,
+                bottom_right,
+            } => geo_query(
+                field,
+                GeoShape::BoundingBox {
+                    top_left,
+                    bottom_right,
+                },
+                field_lookup,
+            ),
+            Self::GeoPolygon { field, points } => {
+                geo_query(field, GeoShape::Polygon(points), field_lookup)
+            }
+            Self::IsNull { field } => {
+                field_lookup
+                    .as_field_type(&field)
+                    .ok_or_else(|| QueryError::NonIndexedField(field.clone()))?;
+                let nulls_field = field_lookup
+                    .as_nulls_field(&field)
+                    .ok_or_else(|| QueryError::NullsNotIndexed(field.clone()))?;
+                Ok(Box::new(TermQuery::new(
+                    Term::from_field_text(nulls_field, &field),
+                    IndexRecordOption::Basic.into(),
+                )))
+            }
+            Self::Knn {
+                field,
+                vector,
+                metric,
+                k,
+                filter,
+            } => {
+                let Some(SearchFieldConfig::Vector { dimensions, .. }) =
+                    field_lookup.as_field_config(&field)
+                else {
+                    return Err(match field_lookup.as_field_type(&field) {
+                        Some(_) => Box::new(QueryError::WrongFieldType(field)),
+                        None => Box::new(QueryError::NonIndexedField(field)),
+                    });
+                };
+                if vector.len() != dimensions {
+                    return Err(Box::new(QueryError::WrongVectorDimensions(
+                        field,
+                        vector.len(),
+                        dimensions,
+                    )));
+                }
+
+                let filter = match filter {
+                    Some(filter) => {
+                        filter.build_tantivy_query(field_lookup, parser, searcher, similarities)?
+                    }
+                    None => Box::new(AllQuery),
+                };
+                Ok(Box::new(KnnQuery::new(
+                    field,
+                    vector,
+                    metric,
+                    k,
+                    filter,
+                    field_lookup.heap_relation_oid(),
+                )))
+            }
+            Self::Match {
+                field,
+                value,
+                tokenizer,
+                fuzziness,
+                transposition_cost_one,
+                prefix,
+                conjunction_mode,
+            } => {
+                let (field, path) = split_field_and_path(&field);
+                let transposition_cost_one = transposition_cost_one.unwrap_or(true);
+                let prefix = prefix.unwrap_or(false);
+                let occur = if conjunction_mode.unwrap_or(false) {
+                    Occur::Must
+                } else {
+                    Occur::Should
+                };
+
+                let (field_type, _, field) = field_lookup
+                    .as_field_type(&field)
+                    .ok_or(QueryError::NonIndexedField(field))?;
+
+                // `value` is plain text, and is only ever tokenized, never parsed as query syntax
+                let mut analyzer = match tokenizer {
+                    Some(tokenizer) => SearchTokenizer::from_json_value(&tokenizer)?
+                        .to_tantivy_tokenizer()
+                        .ok_or(QueryError::InvalidTokenizer(tokenizer))?,
+                    None => searcher.index().tokenizer_for_field(field)?,
+                };
+                let mut stream = analyzer.token_stream(&value);
+                let mut tokens = Vec::new();
+                while stream.advance() {
+                    tokens.push(stream.token().text.clone());
+                }
+
+                let last = tokens.len().saturating_sub(1);
+                let mut terms = Vec::new();
+                for (i, token) in tokens.into_iter().enumerate() {
+                    let distance = fuzziness.map_or(0, |fuzziness| fuzziness.distance(&token));
+                    let is_prefix = prefix && i == last;
+                    let term = value_to_term(
+                        field,
+                        &OwnedValue::Str(token),
+                        &field_type,
+                        path.as_deref(),
+                        false,
+                    )?;
+                    let term_query: Box<dyn Query> = match (distance, is_prefix) {
+                        (0, false) => {
+                            Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs.into()))
+                        }
+                        (0, true) => Box::new(PhrasePrefixQuery::new(vec![term])),
+                        (distance, false) => {
+                            Box::new(FuzzyTermQuery::new(term, distance, transposition_cost_one))
+                        }
+                        (distance, true) => Box::new(FuzzyTermQuery::new_prefix(
+                            term,
+                            distance,
+                            transposition_cost_one,
+                        )),
+                    };
+                    terms.push((occur, term_query));
+                }
+
+                Ok(Box::new(BooleanQuery::new(terms)))
+            }
             Self::MoreLikeThis {
                 min_doc_frequency,
                 max_doc_frequency,
@@ -773,25 +1587,89 @@ impl SearchQueryInput {
                 }
                 Ok(Box::new(query))
             }
+            Self::MultiMatch {
+                fields,
+                value,
+                match_type,
+                tie_breaker,
+                conjunction_mode,
+            } => {
+                let mut match_fields = vec![];
+                for field in fields {
+                    let (field, boost) = split_field_and_boost(&field)
+                        .ok_or(QueryError::InvalidMultiMatchField(field))?;
+                    let (field, path) = split_field_and_path(&field);
+                    let (field_type, _, field) = field_lookup
+                        .as_field_type(&field)
+                        .ok_or(QueryError::NonIndexedField(field))?;
+
+                    // every field is analyzed with its own tokenizer
+                    let mut analyzer = searcher.index().tokenizer_for_field(field)?;
+                    let mut stream = analyzer.token_stream(&value);
+                    let mut tokens = vec![];
+                    while stream.advance() {
+                        let token = stream.token();
+                        let word = value
+                            .get(token.offset_from..token.offset_to)
+                            .unwrap_or(&token.text)
+                            .to_string();
+                        let term = value_to_term(
+                            field,
+                            &OwnedValue::Str(token.text.clone()),
+                            &field_type,
+                            path.as_deref(),
+                            false,
+                        )?;
+                        tokens.push((word, term));
+                    }
+                    match_fields.push(MultiMatchField { boost, tokens });
+                }
+
+                Ok(multi_match_query(
+                    match_type,
+                    match_fields,
+                    tie_breaker.unwrap_or(0.0),
+                    conjunction_mode.unwrap_or(false),
+                    similarities,
+                ))
+            }
+            // names don't change what a query matches
+            Self::Named { query, .. } => {
+                query.build_tantivy_query(field_lookup, parser, searcher, similarities)
+            }
             Self::Parse {
                 query_string,
                 lenient,
                 conjunction_mode,
+                minimum_should_match,
             } => {
                 if let Some(true) = conjunction_mode {
                     parser.set_conjunction_by_default();
                 }
 
-                match lenient {
+                let parsed_query = match lenient {
                     Some(true) => {
                         let (parsed_query, _) = parser.parse_query_lenient(&query_string);
-                        Ok(Box::new(parsed_query))
-                    }
-                    _ => {
-                        Ok(Box::new(parser.parse_query(&query_string).map_err(
-                            |err| QueryError::ParseError(err, query_string),
-                        )?))
+                        parsed_query
                     }
+                    _ => parser
+                        .parse_query(&query_string)
+                        .map_err(|err| QueryError::ParseError(err, query_string.clone()))?,
+                };
+                let parsed_query = parse_with_similarity(
+                    parser,
+                    &query_string,
+                    conjunction_mode.unwrap_or(false),
+                    similarities,
+                )
+                .unwrap_or(parsed_query);
+
+                match minimum_should_match {
+                    Some(minimum_should_match) => Ok(with_minimum_should_match(
+                        parsed_query,
+                        minimum_should_match,
+                    )),
+                    None => Ok(parsed_query),
                 }
             }
             Self::ParseWithField {
@@ -799,14 +1677,16 @@ impl SearchQueryInput {
                 query_string,
                 lenient,
                 conjunction_mode,
+                minimum_should_match,
             } => {
                 let query_string = format!("{field}:({query_string})");
                 Self::Parse {
                     query_string,
                     lenient,
                     conjunction_mode,
+                    minimum_should_match,
                 }
-                .into_tantivy_query(field_lookup, parser, searcher)
+                .build_tantivy_query(field_lookup, parser, searcher, similarities)
             }
             Self::Phrase {
                 field,
@@ -838,6 +1718,7 @@ impl SearchQueryInput {
                 lower_bound,
                 upper_bound,
                 is_datetime,
+                is_numeric,
             } => {
                 let (field, path) = split_field_and_path(&field);
                 let field_name = field;
@@ -846,6 +1727,20 @@ impl SearchQueryInput {
                     .ok_or_else(|| QueryError::WrongFieldType(field_name.clone()))?;
 
                 let is_datetime = is_datetime_typeoid(typeoid) || is_datetime;
+                let decimal_scale = field_lookup.as_decimal_scale(&field_name);
+                let numeric_bound = |bound: Bound<OwnedValue>| -> Result<Bound<OwnedValue>> {
+                    Ok(match bound {
+                        Bound::Included(value) => {
+                            Bound::Included(numeric_value(value, is_numeric, decimal_scale)?)
+                        }
+                        Bound::Excluded(value) => {
+                            Bound::Excluded(numeric_value(value, is_numeric, decimal_scale)?)
+                        }
+                        Bound::Unbounded => Bound::Unbounded,
+                    })
+                };
+                let lower_bound = numeric_bound(lower_bound)?;
+                let upper_bound = numeric_bound(upper_bound)?;
                 let (lower_bound, upper_bound) =
                     check_range_bounds(typeoid, lower_bound, upper_bound)?;
 
@@ -1526,25 +2421,61 @@ impl SearchQueryInput {
                 Ok(Box::new(query))
             }
 
+            Self::ScoreFilter { lower_bound, query } => Ok(Box::new(ScoreFilterQuery::new(
+                query.build_wrapped_tantivy_query(field_lookup, parser, searcher, similarities)?,
+                lower_bound,
+            ))),
+            Self::SparseVector { field, vector } => {
+                let (_, _, search_field) = field_lookup
+                    .as_field_type(&field)
+                    .ok_or_else(|| QueryError::NonIndexedField(field.clone()))?;
+                if !matches!(
+                    field_lookup.as_field_config(&field),
+                    Some(SearchFieldConfig::SparseVector { .. })
+                ) {
+                    return Err(Box::new(QueryError::WrongFieldType(field)));
+                }
+
+                // each token scores its own weight times the document's, which add up to the
+                // dot product of the two vectors
+                Ok(Box::new(BooleanQuery::new(
+                    vector
+                        .into_iter()
+                        .map(|(token, weight)| {
+                            let query: Box<dyn Query> = Box::new(SparseTermQuery::new(
+                                Term::from_field_text(search_field, &token),
+                                weight,
+                            ));
+                            (Occur::Should, query)
+                        })
+                        .collect(),
+                )))
+            }
+
             Self::Term {
                 field,
                 value,
                 is_datetime,
+                is_numeric,
             } => {
                 let record_option = IndexRecordOption::WithFreqsAndPositions;
                 if let Some(field) = field {
                     let (field, path) = split_field_and_path(&field);
+                    let decimal_scale = field_lookup.as_decimal_scale(&field);
                     let (field_type, typeoid, field) = field_lookup
                         .as_field_type(&field)
                         .ok_or(QueryError::NonIndexedField(field))?;
 
                     let is_datetime = is_datetime_typeoid(typeoid) || is_datetime;
+                    let value = numeric_value(value, is_numeric, decimal_scale)?;
+                    let value = uuid_value(value, &field_type, typeoid)?;
                     let term =
                         value_to_term(field, &value, &field_type, path.as_deref(), is_datetime)?;
 
                     Ok(Box::new(TermQuery::new(term, record_option.into())))
                 } else {
                     // If no field is passed, then search all fields.
+                    let value = numeric_value(value, is_numeric, None)?;
                     let all_fields = field_lookup.fields();
                     let mut terms = vec![];
                     for (field_type, _, field) in all_fields {
@@ -1564,14 +2495,18 @@ impl SearchQueryInput {
                     field,
                     value,
                     is_datetime,
+                    is_numeric,
                 } in fields
                 {
                     let (_, path) = split_field_and_path(&field);
+                    let decimal_scale = field_lookup.as_decimal_scale(&field);
                     let (field_type, typeoid, field) = field_lookup
                         .as_field_type(&field)
                         .ok_or(QueryError::NonIndexedField(field))?;
 
                     let is_datetime = is_datetime_typeoid(typeoid) || is_datetime;
+                    let value = numeric_value(value, is_numeric, decimal_scale)?;
+                    let value = uuid_value(value, &field_type, typeoid)?;
                     terms.push(value_to_term(
                         field,
                         &value,
@@ -1584,7 +2519,7 @@ impl SearchQueryInput {
                 Ok(Box::new(TermSetQuery::new(terms)))
             }
             Self::WithIndex { query, .. } => {
-                query.into_tantivy_query(field_lookup, parser, searcher)
+                query.build_tantivy_query(field_lookup, parser, searcher, similarities)
             }
         }
     }
@@ -1671,6 +2606,12 @@ pub fn value_to_term(
         }
     }
 
+    // tantivy serializes bytes as base64, so that's how they come back once the query has been
+    // passed between functions
+    if let (FieldType::Bytes(_), OwnedValue::Str(text)) = (field_type, value) {
+        return Ok(Term::from_field_bytes(field, &BASE64.decode(text)?));
+    }
+
     Ok(match value {
         OwnedValue::Str(text) => Term::from_field_text(field, text),
         OwnedValue::PreTokStr(_) => panic!("pre-tokenized text cannot be converted to term"),
@@ -1713,6 +2654,56 @@ impl TryFrom<&str> for TantivyDateTime {
     }
 }
 
+/// A [`GeoQuery`] for the points of the geo field `field` inside `shape`
+fn geo_query(
+    field: String,
+    shape: GeoShape,
+    field_lookup: &impl AsFieldType<String>,
+) -> Result<Box<dyn Query>, Box<dyn std::error::Error>> {
+    // geo fields are bytes fields, but not every bytes field holds points
+    let Some(SearchFieldConfig::Geo { .. }) = field_lookup.as_field_config(&field) else {
+        return Err(match field_lookup.as_field_type(&field) {
+            Some(_) => Box::new(QueryError::WrongFieldType(field)),
+            None => Box::new(QueryError::NonIndexedField(field)),
+        });
+    };
+    Ok(Box::new(GeoQuery::new(field, shape)))
+}
+
+/// The query parser turns a multi-term query string into a boolean query, whose `should` clauses
+/// are the terms that `minimum_should_match` applies to.  A field-grouped query string, such as
+/// `description:(shoes running)`, can end up as a boolean query with that group as its only clause,
+/// in which case we look inside the group instead.
+fn with_minimum_should_match(
+    query: Box<dyn Query>,
+    minimum_should_match: MinimumShouldMatch,
+) -> Box<dyn Query> {
+    let Some(boolean_query) = query.downcast_ref::<BooleanQuery>() else {
+        return query;
+    };
+
+    let mut clauses = boolean_query
+        .clauses()
+        .iter()
+        .map(|(occur, subquery)| (*occur, (**subquery).box_clone()))
+        .collect::<Vec<_>>();
+    if let [(Occur::Should | Occur::Must, subquery)] = clauses.as_slice() {
+        if subquery.downcast_ref::<BooleanQuery>().is_some() {
+            let (_, subquery) = clauses.pop().unwrap();
+            return with_minimum_should_match(subquery, minimum_should_match);
+        }
+    }
+
+    let num_should = clauses
+        .iter()
+        .filter(|(occur, _)| *occur == Occur::Should)
+        .count();
+    Box::new(BooleanQuery::with_minimum_required_clauses(
+        clauses,
+        minimum_should_match.resolve(num_should),
+    ))
+}
+
 pub fn split_field_and_path(field: &str) -> (String, Option<String>) {
     let json_path = split_json_path(field);
     if json_path.len() == 1 {
@@ -1733,6 +2724,20 @@ enum QueryError {
     FieldMapJsonObject,
     #[error("field '{0}' is not part of the pg_search index")]
     NonIndexedField(String),
+    #[error("field '{0}' must be configured as a fast field")]
+    NonFastField(String),
+    #[error("field '{0}' must be configured with `index_nulls` to search for its NULLs")]
+    NullsNotIndexed(String),
+    #[error("'{0}' cannot be represented exactly with a scale of {1}")]
+    InexactDecimal(String, u32),
+    #[error("vector has {1} dimensions, but field '{0}' has {2}")]
+    WrongVectorDimensions(String, usize, usize),
+    #[error("invalid decay function: {0}")]
+    InvalidDecay(String),
+    #[error("invalid multi_match field '{0}', expected 'field' or 'field^boost'")]
+    InvalidMultiMatchField(String),
+    #[error("invalid tokenizer: {0}")]
+    InvalidTokenizer(serde_json::Value),
     #[error("wrong type given for field")]
     FieldTypeMismatch,
     #[error("could not build regex with pattern '{1}': {0}")]
@@ -0,0 +1,267 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::query::similarity::apply_similarity;
+use crate::schema::{IndexRecordOption, SearchSimilarity};
+use pgrx::PostgresEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use tantivy::query::{
+    Bm25StatisticsProvider, BooleanQuery, BoostQuery, DisjunctionMaxQuery, EmptyQuery,
+    EnableScoring, PhraseQuery, Query, TermQuery, Weight,
+};
+use tantivy::query_grammar::Occur;
+use tantivy::schema::Field;
+use tantivy::Term;
+
+/// How the per-field matches of a multi-field match query are combined.  These follow the
+/// same definitions as Elasticsearch's `multi_match` types.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize, PostgresEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiMatchType {
+    /// Score each document by its best matching field, plus `tie_breaker` times the others
+    #[default]
+    BestFields,
+    /// Sum the scores of every matching field
+    MostFields,
+    /// Treat the fields as one big field, matching each term against any of them
+    CrossFields,
+    /// Match the whole value as a phrase in each field, scored like `BestFields`
+    Phrase,
+}
+
+/// The analyzed terms of the query value for one field, in token order.  Each term is paired
+/// with the word of the query value it was analyzed from.
+pub struct MultiMatchField {
+    pub boost: f32,
+    pub tokens: Vec<(String, Term)>,
+}
+
+/// Split a `"field^boost"` entry from a multi-match field list into its field name and boost.
+/// Returns [`None`] if the boost isn't a non-negative number.
+pub fn split_field_and_boost(field: &str) -> Option<(String, f32)> {
+    match field.rsplit_once('^') {
+        Some((name, boost)) => boost
+            .parse::<f32>()
+            .ok()
+            .filter(|boost| boost.is_finite() && *boost >= 0.0)
+            .map(|boost| (name.to_string(), boost)),
+        None => Some((field.to_string(), 1.0)),
+    }
+}
+
+/// Build the tantivy query for a multi-field match over the already analyzed `fields`.
+///
+/// Each field's query is scored with its field's similarity before it's boosted and combined,
+/// since the combined query can't be rewritten afterwards.
+pub fn multi_match_query(
+    match_type: MultiMatchType,
+    fields: Vec<MultiMatchField>,
+    tie_breaker: f32,
+    conjunction_mode: bool,
+    similarities: &HashMap<Field, SearchSimilarity>,
+) -> Box<dyn Query> {
+    let occur = if conjunction_mode {
+        Occur::Must
+    } else {
+        Occur::Should
+    };
+    let fields = fields
+        .into_iter()
+        .filter(|field| !field.tokens.is_empty())
+        .collect::<Vec<_>>();
+    if fields.is_empty() {
+        return Box::new(EmptyQuery);
+    }
+
+    match match_type {
+        MultiMatchType::BestFields => Box::new(DisjunctionMaxQuery::with_tie_breaker(
+            fields
+                .into_iter()
+                .map(|field| field_query(field, occur, similarities))
+                .collect(),
+            tie_breaker,
+        )),
+        MultiMatchType::MostFields => Box::new(BooleanQuery::new(
+            fields
+                .into_iter()
+                .map(|field| (Occur::Should, field_query(field, occur, similarities)))
+                .collect(),
+        )),
+        MultiMatchType::CrossFields => {
+            // group the terms by the word they were analyzed from, in the order the words first
+            // appear, so that each word can be matched by any one of the fields, however each
+            // field's tokenizer analyzed it
+            let mut groups: Vec<(String, Vec<Term>, Vec<Box<dyn Query>>)> = vec![];
+            for field in fields {
+                for (word, term) in field.tokens {
+                    let query = boosted(term_query(term.clone()), field.boost, similarities);
+                    match groups.iter_mut().find(|(existing, ..)| *existing == word) {
+                        Some((_, terms, queries)) => {
+                            terms.push(term);
+                            queries.push(query);
+                        }
+                        None => groups.push((word, vec![term], vec![query])),
+                    }
+                }
+            }
+            Box::new(BooleanQuery::new(
+                groups
+                    .into_iter()
+                    .map(|(_, terms, queries)| {
+                        let query: Box<dyn Query> = Box::new(BlendedTermsQuery {
+                            query: Box::new(DisjunctionMaxQuery::with_tie_breaker(
+                                queries,
+                                tie_breaker,
+                            )),
+                            terms,
+                        });
+                        (occur, query)
+                    })
+                    .collect(),
+            ))
+        }
+        MultiMatchType::Phrase => Box::new(DisjunctionMaxQuery::with_tie_breaker(
+            fields
+                .into_iter()
+                .map(|field| {
+                    let query: Box<dyn Query> = if field.tokens.len() == 1 {
+                        let (_, term) = field.tokens.into_iter().next().unwrap();
+                        term_query(term)
+                    } else {
+                        Box::new(PhraseQuery::new(
+                            field.tokens.into_iter().map(|(_, term)| term).collect(),
+                        ))
+                    };
+                    boosted(query, field.boost, similarities)
+                })
+                .collect(),
+            tie_breaker,
+        )),
+    }
+}
+
+/// Match the terms of a single field, combined with `occur`
+fn field_query(
+    field: MultiMatchField,
+    occur: Occur,
+    similarities: &HashMap<Field, SearchSimilarity>,
+) -> Box<dyn Query> {
+    let query = Box::new(BooleanQuery::new(
+        field
+            .tokens
+            .into_iter()
+            .map(|(_, term)| (occur, term_query(term)))
+            .collect(),
+    ));
+    boosted(query, field.boost, similarities)
+}
+
+fn term_query(term: Term) -> Box<dyn Query> {
+    Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs.into()))
+}
+
+fn boosted(
+    query: Box<dyn Query>,
+    boost: f32,
+    similarities: &HashMap<Field, SearchSimilarity>,
+) -> Box<dyn Query> {
+    let query = apply_similarity(query, similarities);
+    if boost == 1.0 {
+        query
+    } else {
+        Box::new(BoostQuery::new(query, boost))
+    }
+}
+
+/// Scores `query` as though all of `terms` were the same term of one big field, by giving every
+/// one of them the highest document frequency among them.  Otherwise a word that's rare in one
+/// field but common in another would score best in the field where it's least meaningful.
+struct BlendedTermsQuery {
+    query: Box<dyn Query>,
+    terms: Vec<Term>,
+}
+
+impl Clone for BlendedTermsQuery {
+    fn clone(&self) -> Self {
+        Self {
+            query: self.query.box_clone(),
+            terms: self.terms.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for BlendedTermsQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "BlendedTermsQuery({:?}, {:?})", self.query, self.terms)
+    }
+}
+
+impl Query for BlendedTermsQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        let EnableScoring::Enabled {
+            searcher,
+            statistics_provider,
+        } = enable_scoring
+        else {
+            return self.query.weight(enable_scoring);
+        };
+
+        let mut doc_freq = 0;
+        for term in &self.terms {
+            doc_freq = doc_freq.max(statistics_provider.doc_freq(term)?);
+        }
+        let blended = BlendedStatistics {
+            statistics_provider,
+            terms: &self.terms,
+            doc_freq,
+        };
+        self.query
+            .weight(EnableScoring::enabled_from_statistics_provider(
+                &blended, searcher,
+            ))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        self.query.query_terms(visitor);
+    }
+}
+
+struct BlendedStatistics<'a> {
+    statistics_provider: &'a dyn Bm25StatisticsProvider,
+    terms: &'a [Term],
+    doc_freq: u64,
+}
+
+impl Bm25StatisticsProvider for BlendedStatistics<'_> {
+    fn total_num_tokens(&self, field: Field) -> tantivy::Result<u64> {
+        self.statistics_provider.total_num_tokens(field)
+    }
+
+    fn total_num_docs(&self) -> tantivy::Result<u64> {
+        self.statistics_provider.total_num_docs()
+    }
+
+    fn doc_freq(&self, term: &Term) -> tantivy::Result<u64> {
+        if self.terms.contains(term) {
+            Ok(self.doc_freq)
+        } else {
+            self.statistics_provider.doc_freq(term)
+        }
+    }
+}
@@ -0,0 +1,356 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::query::TantivyDateTime;
+use crate::schema::range::{TantivyRange, MULTIRANGE_ENTRY_KEY};
+use std::fmt;
+use std::ops::Bound;
+use std::sync::Arc;
+use tantivy::columnar::StrColumn;
+use tantivy::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use tantivy::schema::{OwnedValue, DATE_TIME_PRECISION_INDEXED};
+use tantivy::{DateTime, DocId, DocSet, Score, SegmentReader, TantivyError, Term, TERMINATED};
+
+/// A bound of a range, compared the way the range field indexes it
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum RangeKey {
+    Number(f64),
+    Date(DateTime),
+}
+
+impl RangeKey {
+    /// The key of a query value, or `None` if it can't be a bound of a range
+    pub fn from_query_value(value: &OwnedValue, is_datetime: bool) -> Option<Self> {
+        match value {
+            OwnedValue::I64(n) => Some(RangeKey::Number(*n as f64)),
+            OwnedValue::U64(n) => Some(RangeKey::Number(*n as f64)),
+            OwnedValue::F64(n) => Some(RangeKey::Number(*n)),
+            OwnedValue::Date(date) => Some(RangeKey::Date(*date)),
+            OwnedValue::Str(text) if is_datetime => TantivyDateTime::try_from(text.as_str())
+                .ok()
+                .map(|TantivyDateTime(date)| {
+                    RangeKey::Date(date.truncate(DATE_TIME_PRECISION_INDEXED))
+                }),
+            _ => None,
+        }
+    }
+
+    /// The key of a bound of an indexed range, which is a number or an RFC 3339 datetime
+    fn from_entry_value(value: &serde_json::Value) -> Option<Self> {
+        match value {
+            serde_json::Value::Number(n) => n.as_f64().map(RangeKey::Number),
+            serde_json::Value::String(text) => chrono::DateTime::parse_from_rfc3339(text)
+                .ok()
+                .map(|datetime| DateTime::from_timestamp_micros(datetime.timestamp_micros()))
+                .or_else(|| {
+                    TantivyDateTime::try_from(text.as_str())
+                        .ok()
+                        .map(|TantivyDateTime(date)| date)
+                })
+                .map(|date| RangeKey::Date(date.truncate(DATE_TIME_PRECISION_INDEXED))),
+            _ => None,
+        }
+    }
+}
+
+/// One of the ranges of a multirange, as it was indexed
+struct RangeEntry {
+    lower: Option<RangeKey>,
+    upper: Option<RangeKey>,
+    empty: bool,
+    lower_inclusive: bool,
+    upper_inclusive: bool,
+    lower_unbounded: bool,
+    upper_unbounded: bool,
+}
+
+impl RangeEntry {
+    fn parse(text: &str) -> Option<Self> {
+        let range: TantivyRange<serde_json::Value> = serde_json::from_str(text).ok()?;
+        let key = |value: Option<serde_json::Value>| match value {
+            Some(value) => RangeKey::from_entry_value(&value).map(Some),
+            None => Some(None),
+        };
+        Some(RangeEntry {
+            lower: key(range.lower)?,
+            upper: key(range.upper)?,
+            empty: range.empty,
+            lower_inclusive: range.lower_inclusive,
+            upper_inclusive: range.upper_inclusive,
+            lower_unbounded: range.lower_unbounded,
+            upper_unbounded: range.upper_unbounded,
+        })
+    }
+
+    /// Whether the lower bound is below `value`, or at it if `or_equal` and the bound is inclusive
+    fn lower_before(&self, value: RangeKey, or_equal: bool) -> bool {
+        self.lower_unbounded
+            || self.lower.is_some_and(|lower| {
+                lower < value || (or_equal && self.lower_inclusive && lower == value)
+            })
+    }
+
+    /// Whether the upper bound is above `value`, or at it if `or_equal` and the bound is inclusive
+    fn upper_after(&self, value: RangeKey, or_equal: bool) -> bool {
+        self.upper_unbounded
+            || self.upper.is_some_and(|upper| {
+                upper > value || (or_equal && self.upper_inclusive && upper == value)
+            })
+    }
+}
+
+/// What a [`MultirangeQuery`] requires of at least one of the ranges of a multirange, matching
+/// the range queries of a single range field
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangePredicate {
+    /// The range contains the value
+    Term(RangeKey),
+    /// The range overlaps the bounds
+    Intersects(Bound<RangeKey>, Bound<RangeKey>),
+    /// The range is inside the bounds
+    Contains(Bound<RangeKey>, Bound<RangeKey>),
+    /// The range contains the bounds
+    Within(Bound<RangeKey>, Bound<RangeKey>),
+}
+
+impl RangePredicate {
+    fn matches(&self, entry: &RangeEntry) -> bool {
+        match self {
+            RangePredicate::Term(value) => {
+                !entry.empty && entry.lower_before(*value, true) && entry.upper_after(*value, true)
+            }
+            RangePredicate::Intersects(lower, upper) => {
+                if is_empty(lower, upper) || entry.empty {
+                    return false;
+                }
+                let satisfies_lower = match lower {
+                    Bound::Included(lower) => entry.upper_after(*lower, true),
+                    Bound::Excluded(lower) => entry.upper_after(*lower, false),
+                    Bound::Unbounded => true,
+                };
+                let satisfies_upper = match upper {
+                    Bound::Included(upper) => entry.lower_before(*upper, true),
+                    Bound::Excluded(upper) => entry.lower_before(*upper, false),
+                    Bound::Unbounded => true,
+                };
+                satisfies_lower && satisfies_upper
+            }
+            RangePredicate::Contains(lower, upper) => {
+                if entry.empty {
+                    return true;
+                }
+                let satisfies_lower = match lower {
+                    Bound::Included(lower) => entry.lower.is_some_and(|l| l >= *lower),
+                    Bound::Excluded(lower) => entry
+                        .lower
+                        .is_some_and(|l| l > *lower || (!entry.lower_inclusive && l == *lower)),
+                    Bound::Unbounded => true,
+                };
+                let satisfies_upper = match upper {
+                    Bound::Included(upper) => entry.upper.is_some_and(|u| u <= *upper),
+                    Bound::Excluded(upper) => entry
+                        .upper
+                        .is_some_and(|u| u < *upper || (!entry.upper_inclusive && u == *upper)),
+                    Bound::Unbounded => true,
+                };
+                satisfies_lower && satisfies_upper
+            }
+            RangePredicate::Within(lower, upper) => {
+                if is_empty(lower, upper) {
+                    return true;
+                }
+                let satisfies_lower = match lower {
+                    Bound::Included(lower) => entry.lower_before(*lower, true),
+                    Bound::Excluded(lower) => {
+                        entry.lower_unbounded || entry.lower.is_some_and(|l| l <= *lower)
+                    }
+                    Bound::Unbounded => entry.lower_unbounded,
+                };
+                let satisfies_upper = match upper {
+                    Bound::Included(upper) => entry.upper_after(*upper, true),
+                    Bound::Excluded(upper) => {
+                        entry.upper_unbounded || entry.upper.is_some_and(|u| u >= *upper)
+                    }
+                    Bound::Unbounded => entry.upper_unbounded,
+                };
+                satisfies_lower && satisfies_upper
+            }
+        }
+    }
+}
+
+fn is_empty(lower: &Bound<RangeKey>, upper: &Bound<RangeKey>) -> bool {
+    matches!((lower, upper), (Bound::Included(lower), Bound::Excluded(upper)) if lower == upper)
+}
+
+/// Matches the documents of `query` with a range in the multirange field `field` that satisfies
+/// `predicate`.  Every range of a multirange is indexed into the same field, so `query` alone
+/// could match a lower bound of one range together with an upper bound of another.
+pub struct MultirangeQuery {
+    field: String,
+    query: Box<dyn Query>,
+    predicate: Arc<RangePredicate>,
+}
+
+impl MultirangeQuery {
+    pub fn new(field: String, query: Box<dyn Query>, predicate: RangePredicate) -> Self {
+        Self {
+            field,
+            query,
+            predicate: Arc::new(predicate),
+        }
+    }
+}
+
+impl Clone for MultirangeQuery {
+    fn clone(&self) -> Self {
+        Self {
+            field: self.field.clone(),
+            query: self.query.box_clone(),
+            predicate: self.predicate.clone(),
+        }
+    }
+}
+
+impl fmt::Debug for MultirangeQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Multirange(field={:?}, predicate={:?}, query={:?})",
+            self.field, self.predicate, self.query
+        )
+    }
+}
+
+impl Query for MultirangeQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        Ok(Box::new(MultirangeWeight {
+            weight: self.query.weight(enable_scoring)?,
+            field: self.field.clone(),
+            predicate: self.predicate.clone(),
+        }))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        self.query.query_terms(visitor)
+    }
+}
+
+struct MultirangeWeight {
+    weight: Box<dyn Weight>,
+    field: String,
+    predicate: Arc<RangePredicate>,
+}
+
+impl MultirangeWeight {
+    fn multirange_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> tantivy::Result<MultirangeScorer> {
+        let mut scorer = MultirangeScorer {
+            scorer: self.weight.scorer(reader, boost)?,
+            entries: reader
+                .fast_fields()
+                .str(&format!("{}.{MULTIRANGE_ENTRY_KEY}", self.field))?,
+            predicate: self.predicate.clone(),
+            text: String::new(),
+        };
+        scorer.skip_unmatched();
+        Ok(scorer)
+    }
+}
+
+impl Weight for MultirangeWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        Ok(Box::new(self.multirange_scorer(reader, boost)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer = self.multirange_scorer(reader, 1.0)?;
+        if scorer.seek(doc) == doc {
+            self.weight.explain(reader, doc)
+        } else {
+            Err(TantivyError::InvalidArgument(format!(
+                "Document #({doc}) does not match"
+            )))
+        }
+    }
+}
+
+struct MultirangeScorer {
+    scorer: Box<dyn Scorer>,
+    entries: Option<StrColumn>,
+    predicate: Arc<RangePredicate>,
+    text: String,
+}
+
+impl MultirangeScorer {
+    /// Advance to the first document, starting at the current one, with a range that satisfies
+    /// the predicate
+    fn skip_unmatched(&mut self) -> DocId {
+        let mut doc = self.scorer.doc();
+        while doc != TERMINATED {
+            if self.matches(doc) {
+                break;
+            }
+            doc = self.scorer.advance();
+        }
+        doc
+    }
+
+    fn matches(&mut self, doc: DocId) -> bool {
+        let Some(entries) = &self.entries else {
+            return false;
+        };
+        for ord in entries.term_ords(doc) {
+            self.text.clear();
+            if entries.ord_to_str(ord, &mut self.text).is_ok()
+                && RangeEntry::parse(&self.text).is_some_and(|entry| self.predicate.matches(&entry))
+            {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl DocSet for MultirangeScorer {
+    fn advance(&mut self) -> DocId {
+        self.scorer.advance();
+        self.skip_unmatched()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.scorer.seek(target);
+        self.skip_unmatched()
+    }
+
+    fn doc(&self) -> DocId {
+        self.scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorer.size_hint()
+    }
+}
+
+impl Scorer for MultirangeScorer {
+    fn score(&mut self) -> Score {
+        self.scorer.score()
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use std::fmt;
+use std::ops::Bound;
+use tantivy::query::{EnableScoring, Explanation, Query, Scorer, Weight};
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, Term, TERMINATED};
+
+/// Matches the documents of `query` whose score is above `lower_bound`, so that the documents
+/// below it are discarded while they're being collected.  The bound is kept as the `f64` it was
+/// given as, and scores are compared to it as `f64`s, so that it can't be rounded past one.
+pub struct ScoreFilterQuery {
+    query: Box<dyn Query>,
+    lower_bound: Bound<f64>,
+}
+
+impl ScoreFilterQuery {
+    pub fn new(query: Box<dyn Query>, lower_bound: Bound<f64>) -> Self {
+        Self { query, lower_bound }
+    }
+}
+
+impl Clone for ScoreFilterQuery {
+    fn clone(&self) -> Self {
+        Self {
+            query: self.query.box_clone(),
+            lower_bound: self.lower_bound,
+        }
+    }
+}
+
+impl fmt::Debug for ScoreFilterQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "ScoreFilter(lower_bound={:?}, query={:?})",
+            self.lower_bound, self.query
+        )
+    }
+}
+
+impl Query for ScoreFilterQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        // the filter is on the wrapped query's score, so it's always scored, even if our
+        // caller doesn't need scores
+        let enable_scoring = match enable_scoring.searcher() {
+            Some(searcher) => EnableScoring::enabled_from_searcher(searcher),
+            None => {
+                return Err(TantivyError::InvalidArgument(
+                    "a score filter needs a searcher".to_string(),
+                ))
+            }
+        };
+
+        Ok(Box::new(ScoreFilterWeight {
+            weight: self.query.weight(enable_scoring)?,
+            lower_bound: self.lower_bound,
+        }))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        self.query.query_terms(visitor)
+    }
+}
+
+struct ScoreFilterWeight {
+    weight: Box<dyn Weight>,
+    lower_bound: Bound<f64>,
+}
+
+impl Weight for ScoreFilterWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        let mut scorer = ScoreFilterScorer {
+            scorer: self.weight.scorer(reader, boost)?,
+            lower_bound: self.lower_bound,
+            score: 0.0,
+        };
+        scorer.skip_filtered();
+        Ok(Box::new(scorer))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let explanation = self.weight.explain(reader, doc)?;
+        if passes(self.lower_bound, explanation.value()) {
+            Ok(explanation)
+        } else {
+            Err(TantivyError::InvalidArgument(format!(
+                "Document #({doc}) does not match"
+            )))
+        }
+    }
+}
+
+fn passes(lower_bound: Bound<f64>, score: Score) -> bool {
+    match lower_bound {
+        Bound::Included(min) => score as f64 >= min,
+        Bound::Excluded(min) => score as f64 > min,
+        Bound::Unbounded => true,
+    }
+}
+
+struct ScoreFilterScorer {
+    scorer: Box<dyn Scorer>,
+    lower_bound: Bound<f64>,
+    score: Score,
+}
+
+impl ScoreFilterScorer {
+    /// Advance to the first document, starting at the current one, that passes the filter, and
+    /// remember its score so it isn't computed twice
+    fn skip_filtered(&mut self) -> DocId {
+        let mut doc = self.scorer.doc();
+        while doc != TERMINATED {
+            self.score = self.scorer.score();
+            if passes(self.lower_bound, self.score) {
+                break;
+            }
+            doc = self.scorer.advance();
+        }
+        doc
+    }
+}
+
+impl DocSet for ScoreFilterScorer {
+    fn advance(&mut self) -> DocId {
+        self.scorer.advance();
+        self.skip_filtered()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.scorer.seek(target);
+        self.skip_filtered()
+    }
+
+    fn doc(&self) -> DocId {
+        self.scorer.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.scorer.size_hint()
+    }
+}
+
+impl Scorer for ScoreFilterScorer {
+    fn score(&mut self) -> Score {
+        self.score
+    }
+}
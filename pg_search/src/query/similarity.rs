@@ -0,0 +1,535 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::schema::SearchSimilarity;
+use std::collections::HashMap;
+use std::fmt;
+use tantivy::fieldnorm::FieldNormReader;
+use tantivy::postings::{Postings, SegmentPostings};
+use tantivy::query::{
+    Bm25StatisticsProvider, BooleanQuery, BoostQuery, EmptyScorer, EnableScoring, Explanation,
+    PhrasePrefixQuery, PhraseQuery, Query, QueryParser, Scorer, TermQuery, Weight,
+};
+use tantivy::query_grammar::{Occur, UserInputAst};
+use tantivy::schema::{Field, IndexRecordOption};
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, Term};
+
+/// Rewrite the term and phrase queries in `query` that target a field with a non-default
+/// [`SearchSimilarity`] so that they're scored with that similarity instead of tantivy's BM25.
+///
+/// Boolean queries are rewritten recursively.  Tantivy's other wrappers, such as boosts and
+/// dis-max queries, don't expose the queries they wrap, so we rewrite those as we build them
+/// instead.  Fuzzy, regex and range queries are constant-scored, so they're left as they are.
+pub fn apply_similarity(
+    query: Box<dyn Query>,
+    similarities: &HashMap<Field, SearchSimilarity>,
+) -> Box<dyn Query> {
+    if similarities.is_empty() {
+        return query;
+    }
+
+    // the query parser's output is handed back to us already boxed, and we box it again
+    if let Some(boxed) = query.downcast_ref::<Box<dyn Query>>() {
+        return apply_similarity((**boxed).box_clone(), similarities);
+    }
+
+    if let Some(term_query) = query.downcast_ref::<TermQuery>() {
+        return match similarities.get(&term_query.term().field()) {
+            Some(similarity) => Box::new(SimilarityTermQuery {
+                term: term_query.term().clone(),
+                similarity: *similarity,
+            }),
+            None => query,
+        };
+    }
+
+    if let Some(phrase_query) = query.downcast_ref::<PhraseQuery>() {
+        return match similarities.get(&phrase_query.field()) {
+            Some(similarity) => Box::new(SimilarityPhraseQuery {
+                terms: phrase_query.phrase_terms(),
+                phrase: query,
+                similarity: *similarity,
+            }),
+            None => query,
+        };
+    }
+
+    // a phrase prefix without any whole terms is a constant-scored term range
+    if let Some(phrase_prefix_query) = query.downcast_ref::<PhrasePrefixQuery>() {
+        return match similarities.get(&phrase_prefix_query.field()) {
+            Some(similarity) if !phrase_prefix_query.phrase_terms().is_empty() => {
+                Box::new(SimilarityPhraseQuery {
+                    terms: phrase_prefix_query.phrase_terms(),
+                    phrase: query,
+                    similarity: *similarity,
+                })
+            }
+            _ => query,
+        };
+    }
+
+    if let Some(boolean_query) = query.downcast_ref::<BooleanQuery>() {
+        let clauses = boolean_query
+            .clauses()
+            .iter()
+            .map(|(occur, subquery)| {
+                (
+                    *occur,
+                    apply_similarity((**subquery).box_clone(), similarities),
+                )
+            })
+            .collect();
+        return Box::new(BooleanQuery::with_minimum_required_clauses(
+            clauses,
+            boolean_query.get_minimum_number_should_match(),
+        ));
+    }
+
+    query
+}
+
+/// The query parser wraps the `term^2` boosts of a query string in a [`BoostQuery`], whose
+/// query we can't rewrite.  If `query_string` has boosts, this builds each boosted part of it on
+/// its own, rewrites it, and boosts it ourselves.  Returns [`None`] if there are no boosts.
+///
+/// The query string must already have been parsed successfully by `parser`.
+pub fn parse_with_similarity(
+    parser: &QueryParser,
+    query_string: &str,
+    conjunction_mode: bool,
+    similarities: &HashMap<Field, SearchSimilarity>,
+) -> Option<Box<dyn Query>> {
+    let (ast, _) = tantivy::query_grammar::parse_query_lenient(query_string);
+    if similarities.is_empty() || !has_boost(&ast) {
+        return None;
+    }
+
+    let default_occur = if conjunction_mode {
+        Occur::Must
+    } else {
+        Occur::Should
+    };
+    let query = match build_with_similarity(parser, ast, default_occur, similarities) {
+        // like the parser, a query of only negative clauses matches everything else
+        BuiltQuery::Clauses(mut clauses) => {
+            if clauses.iter().all(|(occur, _)| *occur == Occur::MustNot) {
+                clauses.push((Occur::Must, Box::new(tantivy::query::AllQuery)));
+            }
+            Box::new(BooleanQuery::new(clauses))
+        }
+        BuiltQuery::Query(query) => query,
+    };
+    Some(query)
+}
+
+enum BuiltQuery {
+    Clauses(Vec<(Occur, Box<dyn Query>)>),
+    Query(Box<dyn Query>),
+}
+
+fn build_with_similarity(
+    parser: &QueryParser,
+    ast: UserInputAst,
+    default_occur: Occur,
+    similarities: &HashMap<Field, SearchSimilarity>,
+) -> BuiltQuery {
+    if !has_boost(&ast) {
+        let (query, _) = parser.build_query_from_user_input_ast_lenient(ast);
+        return BuiltQuery::Query(apply_similarity(query, similarities));
+    }
+
+    match ast {
+        UserInputAst::Boost(ast, boost) => {
+            let query = build_with_similarity(parser, *ast, default_occur, similarities).query();
+            BuiltQuery::Query(Box::new(BoostQuery::new(query, boost as Score)))
+        }
+        UserInputAst::Clause(clauses) => BuiltQuery::Clauses(
+            clauses
+                .into_iter()
+                .map(|(occur, ast)| {
+                    let query =
+                        build_with_similarity(parser, ast, default_occur, similarities).query();
+                    (occur.unwrap_or(default_occur), query)
+                })
+                .collect(),
+        ),
+        UserInputAst::Leaf(_) => unreachable!("a leaf has no boost"),
+    }
+}
+
+impl BuiltQuery {
+    fn query(self) -> Box<dyn Query> {
+        match self {
+            BuiltQuery::Clauses(clauses) => Box::new(BooleanQuery::new(clauses)),
+            BuiltQuery::Query(query) => query,
+        }
+    }
+}
+
+fn has_boost(ast: &UserInputAst) -> bool {
+    match ast {
+        UserInputAst::Boost(..) => true,
+        UserInputAst::Clause(clauses) => clauses.iter().any(|(_, ast)| has_boost(ast)),
+        UserInputAst::Leaf(_) => false,
+    }
+}
+
+/// The parts of a term's score that are the same for every document
+#[derive(Copy, Clone)]
+struct TermStatistics {
+    idf: Score,
+    average_fieldnorm: Score,
+}
+
+impl TermStatistics {
+    fn new(
+        similarity: SearchSimilarity,
+        statistics_provider: &dyn Bm25StatisticsProvider,
+        term: &Term,
+    ) -> tantivy::Result<Self> {
+        let total_num_docs = statistics_provider.total_num_docs()?;
+        let total_num_tokens = statistics_provider.total_num_tokens(term.field())?;
+        let doc_freq = statistics_provider.doc_freq(term)?;
+
+        let idf = match similarity {
+            SearchSimilarity::Bm25 { .. } => {
+                let x = (total_num_docs.saturating_sub(doc_freq) as Score + 0.5)
+                    / (doc_freq as Score + 0.5);
+                (1.0 + x).ln()
+            }
+            SearchSimilarity::TfIdf => {
+                1.0 + (total_num_docs as Score / (doc_freq as Score + 1.0)).ln()
+            }
+            SearchSimilarity::Constant => 1.0,
+        };
+        let average_fieldnorm = total_num_tokens as Score / total_num_docs.max(1) as Score;
+
+        Ok(TermStatistics {
+            idf,
+            average_fieldnorm,
+        })
+    }
+
+    /// The score of a term that appears `tf` times in a document of `fieldnorm` tokens
+    fn score(&self, similarity: SearchSimilarity, tf: Score, fieldnorm: Score) -> Score {
+        match similarity {
+            SearchSimilarity::Bm25 { k1, b } => {
+                let norm = k1 * (1.0 - b + b * fieldnorm / self.average_fieldnorm);
+                self.idf * (tf * (k1 + 1.0)) / (tf + norm)
+            }
+            SearchSimilarity::TfIdf => tf.sqrt() * self.idf * self.idf / fieldnorm.max(1.0).sqrt(),
+            SearchSimilarity::Constant => 1.0,
+        }
+    }
+}
+
+/// The fieldnorms of `field`, where fields without fieldnorms are treated as if every document
+/// has a length of 1
+fn fieldnorms(reader: &SegmentReader, field: Field) -> tantivy::Result<FieldNormReader> {
+    Ok(reader
+        .fieldnorms_readers()
+        .get_field(field)?
+        .unwrap_or_else(|| FieldNormReader::constant(reader.max_doc(), 1)))
+}
+
+#[derive(Clone)]
+struct SimilarityTermQuery {
+    term: Term,
+    similarity: SearchSimilarity,
+}
+
+impl fmt::Debug for SimilarityTermQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SimilarityTermQuery({:?}, {:?})",
+            self.term, self.similarity
+        )
+    }
+}
+
+impl Query for SimilarityTermQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        let EnableScoring::Enabled {
+            statistics_provider,
+            ..
+        } = enable_scoring
+        else {
+            // without scoring there's nothing for us to do differently than a plain TermQuery
+            return TermQuery::new(self.term.clone(), IndexRecordOption::Basic)
+                .weight(enable_scoring);
+        };
+
+        Ok(Box::new(SimilarityTermWeight {
+            term: self.term.clone(),
+            similarity: self.similarity,
+            statistics: TermStatistics::new(self.similarity, statistics_provider, &self.term)?,
+        }))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        visitor(&self.term, false);
+    }
+}
+
+struct SimilarityTermWeight {
+    term: Term,
+    similarity: SearchSimilarity,
+    statistics: TermStatistics,
+}
+
+impl SimilarityTermWeight {
+    fn term_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> tantivy::Result<Option<SimilarityTermScorer>> {
+        let field = self.term.field();
+        let Some(postings) = reader
+            .inverted_index(field)?
+            .read_postings(&self.term, IndexRecordOption::WithFreqs)?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(SimilarityTermScorer {
+            postings,
+            fieldnorms: fieldnorms(reader, field)?,
+            similarity: self.similarity,
+            statistics: self.statistics,
+            boost,
+        }))
+    }
+}
+
+impl Weight for SimilarityTermWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        match self.term_scorer(reader, boost)? {
+            Some(scorer) => Ok(Box::new(scorer)),
+            None => Ok(Box::new(EmptyScorer)),
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer = self.term_scorer(reader, 1.0)?;
+        match scorer.as_mut() {
+            Some(scorer) if scorer.seek(doc) == doc => Ok(Explanation::new_with_string(
+                format!("{:?} similarity", self.similarity),
+                scorer.score(),
+            )),
+            _ => Err(TantivyError::InvalidArgument(format!(
+                "Document #({doc}) does not match"
+            ))),
+        }
+    }
+}
+
+struct SimilarityTermScorer {
+    postings: SegmentPostings,
+    fieldnorms: FieldNormReader,
+    similarity: SearchSimilarity,
+    statistics: TermStatistics,
+    boost: Score,
+}
+
+impl DocSet for SimilarityTermScorer {
+    fn advance(&mut self) -> DocId {
+        self.postings.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.postings.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+impl Scorer for SimilarityTermScorer {
+    fn score(&mut self) -> Score {
+        let tf = self.postings.term_freq() as Score;
+        let fieldnorm = self.fieldnorms.fieldnorm(self.postings.doc()) as Score;
+        self.statistics.score(self.similarity, tf, fieldnorm) * self.boost
+    }
+}
+
+/// A phrase, or phrase prefix, query whose matches are scored with a [`SearchSimilarity`].
+///
+/// The phrase itself decides which documents match, and each of its whole `terms` is then
+/// scored on its own, with the phrase scoring as the sum of its terms.  This approximates how
+/// tantivy scores a phrase:  a term is scored by how often it appears anywhere in the document
+/// rather than by how often the phrase does, and the prefix of a phrase prefix isn't scored.
+struct SimilarityPhraseQuery {
+    phrase: Box<dyn Query>,
+    terms: Vec<Term>,
+    similarity: SearchSimilarity,
+}
+
+impl Clone for SimilarityPhraseQuery {
+    fn clone(&self) -> Self {
+        Self {
+            phrase: self.phrase.box_clone(),
+            terms: self.terms.clone(),
+            similarity: self.similarity,
+        }
+    }
+}
+
+impl fmt::Debug for SimilarityPhraseQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "SimilarityPhraseQuery({:?}, {:?})",
+            self.phrase, self.similarity
+        )
+    }
+}
+
+impl Query for SimilarityPhraseQuery {
+    fn weight(&self, enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        let EnableScoring::Enabled {
+            searcher,
+            statistics_provider,
+        } = enable_scoring
+        else {
+            return self.phrase.weight(enable_scoring);
+        };
+
+        let terms = self
+            .terms
+            .iter()
+            .map(|term| {
+                let statistics = TermStatistics::new(self.similarity, statistics_provider, term)?;
+                Ok((term.clone(), statistics))
+            })
+            .collect::<tantivy::Result<_>>()?;
+
+        Ok(Box::new(SimilarityPhraseWeight {
+            phrase: self
+                .phrase
+                .weight(EnableScoring::disabled_from_searcher(searcher))?,
+            terms,
+            similarity: self.similarity,
+        }))
+    }
+
+    fn query_terms<'a>(&'a self, visitor: &mut dyn FnMut(&'a Term, bool)) {
+        self.phrase.query_terms(visitor);
+    }
+}
+
+struct SimilarityPhraseWeight {
+    phrase: Box<dyn Weight>,
+    terms: Vec<(Term, TermStatistics)>,
+    similarity: SearchSimilarity,
+}
+
+impl SimilarityPhraseWeight {
+    fn phrase_scorer(
+        &self,
+        reader: &SegmentReader,
+        boost: Score,
+    ) -> tantivy::Result<SimilarityPhraseScorer> {
+        let mut terms = vec![];
+        for (term, statistics) in &self.terms {
+            if let Some(postings) = reader
+                .inverted_index(term.field())?
+                .read_postings(term, IndexRecordOption::WithFreqs)?
+            {
+                terms.push((postings, *statistics));
+            }
+        }
+
+        let field = self.terms[0].0.field();
+        Ok(SimilarityPhraseScorer {
+            phrase: self.phrase.scorer(reader, 1.0)?,
+            terms,
+            fieldnorms: fieldnorms(reader, field)?,
+            similarity: self.similarity,
+            boost,
+        })
+    }
+}
+
+impl Weight for SimilarityPhraseWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        Ok(Box::new(self.phrase_scorer(reader, boost)?))
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer = self.phrase_scorer(reader, 1.0)?;
+        if scorer.seek(doc) != doc {
+            return Err(TantivyError::InvalidArgument(format!(
+                "Document #({doc}) does not match"
+            )));
+        }
+        Ok(Explanation::new_with_string(
+            format!("{:?} similarity", self.similarity),
+            scorer.score(),
+        ))
+    }
+}
+
+struct SimilarityPhraseScorer {
+    phrase: Box<dyn Scorer>,
+    terms: Vec<(SegmentPostings, TermStatistics)>,
+    fieldnorms: FieldNormReader,
+    similarity: SearchSimilarity,
+    boost: Score,
+}
+
+impl DocSet for SimilarityPhraseScorer {
+    fn advance(&mut self) -> DocId {
+        self.phrase.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.phrase.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.phrase.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.phrase.size_hint()
+    }
+}
+
+impl Scorer for SimilarityPhraseScorer {
+    fn score(&mut self) -> Score {
+        let doc = self.phrase.doc();
+        let fieldnorm = self.fieldnorms.fieldnorm(doc) as Score;
+
+        let mut score = 0.0;
+        for (postings, statistics) in &mut self.terms {
+            if postings.doc() < doc {
+                postings.seek(doc);
+            }
+            if postings.doc() == doc {
+                let tf = postings.term_freq() as Score;
+                score += statistics.score(self.similarity, tf, fieldnorm);
+            }
+        }
+        score * self.boost
+    }
+}
@@ -0,0 +1,112 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use crate::schema::sparse::sparse_weight;
+use std::fmt;
+use tantivy::postings::{Postings, SegmentPostings};
+use tantivy::query::{EmptyScorer, EnableScoring, Explanation, Query, Scorer, Weight};
+use tantivy::schema::IndexRecordOption;
+use tantivy::{DocId, DocSet, Score, SegmentReader, TantivyError, Term};
+
+/// Matches the documents whose sparse vector has the token of `term`, and scores them by the
+/// product of `weight` and the document's weight for the token.  A boolean query of these adds
+/// up to the dot product of the two sparse vectors.
+#[derive(Clone)]
+pub struct SparseTermQuery {
+    term: Term,
+    weight: f32,
+}
+
+impl SparseTermQuery {
+    pub fn new(term: Term, weight: f32) -> Self {
+        Self { term, weight }
+    }
+}
+
+impl fmt::Debug for SparseTermQuery {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SparseTermQuery({:?}, {})", self.term, self.weight)
+    }
+}
+
+impl Query for SparseTermQuery {
+    fn weight(&self, _enable_scoring: EnableScoring<'_>) -> tantivy::Result<Box<dyn Weight>> {
+        Ok(Box::new(SparseTermWeight {
+            term: self.term.clone(),
+            weight: self.weight,
+        }))
+    }
+}
+
+struct SparseTermWeight {
+    term: Term,
+    weight: f32,
+}
+
+impl Weight for SparseTermWeight {
+    fn scorer(&self, reader: &SegmentReader, boost: Score) -> tantivy::Result<Box<dyn Scorer>> {
+        let inverted_index = reader.inverted_index(self.term.field())?;
+        match inverted_index.read_postings(&self.term, IndexRecordOption::WithFreqs)? {
+            Some(postings) => Ok(Box::new(SparseScorer {
+                postings,
+                weight: self.weight * boost,
+            })),
+            None => Ok(Box::new(EmptyScorer)),
+        }
+    }
+
+    fn explain(&self, reader: &SegmentReader, doc: DocId) -> tantivy::Result<Explanation> {
+        let mut scorer = self.scorer(reader, 1.0)?;
+        if scorer.seek(doc) == doc {
+            Ok(Explanation::new("sparse vector weight", scorer.score()))
+        } else {
+            Err(TantivyError::InvalidArgument(format!(
+                "Document #({doc}) does not match"
+            )))
+        }
+    }
+}
+
+/// Scores each document of a token's postings by the weight its term frequency stands for
+struct SparseScorer {
+    postings: SegmentPostings,
+    weight: Score,
+}
+
+impl DocSet for SparseScorer {
+    fn advance(&mut self) -> DocId {
+        self.postings.advance()
+    }
+
+    fn seek(&mut self, target: DocId) -> DocId {
+        self.postings.seek(target)
+    }
+
+    fn doc(&self) -> DocId {
+        self.postings.doc()
+    }
+
+    fn size_hint(&self) -> u32 {
+        self.postings.size_hint()
+    }
+}
+
+impl Scorer for SparseScorer {
+    fn score(&mut self) -> Score {
+        sparse_weight(self.postings.term_freq()) * self.weight
+    }
+}
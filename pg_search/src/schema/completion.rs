@@ -0,0 +1,73 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Completion fields index each of their inputs as a single term, so that every segment's term
+//! dictionary is a sorted dictionary of that segment's weighted inputs, which we can walk with a
+//! prefix automaton.
+//!
+//! Each term is the lowercased input, which is what prefixes are matched against, followed by
+//! [`SEPARATOR`] and the input as it was written, which is what we suggest.  If the field has a
+//! weight, that's followed by another [`SEPARATOR`] and the bits of the row's weight in hex, so
+//! that ranking a completion doesn't need to look up the rows it came from.
+
+use tantivy::schema::OwnedValue;
+use tantivy::tokenizer::{PreTokenizedString, Token};
+
+pub const SEPARATOR: char = '\u{0}';
+
+/// Convert a value of a completion field, from a row whose weight is `weight`, into the single
+/// term it's indexed as
+pub fn completion_value(value: OwnedValue, weight: Option<f64>) -> OwnedValue {
+    match value {
+        OwnedValue::Str(input) => {
+            let input = input.trim();
+            let mut term = format!("{}{SEPARATOR}{input}", input.to_lowercase());
+            if let Some(weight) = weight {
+                term.push_str(&format!("{SEPARATOR}{:016x}", weight.to_bits()));
+            }
+            OwnedValue::PreTokStr(PreTokenizedString {
+                text: input.to_string(),
+                tokens: vec![Token {
+                    offset_from: 0,
+                    offset_to: input.len(),
+                    position: 0,
+                    text: term,
+                    position_length: 1,
+                }],
+            })
+        }
+        other => other,
+    }
+}
+
+/// The prefix that completion terms are matched against for `input`
+pub fn completion_prefix(input: &str) -> String {
+    input.trim().to_lowercase()
+}
+
+/// The original input that a completion field's term was indexed from, and the weight of its row
+/// if the field has a weight
+pub fn completion_term(term: &[u8]) -> Option<(&str, Option<f64>)> {
+    let term = std::str::from_utf8(term).ok()?;
+    let mut parts = term.split(SEPARATOR).skip(1);
+    let input = parts.next()?;
+    let weight = match parts.next() {
+        Some(bits) => Some(f64::from_bits(u64::from_str_radix(bits, 16).ok()?)),
+        None => None,
+    };
+    Some((input, weight))
+}
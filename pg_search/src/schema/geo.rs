@@ -0,0 +1,99 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Geo point fields store each row's latitude and longitude as the little-endian bytes of two
+//! `f64`s, in a bytes fast field, so that they can be read back per document when filtering or
+//! sorting by location.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use tantivy::schema::OwnedValue;
+
+/// The mean radius of the Earth, in meters
+const EARTH_RADIUS_METERS: f64 = 6_371_008.8;
+
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeoPoint {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl GeoPoint {
+    /// A point at `lat` and `lon` degrees, or [`None`] if either is out of range
+    pub fn new(lat: f64, lon: f64) -> Option<Self> {
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon))
+            .then_some(Self { lat, lon })
+    }
+
+    /// A point from a `{"lat": ..., "lon": ...}` JSON object
+    pub fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let obj = value.as_object()?;
+        Self::new(obj.get("lat")?.as_f64()?, obj.get("lon")?.as_f64()?)
+    }
+
+    /// The great-circle distance to `other`, in meters
+    pub fn distance(&self, other: &GeoPoint) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.lon - self.lon).to_radians();
+        let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_METERS * a.sqrt().min(1.0).asin()
+    }
+}
+
+impl Display for GeoPoint {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.lat, self.lon)
+    }
+}
+
+impl FromStr for GeoPoint {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (lat, lon) = s
+            .split_once(',')
+            .ok_or_else(|| anyhow::anyhow!("'{s}' is not a 'lat,lon' pair"))?;
+        Self::new(lat.parse()?, lon.parse()?)
+            .ok_or_else(|| anyhow::anyhow!("'{s}' is not a valid geo point"))
+    }
+}
+
+/// Convert a point into the value it's stored as
+pub fn geo_value(point: GeoPoint) -> OwnedValue {
+    OwnedValue::Bytes(
+        point
+            .lat
+            .to_le_bytes()
+            .into_iter()
+            .chain(point.lon.to_le_bytes())
+            .collect(),
+    )
+}
+
+/// Read back a point that was stored by [`geo_value`], or [`None`] if `bytes` isn't one
+pub fn read_geo(bytes: &[u8]) -> Option<GeoPoint> {
+    if bytes.len() != 2 * std::mem::size_of::<f64>() {
+        return None;
+    }
+    let (lat, lon) = bytes.split_at(std::mem::size_of::<f64>());
+    Some(GeoPoint {
+        lat: f64::from_le_bytes(lat.try_into().ok()?),
+        lon: f64::from_le_bytes(lon.try_into().ok()?),
+    })
+}
@@ -16,8 +16,13 @@
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
 mod anyenum;
+pub mod completion;
 mod document;
+pub mod geo;
 pub mod range;
+mod similarity;
+pub mod sparse;
+pub mod vector;
 
 use anyhow::{Context, Result};
 use derive_more::{AsRef, Display, From, Into};
@@ -28,14 +33,17 @@ use serde_json::json;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use tantivy::schema::{
-    DateOptions, Field, JsonObjectOptions, NumericOptions, Schema, TextFieldIndexing, TextOptions,
+    BytesOptions, DateOptions, Field, IpAddrOptions, JsonObjectOptions, NumericOptions, Schema,
+    TextFieldIndexing, TextOptions,
 };
 use thiserror::Error;
 use tokenizers::{SearchNormalizer, SearchTokenizer};
 
 use crate::postgres::index::get_fields;
+use crate::postgres::range::multirange_range_typeoid;
 use crate::query::AsFieldType;
 pub use anyenum::AnyEnum;
+pub use similarity::SearchSimilarity;
 
 /// The id of a field, stored in the index.
 #[derive(Debug, Clone, Display, From, AsRef, PartialEq, Eq, Serialize, Deserialize, Hash)]
@@ -50,6 +58,13 @@ pub struct SearchFieldId(pub Field);
 /// The name of the index, as it appears to Postgres.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct SearchIndexName(pub String);
+
+/// The name of the field that records the fields of a row whose column is NULL.
+pub const NULLS_FIELD_NAME: &str = "_pg_search_nulls";
+
+/// The largest scale of an exact decimal field, whose scaled values must fit in an `i64`.
+pub const MAX_DECIMAL_SCALE: u32 = 18;
+
 /// The type of the search field.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SearchFieldType {
@@ -61,6 +76,9 @@ pub enum SearchFieldType {
     Json,
     Date,
     Range,
+    Geo,
+    Inet,
+    Bytes,
 }
 
 impl TryFrom<&PgOid> for SearchFieldType {
@@ -68,9 +86,10 @@ impl TryFrom<&PgOid> for SearchFieldType {
     fn try_from(pg_oid: &PgOid) -> Result<Self, Self::Error> {
         match &pg_oid {
             PgOid::BuiltIn(builtin) => match builtin {
-                PgBuiltInOids::TEXTOID | PgBuiltInOids::VARCHAROID | PgBuiltInOids::UUIDOID => {
-                    Ok(SearchFieldType::Text)
-                }
+                PgBuiltInOids::TEXTOID
+                | PgBuiltInOids::VARCHAROID
+                | PgBuiltInOids::UUIDOID
+                | PgBuiltInOids::TSVECTOROID => Ok(SearchFieldType::Text),
                 PgBuiltInOids::INT2OID | PgBuiltInOids::INT4OID | PgBuiltInOids::INT8OID => {
                     Ok(SearchFieldType::I64)
                 }
@@ -91,6 +110,10 @@ impl TryFrom<&PgOid> for SearchFieldType {
                 | PgBuiltInOids::TIMESTAMPTZOID
                 | PgBuiltInOids::TIMEOID
                 | PgBuiltInOids::TIMETZOID => Ok(SearchFieldType::Date),
+                PgBuiltInOids::POINTOID => Ok(SearchFieldType::Geo),
+                PgBuiltInOids::INETOID | PgBuiltInOids::CIDROID => Ok(SearchFieldType::Inet),
+                PgBuiltInOids::BYTEAOID => Ok(SearchFieldType::Bytes),
+                _ if multirange_range_typeoid(*pg_oid).is_some() => Ok(SearchFieldType::Range),
                 _ => Err(SearchIndexSchemaError::InvalidPgOid(*pg_oid)),
             },
             PgOid::Custom(custom) => {
@@ -105,7 +128,7 @@ impl TryFrom<&PgOid> for SearchFieldType {
     }
 }
 
-#[derive(Deserialize, Serialize, Clone, Debug, PartialEq, Eq)]
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
 pub enum SearchFieldConfig {
     Text {
         #[serde(default = "default_as_true")]
@@ -123,6 +146,10 @@ pub enum SearchFieldConfig {
         #[serde(default)]
         normalizer: SearchNormalizer,
         #[serde(default)]
+        similarity: SearchSimilarity,
+        #[serde(default)]
+        index_nulls: bool,
+        #[serde(default)]
         column: Option<String>,
     },
     Json {
@@ -143,12 +170,18 @@ pub enum SearchFieldConfig {
         #[serde(default)]
         normalizer: SearchNormalizer,
         #[serde(default)]
+        similarity: SearchSimilarity,
+        #[serde(default)]
+        index_nulls: bool,
+        #[serde(default)]
         column: Option<String>,
     },
     Range {
         #[serde(default = "default_as_false")]
         stored: bool,
         #[serde(default)]
+        index_nulls: bool,
+        #[serde(default)]
         column: Option<String>,
     },
     Numeric {
@@ -159,6 +192,12 @@ pub enum SearchFieldConfig {
         #[serde(default = "default_as_false")]
         stored: bool,
         #[serde(default)]
+        index_nulls: bool,
+        /// The number of decimal digits kept by a `numeric` column indexed as an exact decimal,
+        /// whose values are stored as integers scaled by `10^scale`
+        #[serde(default)]
+        scale: Option<u32>,
+        #[serde(default)]
         column: Option<String>,
     },
     Boolean {
@@ -169,6 +208,8 @@ pub enum SearchFieldConfig {
         #[serde(default = "default_as_false")]
         stored: bool,
         #[serde(default)]
+        index_nulls: bool,
+        #[serde(default)]
         column: Option<String>,
     },
     Date {
@@ -178,6 +219,66 @@ pub enum SearchFieldConfig {
         fast: bool,
         #[serde(default = "default_as_false")]
         stored: bool,
+        #[serde(default)]
+        index_nulls: bool,
+        #[serde(default)]
+        column: Option<String>,
+    },
+    Inet {
+        #[serde(default = "default_as_true")]
+        indexed: bool,
+        #[serde(default = "default_as_true")]
+        fast: bool,
+        #[serde(default = "default_as_false")]
+        stored: bool,
+        #[serde(default)]
+        index_nulls: bool,
+        #[serde(default)]
+        column: Option<String>,
+    },
+    Bytes {
+        #[serde(default = "default_as_true")]
+        indexed: bool,
+        #[serde(default = "default_as_true")]
+        fast: bool,
+        #[serde(default = "default_as_false")]
+        stored: bool,
+        #[serde(default)]
+        index_nulls: bool,
+        #[serde(default)]
+        column: Option<String>,
+    },
+    /// A uuid column, indexed as the 128-bit value of each uuid rather than as text
+    Uuid {
+        #[serde(default = "default_as_true")]
+        indexed: bool,
+        #[serde(default = "default_as_true")]
+        fast: bool,
+        #[serde(default = "default_as_false")]
+        stored: bool,
+        #[serde(default)]
+        index_nulls: bool,
+        #[serde(default)]
+        column: Option<String>,
+    },
+    Completion {
+        /// A numeric fast field whose value ranks the suggestions of each row
+        #[serde(default)]
+        weight: Option<String>,
+        #[serde(default)]
+        column: Option<String>,
+    },
+    Vector {
+        /// The number of elements every vector of the field must have
+        dimensions: usize,
+        #[serde(default)]
+        column: Option<String>,
+    },
+    SparseVector {
+        #[serde(default)]
+        column: Option<String>,
+    },
+    Geo {
         #[serde(default)]
         column: Option<String>,
     },
@@ -232,6 +333,18 @@ impl SearchFieldConfig {
             None => Ok(SearchNormalizer::Raw),
         }?;
 
+        let similarity = match obj.get("similarity") {
+            Some(v) => SearchSimilarity::from_json_value(v),
+            None => Ok(SearchSimilarity::default()),
+        }?;
+
+        let index_nulls = match obj.get("index_nulls") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'index_nulls' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
         let column = match obj.get("column") {
             Some(v) => v
                 .as_str()
@@ -248,6 +361,8 @@ impl SearchFieldConfig {
             tokenizer,
             record,
             normalizer,
+            similarity,
+            index_nulls,
             column,
         })
     }
@@ -300,6 +415,11 @@ impl SearchFieldConfig {
             None => Ok(SearchNormalizer::Raw),
         }?;
 
+        let similarity = match obj.get("similarity") {
+            Some(v) => SearchSimilarity::from_json_value(v),
+            None => Ok(SearchSimilarity::default()),
+        }?;
+
         let fieldnorms = match obj.get("fieldnorms") {
             Some(v) => v
                 .as_bool()
@@ -307,6 +427,13 @@ impl SearchFieldConfig {
             None => Ok(true),
         }?;
 
+        let index_nulls = match obj.get("index_nulls") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'index_nulls' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
         let column = match obj.get("column") {
             Some(v) => v
                 .as_str()
@@ -324,6 +451,8 @@ impl SearchFieldConfig {
             tokenizer,
             record,
             normalizer,
+            similarity,
+            index_nulls,
             column,
         })
     }
@@ -340,6 +469,13 @@ impl SearchFieldConfig {
             None => Ok(false),
         }?;
 
+        let index_nulls = match obj.get("index_nulls") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'index_nulls' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
         let column = match obj.get("column") {
             Some(v) => v
                 .as_str()
@@ -348,7 +484,11 @@ impl SearchFieldConfig {
             None => Ok(None),
         }?;
 
-        Ok(SearchFieldConfig::Range { stored, column })
+        Ok(SearchFieldConfig::Range {
+            stored,
+            index_nulls,
+            column,
+        })
     }
 
     pub fn numeric_from_json(value: serde_json::Value) -> Result<Self> {
@@ -377,6 +517,26 @@ impl SearchFieldConfig {
             None => Ok(false),
         }?;
 
+        let index_nulls = match obj.get("index_nulls") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'index_nulls' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
+        let scale = match obj.get("scale") {
+            Some(v) => v
+                .as_u64()
+                .filter(|scale| *scale <= MAX_DECIMAL_SCALE as u64)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "'scale' field should be an integer between 0 and {MAX_DECIMAL_SCALE}"
+                    )
+                })
+                .map(|scale| Some(scale as u32)),
+            None => Ok(None),
+        }?;
+
         let column = match obj.get("column") {
             Some(v) => v
                 .as_str()
@@ -389,6 +549,8 @@ impl SearchFieldConfig {
             indexed,
             fast,
             stored,
+            index_nulls,
+            scale,
             column,
         })
     }
@@ -419,6 +581,13 @@ impl SearchFieldConfig {
             None => Ok(false),
         }?;
 
+        let index_nulls = match obj.get("index_nulls") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'index_nulls' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
         let column = match obj.get("column") {
             Some(v) => v
                 .as_str()
@@ -431,6 +600,7 @@ impl SearchFieldConfig {
             indexed,
             fast,
             stored,
+            index_nulls,
             column,
         })
     }
@@ -461,6 +631,13 @@ impl SearchFieldConfig {
             None => Ok(false),
         }?;
 
+        let index_nulls = match obj.get("index_nulls") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'index_nulls' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
         let column = match obj.get("column") {
             Some(v) => v
                 .as_str()
@@ -473,10 +650,242 @@ impl SearchFieldConfig {
             indexed,
             fast,
             stored,
+            index_nulls,
+            column,
+        })
+    }
+
+    pub fn inet_from_json(value: serde_json::Value) -> Result<Self> {
+        let obj = value
+            .as_object()
+            .context("Expected a JSON object for Inet configuration")?;
+
+        let indexed = match obj.get("indexed") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'indexed' field should be a boolean")),
+            None => Ok(true),
+        }?;
+
+        let fast = match obj.get("fast") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'fast' field should be a boolean")),
+            None => Ok(true),
+        }?;
+
+        let stored = match obj.get("stored") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'stored' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
+        let index_nulls = match obj.get("index_nulls") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'index_nulls' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
+        let column = match obj.get("column") {
+            Some(v) => v
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'column' field should be a string"))
+                .map(|s| Some(s.to_string())),
+            None => Ok(None),
+        }?;
+
+        Ok(SearchFieldConfig::Inet {
+            indexed,
+            fast,
+            stored,
+            index_nulls,
+            column,
+        })
+    }
+
+    pub fn bytes_from_json(value: serde_json::Value) -> Result<Self> {
+        let obj = value
+            .as_object()
+            .context("Expected a JSON object for Bytes configuration")?;
+
+        let indexed = match obj.get("indexed") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'indexed' field should be a boolean")),
+            None => Ok(true),
+        }?;
+
+        let fast = match obj.get("fast") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'fast' field should be a boolean")),
+            None => Ok(true),
+        }?;
+
+        let stored = match obj.get("stored") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'stored' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
+        let index_nulls = match obj.get("index_nulls") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'index_nulls' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
+        let column = match obj.get("column") {
+            Some(v) => v
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'column' field should be a string"))
+                .map(|s| Some(s.to_string())),
+            None => Ok(None),
+        }?;
+
+        Ok(SearchFieldConfig::Bytes {
+            indexed,
+            fast,
+            stored,
+            index_nulls,
+            column,
+        })
+    }
+
+    pub fn uuid_from_json(value: serde_json::Value) -> Result<Self> {
+        let obj = value
+            .as_object()
+            .context("Expected a JSON object for Uuid configuration")?;
+
+        let indexed = match obj.get("indexed") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'indexed' field should be a boolean")),
+            None => Ok(true),
+        }?;
+
+        let fast = match obj.get("fast") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'fast' field should be a boolean")),
+            None => Ok(true),
+        }?;
+
+        let stored = match obj.get("stored") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'stored' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
+        let index_nulls = match obj.get("index_nulls") {
+            Some(v) => v
+                .as_bool()
+                .ok_or_else(|| anyhow::anyhow!("'index_nulls' field should be a boolean")),
+            None => Ok(false),
+        }?;
+
+        let column = match obj.get("column") {
+            Some(v) => v
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'column' field should be a string"))
+                .map(|s| Some(s.to_string())),
+            None => Ok(None),
+        }?;
+
+        Ok(SearchFieldConfig::Uuid {
+            indexed,
+            fast,
+            stored,
+            index_nulls,
             column,
         })
     }
 
+    pub fn completion_from_json(value: serde_json::Value) -> Result<Self> {
+        let obj = value
+            .as_object()
+            .context("Expected a JSON object for Completion configuration")?;
+
+        let weight = match obj.get("weight") {
+            Some(v) => v
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'weight' field should be a string"))
+                .map(|s| Some(s.to_string())),
+            None => Ok(None),
+        }?;
+
+        let column = match obj.get("column") {
+            Some(v) => v
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'column' field should be a string"))
+                .map(|s| Some(s.to_string())),
+            None => Ok(None),
+        }?;
+
+        Ok(SearchFieldConfig::Completion { weight, column })
+    }
+
+    pub fn vector_from_json(value: serde_json::Value) -> Result<Self> {
+        let obj = value
+            .as_object()
+            .context("Expected a JSON object for Vector configuration")?;
+
+        let dimensions = match obj.get("dimensions") {
+            Some(v) => v
+                .as_u64()
+                .filter(|dimensions| *dimensions > 0)
+                .ok_or_else(|| anyhow::anyhow!("'dimensions' field should be a positive integer"))
+                .map(|dimensions| dimensions as usize),
+            None => Err(anyhow::anyhow!("'dimensions' field is required")),
+        }?;
+
+        let column = match obj.get("column") {
+            Some(v) => v
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'column' field should be a string"))
+                .map(|s| Some(s.to_string())),
+            None => Ok(None),
+        }?;
+
+        Ok(SearchFieldConfig::Vector { dimensions, column })
+    }
+
+    pub fn sparse_vector_from_json(value: serde_json::Value) -> Result<Self> {
+        let obj = value
+            .as_object()
+            .context("Expected a JSON object for SparseVector configuration")?;
+
+        let column = match obj.get("column") {
+            Some(v) => v
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'column' field should be a string"))
+                .map(|s| Some(s.to_string())),
+            None => Ok(None),
+        }?;
+
+        Ok(SearchFieldConfig::SparseVector { column })
+    }
+
+    pub fn geo_from_json(value: serde_json::Value) -> Result<Self> {
+        let obj = value
+            .as_object()
+            .context("Expected a JSON object for Geo configuration")?;
+
+        let column = match obj.get("column") {
+            Some(v) => v
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("'column' field should be a string"))
+                .map(|s| Some(s.to_string())),
+            None => Ok(None),
+        }?;
+
+        Ok(SearchFieldConfig::Geo { column })
+    }
+
     pub fn column(&self) -> Option<&String> {
         match self {
             Self::Text { column, .. }
@@ -484,7 +893,42 @@ impl SearchFieldConfig {
             | Self::Range { column, .. }
             | Self::Numeric { column, .. }
             | Self::Boolean { column, .. }
-            | Self::Date { column, .. } => column.as_ref(),
+            | Self::Date { column, .. }
+            | Self::Inet { column, .. }
+            | Self::Bytes { column, .. }
+            | Self::Uuid { column, .. }
+            | Self::Completion { column, .. }
+            | Self::Vector { column, .. }
+            | Self::SparseVector { column, .. }
+            | Self::Geo { column, .. } => column.as_ref(),
+        }
+    }
+
+    /// The scale of a `numeric` field indexed as an exact decimal, if it is one
+    pub fn decimal_scale(&self) -> Option<u32> {
+        match self {
+            Self::Numeric { scale, .. } => *scale,
+            _ => None,
+        }
+    }
+
+    /// Whether the rows where the field's column is NULL are recorded in the index, so that
+    /// they can be searched for with [`crate::query::SearchQueryInput::IsNull`]
+    pub fn index_nulls(&self) -> bool {
+        match self {
+            Self::Text { index_nulls, .. }
+            | Self::Json { index_nulls, .. }
+            | Self::Range { index_nulls, .. }
+            | Self::Numeric { index_nulls, .. }
+            | Self::Boolean { index_nulls, .. }
+            | Self::Date { index_nulls, .. }
+            | Self::Inet { index_nulls, .. }
+            | Self::Bytes { index_nulls, .. }
+            | Self::Uuid { index_nulls, .. } => *index_nulls,
+            Self::Completion { .. }
+            | Self::Vector { .. }
+            | Self::SparseVector { .. }
+            | Self::Geo { .. } => false,
         }
     }
 }
@@ -545,6 +989,22 @@ impl From<SearchFieldConfig> for TextOptions {
                     text_options = text_options.set_indexing_options(text_field_indexing);
                 }
             }
+            SearchFieldConfig::Completion { .. } => {
+                // inputs are added pre-tokenized, so the tokenizer is never actually used
+                let text_field_indexing = TextFieldIndexing::default()
+                    .set_index_option(IndexRecordOption::Basic.into())
+                    .set_fieldnorms(false)
+                    .set_tokenizer("raw");
+                text_options = text_options.set_indexing_options(text_field_indexing);
+            }
+            SearchFieldConfig::SparseVector { .. } => {
+                // weights are part of each term, and are added pre-tokenized
+                let text_field_indexing = TextFieldIndexing::default()
+                    .set_index_option(IndexRecordOption::Basic.into())
+                    .set_fieldnorms(false)
+                    .set_tokenizer("raw");
+                text_options = text_options.set_indexing_options(text_field_indexing);
+            }
             _ => panic!("attempted to convert non-text search field config to tantivy text config"),
         }
         text_options
@@ -662,7 +1122,73 @@ impl From<SearchFieldConfig> for DateOptions {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+impl From<SearchFieldConfig> for IpAddrOptions {
+    fn from(config: SearchFieldConfig) -> Self {
+        let mut ip_addr_options = IpAddrOptions::default();
+        match config {
+            SearchFieldConfig::Inet {
+                indexed,
+                fast,
+                stored,
+                ..
+            }
+            | SearchFieldConfig::Uuid {
+                indexed,
+                fast,
+                stored,
+                ..
+            } => {
+                if stored {
+                    ip_addr_options = ip_addr_options.set_stored();
+                }
+                if fast {
+                    ip_addr_options = ip_addr_options.set_fast();
+                }
+                if indexed {
+                    ip_addr_options = ip_addr_options.set_indexed();
+                }
+            }
+            _ => {
+                panic!("attempted to convert non-inet search field config to tantivy ip config")
+            }
+        }
+        ip_addr_options
+    }
+}
+
+impl From<SearchFieldConfig> for BytesOptions {
+    fn from(config: SearchFieldConfig) -> Self {
+        match config {
+            // vectors and geo points are only ever read back from their fast field
+            SearchFieldConfig::Vector { .. } | SearchFieldConfig::Geo { .. } => {
+                BytesOptions::default().set_fast()
+            }
+            SearchFieldConfig::Bytes {
+                indexed,
+                fast,
+                stored,
+                ..
+            } => {
+                let mut bytes_options = BytesOptions::default();
+                if stored {
+                    bytes_options = bytes_options.set_stored();
+                }
+                if fast {
+                    bytes_options = bytes_options.set_fast();
+                }
+                if indexed {
+                    bytes_options = bytes_options.set_indexed();
+                }
+                bytes_options
+            }
+            _ => {
+                panic!("attempted to convert non-bytes search field config to tantivy bytes config")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct SearchField {
     /// The id of the field, stored in the index.
     pub id: SearchFieldId,
@@ -703,7 +1229,30 @@ impl SearchIndexSchema {
         let mut search_fields = vec![];
 
         for (name, config, field_type) in fields {
+            if name.0 == NULLS_FIELD_NAME {
+                return Err(SearchIndexSchemaError::ReservedFieldName(name.0));
+            }
+
+            // vectors and geo points are stored as bytes, sparse vectors as text and uuids as the
+            // 128-bit values of IP address fields, whatever the type of their column
             let id: SearchFieldId = match field_type {
+                _ if matches!(
+                    config,
+                    SearchFieldConfig::Vector { .. } | SearchFieldConfig::Geo { .. }
+                ) =>
+                {
+                    builder.add_bytes_field(name.as_ref(), config.clone())
+                }
+                _ if matches!(config, SearchFieldConfig::SparseVector { .. }) => {
+                    builder.add_text_field(name.as_ref(), config.clone())
+                }
+                _ if matches!(config, SearchFieldConfig::Uuid { .. }) => {
+                    builder.add_ip_addr_field(name.as_ref(), config.clone())
+                }
+                // exact decimals are stored as scaled integers
+                SearchFieldType::F64 if config.decimal_scale().is_some() => {
+                    builder.add_i64_field(name.as_ref(), config.clone())
+                }
                 SearchFieldType::Text => builder.add_text_field(name.as_ref(), config.clone()),
                 SearchFieldType::I64 => builder.add_i64_field(name.as_ref(), config.clone()),
                 SearchFieldType::U64 => builder.add_u64_field(name.as_ref(), config.clone()),
@@ -712,6 +1261,9 @@ impl SearchIndexSchema {
                 SearchFieldType::Json => builder.add_json_field(name.as_ref(), config.clone()),
                 SearchFieldType::Range => builder.add_json_field(name.as_ref(), config.clone()),
                 SearchFieldType::Date => builder.add_date_field(name.as_ref(), config.clone()),
+                SearchFieldType::Geo => builder.add_bytes_field(name.as_ref(), config.clone()),
+                SearchFieldType::Inet => builder.add_ip_addr_field(name.as_ref(), config.clone()),
+                SearchFieldType::Bytes => builder.add_bytes_field(name.as_ref(), config.clone()),
             }
             .into();
 
@@ -727,6 +1279,18 @@ impl SearchIndexSchema {
         // so we don't need to worry about name conflicts
         builder.add_u64_field("ctid", tantivy::schema::INDEXED | tantivy::schema::FAST);
 
+        // the names of the fields whose column is NULL, for the fields that index their nulls
+        if search_fields.iter().any(|field| field.config.index_nulls()) {
+            let nulls_indexing = TextFieldIndexing::default()
+                .set_index_option(IndexRecordOption::Basic.into())
+                .set_fieldnorms(false)
+                .set_tokenizer("raw");
+            builder.add_text_field(
+                NULLS_FIELD_NAME,
+                TextOptions::default().set_indexing_options(nulls_indexing),
+            );
+        }
+
         Ok(Self {
             key: key_index,
             schema: builder.build(),
@@ -770,6 +1334,11 @@ impl SearchIndexSchema {
         lookup
     }
 
+    /// The field that records which fields' columns are NULL, if any field indexes its nulls
+    pub fn nulls_field(&self) -> Option<Field> {
+        self.schema.get_field(NULLS_FIELD_NAME).ok()
+    }
+
     pub fn key_field(&self) -> SearchField {
         self.fields
             .get(self.key)
@@ -808,7 +1377,13 @@ impl SearchIndexSchema {
     }
 
     pub fn is_fast_field(&self, name: &str) -> bool {
+        // bytes can be read back from their fast field, but can't be sorted by it
         self.is_field_raw_sortable(name)
+            || self
+                .get_search_field(&SearchFieldName(name.to_string()))
+                .is_some_and(|field| {
+                    matches!(field.config, SearchFieldConfig::Bytes { fast: true, .. })
+                })
     }
 
     pub fn is_numeric_fast_field(&self, name: &str) -> bool {
@@ -824,6 +1399,30 @@ impl SearchIndexSchema {
         }
     }
 
+    /// The geo point field indexed from the column `column`, under either its own name or an
+    /// alias with a `column` key
+    pub fn geo_field(&self, column: &str) -> Option<&SearchField> {
+        self.fields.iter().find(|field| {
+            matches!(field.config, SearchFieldConfig::Geo { .. })
+                && field
+                    .config
+                    .column()
+                    .map_or(field.name.0 == column, |source| source == column)
+        })
+    }
+
+    /// The field indexed from the column `column` that records when it's NULL, under either its
+    /// own name or an alias with a `column` key
+    pub fn nulls_indexed_field(&self, column: &str) -> Option<&SearchField> {
+        self.fields.iter().find(|field| {
+            field.config.index_nulls()
+                && field
+                    .config
+                    .column()
+                    .map_or(field.name.0 == column, |source| source == column)
+        })
+    }
+
     fn is_field_sortable(&self, name: &str, desired_normalizer: SearchNormalizer) -> Option<()> {
         let search_field = self.get_search_field(&SearchFieldName(name.to_string()))?;
 
@@ -836,6 +1435,7 @@ impl SearchIndexSchema {
             SearchFieldConfig::Numeric { fast: true, .. } => Some(()),
             SearchFieldConfig::Boolean { fast: true, .. } => Some(()),
             SearchFieldConfig::Date { fast: true, .. } => Some(()),
+            SearchFieldConfig::Uuid { fast: true, .. } => Some(()),
             _ => None,
         }
     }
@@ -854,6 +1454,22 @@ impl SearchIndexSchema {
         }
         lookup
     }
+
+    /// The fields that were configured with a `similarity` other than tantivy's default BM25
+    pub fn similarities(&self) -> HashMap<Field, SearchSimilarity> {
+        self.fields
+            .iter()
+            .filter_map(|field| match field.config {
+                SearchFieldConfig::Text { similarity, .. }
+                | SearchFieldConfig::Json { similarity, .. }
+                    if !similarity.is_default() =>
+                {
+                    Some((field.id.0, similarity))
+                }
+                _ => None,
+            })
+            .collect()
+    }
 }
 
 // Index record schema
@@ -911,6 +1527,8 @@ pub enum SearchIndexSchemaError {
     NoKeyFieldSpecified,
     #[error("no ctid field specified for search index")]
     NoCtidFieldSpecified,
+    #[error("'{0}' is reserved and cannot be the name of a field")]
+    ReservedFieldName(String),
 }
 
 fn default_as_true() -> bool {
@@ -1001,6 +1619,36 @@ impl AsFieldType<String> for (&PgRelation, &SearchIndexSchema) {
                 (field_type, self.typeoid(search_field), field)
             })
     }
+
+    fn as_nulls_field(&self, from: &String) -> Option<Field> {
+        self.1
+            .get_search_field(&SearchFieldName(from.into()))
+            .filter(|search_field| search_field.config.index_nulls())
+            .and_then(|_| self.1.nulls_field())
+    }
+
+    fn as_decimal_scale(&self, from: &String) -> Option<u32> {
+        self.1
+            .get_search_field(&SearchFieldName(from.into()))
+            .and_then(|search_field| search_field.config.decimal_scale())
+    }
+
+    fn similarities(&self) -> HashMap<Field, SearchSimilarity> {
+        self.1.similarities()
+    }
+
+    fn as_field_config(&self, from: &String) -> Option<SearchFieldConfig> {
+        self.1
+            .get_search_field(&SearchFieldName(from.into()))
+            .map(|search_field| search_field.config.clone())
+    }
+
+    fn heap_relation_oid(&self) -> pgrx::pg_sys::Oid {
+        self.0
+            .heap_relation()
+            .expect("index should belong to a heap relation")
+            .oid()
+    }
 }
 
 #[cfg(test)]
@@ -1,14 +1,18 @@
 use serde::{Deserialize, Serialize};
 
+/// Each range of a multirange is indexed as its own [`TantivyRange`], along with this key whose
+/// value is the range's JSON text, so that a document's ranges can be read back individually
+pub const MULTIRANGE_ENTRY_KEY: &str = "entry";
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TantivyRange<T> {
-    lower: Option<T>,
-    upper: Option<T>,
-    empty: bool,
-    lower_inclusive: bool,
-    upper_inclusive: bool,
-    lower_unbounded: bool,
-    upper_unbounded: bool,
+    pub(crate) lower: Option<T>,
+    pub(crate) upper: Option<T>,
+    pub(crate) empty: bool,
+    pub(crate) lower_inclusive: bool,
+    pub(crate) upper_inclusive: bool,
+    pub(crate) lower_unbounded: bool,
+    pub(crate) upper_unbounded: bool,
 }
 
 pub struct TantivyRangeBuilder<T> {
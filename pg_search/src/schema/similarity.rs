@@ -0,0 +1,89 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+use anyhow::Result;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Tantivy's own BM25 parameters, which we use as our defaults
+const DEFAULT_K1: f32 = 1.2;
+const DEFAULT_B: f32 = 0.75;
+
+/// How term matches against a text or json field are scored.
+///
+/// Configured per-field with the `similarity` key, ie:
+/// `text_fields = '{"title": {"similarity": {"type": "bm25", "k1": 1.0, "b": 0.3}}}'`
+///
+/// BM25's parameters are checked as they're deserialized, so they're never NaN.
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SearchSimilarity {
+    Bm25 {
+        #[serde(default = "default_k1", deserialize_with = "deserialize_k1")]
+        k1: f32,
+        #[serde(default = "default_b", deserialize_with = "deserialize_b")]
+        b: f32,
+    },
+    /// Every matching term scores `1.0`, regardless of frequency or field length
+    Constant,
+    /// Classic Lucene TF-IDF:  `sqrt(tf) * idf^2 / sqrt(field length)`
+    TfIdf,
+}
+
+impl Default for SearchSimilarity {
+    fn default() -> Self {
+        SearchSimilarity::Bm25 {
+            k1: DEFAULT_K1,
+            b: DEFAULT_B,
+        }
+    }
+}
+
+impl SearchSimilarity {
+    pub fn from_json_value(value: &serde_json::Value) -> Result<Self> {
+        Ok(serde_json::from_value(value.clone())?)
+    }
+
+    /// Is this tantivy's built-in BM25 scoring?  If so, there's no need to override it.
+    pub fn is_default(&self) -> bool {
+        *self == SearchSimilarity::default()
+    }
+}
+
+fn default_k1() -> f32 {
+    DEFAULT_K1
+}
+
+fn default_b() -> f32 {
+    DEFAULT_B
+}
+
+fn deserialize_k1<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let k1 = f32::deserialize(deserializer)?;
+    if !k1.is_finite() || k1 < 0.0 {
+        return Err(D::Error::custom("'k1' must be a non-negative number"));
+    }
+    Ok(k1)
+}
+
+fn deserialize_b<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+    let b = f32::deserialize(deserializer)?;
+    if !b.is_finite() || !(0.0..=1.0).contains(&b) {
+        return Err(D::Error::custom("'b' must be between 0 and 1"));
+    }
+    Ok(b)
+}
@@ -0,0 +1,65 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Sparse vector fields index each token of a `{"token": weight}` map as a term of its own,
+//! whose frequency is the token's weight in hundredths, so that a query can score documents by
+//! the dot product of its own weights with theirs by streaming the postings of its tokens.
+
+use tantivy::schema::OwnedValue;
+use tantivy::tokenizer::{PreTokenizedString, Token};
+
+/// Weights are indexed as a whole number of hundredths
+const WEIGHT_SCALE: f32 = 100.0;
+
+/// The largest weight a token can have.  A token is added to the document once for every
+/// hundredth of its weight, so this keeps the size of a document in check.
+const MAX_WEIGHT: f32 = 100.0;
+
+/// Convert a `{"token": weight}` map into the terms it's indexed as.  Returns [`None`] if `value`
+/// isn't an object of weights between 0 and [`MAX_WEIGHT`].  Weights are rounded to the nearest
+/// hundredth, and tokens whose weight rounds to 0 are left out.
+pub fn sparse_vector_value(value: &serde_json::Value) -> Option<OwnedValue> {
+    let mut tokens = vec![];
+    for (token, weight) in value.as_object()? {
+        let weight = weight
+            .as_f64()
+            .map(|weight| weight as f32)
+            .filter(|weight| (0.0..=MAX_WEIGHT).contains(weight))?;
+
+        // tantivy counts every time a token is repeated towards its term frequency
+        let term_freq = (weight * WEIGHT_SCALE).round() as usize;
+        for _ in 0..term_freq {
+            tokens.push(Token {
+                offset_from: 0,
+                offset_to: 0,
+                position: tokens.len(),
+                text: token.clone(),
+                position_length: 1,
+            });
+        }
+    }
+
+    Some(OwnedValue::PreTokStr(PreTokenizedString {
+        text: String::new(),
+        tokens,
+    }))
+}
+
+/// The weight a document's token was indexed with, from the token's term frequency
+pub fn sparse_weight(term_freq: u32) -> f32 {
+    term_freq as f32 / WEIGHT_SCALE
+}
@@ -0,0 +1,39 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+//! Vector fields store each row's embedding as the little-endian bytes of its `f32`s, in a bytes
+//! fast field, so that they can be read back per document when scoring a kNN query.
+
+use tantivy::schema::OwnedValue;
+
+/// Convert the elements of a vector into the value it's stored as
+pub fn vector_value(vector: &[f32]) -> OwnedValue {
+    OwnedValue::Bytes(
+        vector
+            .iter()
+            .flat_map(|element| element.to_le_bytes())
+            .collect(),
+    )
+}
+
+/// Read back a vector that was stored by [`vector_value`]
+pub fn read_vector(bytes: &[u8]) -> Vec<f32> {
+    bytes
+        .chunks_exact(std::mem::size_of::<f32>())
+        .map(|chunk| f32::from_le_bytes(chunk.try_into().expect("chunk should be 4 bytes")))
+        .collect()
+}
@@ -0,0 +1,94 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgConnection;
+
+#[rstest]
+fn bytes_term_query(mut conn: PgConnection) {
+    FilesTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM files
+    WHERE id @@@ paradedb.term('digest', sha256('hosts'))
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM files
+    WHERE id @@@ paradedb.term('digest', '\x00ff00ff'::bytea)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(4,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM files
+    WHERE id @@@ paradedb.term_set(ARRAY[
+        paradedb.term('digest', sha256('passwd')),
+        paradedb.term('digest', '\x00ff00ff'::bytea)
+    ])
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (4,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM files
+    WHERE id @@@ paradedb.boolean(
+        must => ARRAY[paradedb.term('digest', sha256('hosts'))],
+        must_not => ARRAY[paradedb.term('path', 'tmp')]
+    )
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+}
+
+#[rstest]
+fn bytes_fast_field(mut conn: PgConnection) {
+    FilesTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32, Vec<u8>)> = r#"
+    SELECT id, digest FROM files
+    WHERE id @@@ paradedb.term('digest', '\x00ff00ff'::bytea) OR id @@@ paradedb.term('id', 2)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    let (expected,): (Vec<u8>,) = "SELECT sha256('passwd')".fetch_one(&mut conn);
+    assert_eq!(rows, vec![(2, expected), (4, vec![0x00, 0xff, 0x00, 0xff])]);
+
+    let rows: Vec<(String, String, bool)> = r#"
+    SELECT name, field_type, fast FROM paradedb.schema('files_idx') WHERE name = 'digest'
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("digest".into(), "Bytes".into(), true)]);
+
+    let result = r#"
+    CREATE INDEX files_path_idx ON files
+    USING bm25 (id, path)
+    WITH (key_field = 'id', bytes_fields = '{"path": {}}')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
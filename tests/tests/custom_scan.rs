@@ -149,6 +149,119 @@ fn snippets_project(mut conn: PgConnection) {
     assert_eq!(snippet, String::from("Plastic <b>Keyboard</b>"));
 }
 
+#[rstest]
+fn snippets_fragments_project(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    r#"
+    CREATE TABLE notes (id SERIAL PRIMARY KEY, body TEXT);
+    INSERT INTO notes (body) VALUES
+        ('The fox ran off. Nothing else happens for quite a while here. A fox and a fox met.'),
+        ('No matches in this one.');
+    CREATE INDEX notes_idx ON notes USING bm25 (id, body) WITH (key_field='id');
+    "#
+    .execute(&mut conn);
+
+    let (snippets,) = r#"
+    SELECT paradedb.snippets(body, max_num_chars => 20) FROM notes WHERE body @@@ 'fox'
+    "#
+    .fetch_one::<(Vec<String>,)>(&mut conn);
+    assert_eq!(
+        snippets,
+        vec![
+            "The <b>fox</b> ran off".to_string(),
+            "while here. A <b>fox</b>".to_string(),
+            "and a <b>fox</b> met".to_string(),
+        ]
+    );
+
+    let (snippets,) = r#"
+    SELECT paradedb.snippets(body, '<em>', '</em>', 20, 2) FROM notes WHERE body @@@ 'fox'
+    "#
+    .fetch_one::<(Vec<String>,)>(&mut conn);
+    assert_eq!(
+        snippets,
+        vec![
+            "The <em>fox</em> ran off".to_string(),
+            "while here. A <em>fox</em>".to_string(),
+        ]
+    );
+
+    let (positions,) = r#"
+    SELECT (paradedb.snippet_positions(body)).positions::text FROM notes WHERE body @@@ 'fox'
+    "#
+    .fetch_one::<(String,)>(&mut conn);
+    assert_eq!(positions, r#"{"[4,7)","[64,67)","[74,77)"}"#);
+
+    // works alongside the other snippet functions
+    let (id, snippet, positions) = r#"
+    SELECT id, paradedb.snippet(description),
+           (paradedb.snippet_positions(description)).positions::text
+    FROM paradedb.bm25_search WHERE description @@@ 'keyboard'
+    ORDER BY paradedb.score(id) DESC LIMIT 1
+    "#
+    .fetch_one::<(i32, String, String)>(&mut conn);
+    assert_eq!(id, 2);
+    assert_eq!(snippet, "Plastic <b>Keyboard</b>");
+    assert_eq!(positions, r#"{"[8,16)"}"#);
+}
+
+#[rstest]
+fn snippets_json_and_array_project(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE reviews (id SERIAL PRIMARY KEY, tags TEXT[], attrs JSONB);
+    INSERT INTO reviews (tags, attrs) VALUES (
+        ARRAY['red apple', 'green pear', 'red cherry and red grape'],
+        '{"review": {"title": "Great", "body": "The quick brown fox"}, "other": "fox"}'
+    );
+    CREATE INDEX reviews_idx ON reviews USING bm25 (id, tags, attrs) WITH (key_field='id');
+    "#
+    .execute(&mut conn);
+
+    // the array element with the most matches is the one that's highlighted
+    let (snippet, element, positions) = r#"
+    SELECT paradedb.snippet(tags),
+           (paradedb.snippet_positions(tags)).element,
+           (paradedb.snippet_positions(tags)).positions::text
+    FROM reviews WHERE tags @@@ 'red'
+    "#
+    .fetch_one::<(String, String, String)>(&mut conn);
+    assert_eq!(snippet, "<b>red</b> cherry and <b>red</b> grape");
+    assert_eq!(element, "red cherry and red grape");
+    assert_eq!(positions, r#"{"[0,3)","[15,18)"}"#);
+
+    let (snippet,) = r#"
+    SELECT paradedb.snippet(attrs->'review'->>'body')
+    FROM reviews WHERE id @@@ paradedb.term('attrs.review.body', 'fox')
+    "#
+    .fetch_one::<(String,)>(&mut conn);
+    assert_eq!(snippet, "The quick brown <b>fox</b>");
+
+    let (element, positions) = r#"
+    SELECT (paradedb.snippet_positions(attrs->'review'->>'body')).element,
+           (paradedb.snippet_positions(attrs->'review'->>'body')).positions::text
+    FROM reviews WHERE id @@@ paradedb.term('attrs.review.body', 'fox')
+    "#
+    .fetch_one::<(String, String)>(&mut conn);
+    assert_eq!(element, "The quick brown fox");
+    assert_eq!(positions, r#"{"[16,19)"}"#);
+
+    let (snippet,) = r#"
+    SELECT paradedb.snippet(attrs->'review'->>'body', max_num_chars => 10)
+    FROM reviews WHERE id @@@ paradedb.term('attrs.review.body', 'fox')
+    "#
+    .fetch_one::<(String,)>(&mut conn);
+    assert_eq!(snippet, "brown <b>fox</b>");
+
+    // matches at other paths aren't highlighted
+    let (snippet,) = r#"
+    SELECT paradedb.snippet(attrs->'review'->>'body')
+    FROM reviews WHERE id @@@ paradedb.term('attrs.other', 'fox')
+    "#
+    .fetch_one::<(String,)>(&mut conn);
+    assert_eq!(snippet, "");
+}
+
 #[rstest]
 fn scores_and_snippets_project(mut conn: PgConnection) {
     SimpleProductsTable::setup().execute(&mut conn);
@@ -516,3 +629,100 @@ fn cte_issue_1951(mut conn: PgConnection) {
     "#.fetch_result::<(i32, )>(&mut conn).expect("query failed");
     assert_eq!(results.len(), 1);
 }
+
+#[rstest]
+fn score_threshold_pushdown(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    // 'Plastic Keyboard' scores about 3.27, and 'Ergonomic metal keyboard' is below 3
+    let rows = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE description @@@ 'keyboard' AND paradedb.score(id) > 3
+    ORDER BY id
+    "#
+    .fetch::<(i32,)>(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let rows = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE description @@@ 'keyboard' AND paradedb.score(id) >= 0
+    ORDER BY id
+    "#
+    .fetch::<(i32,)>(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,)]);
+
+    // the comparison can be written either way around, and works with TopN
+    let rows = r#"
+    SELECT id, paradedb.score(id) FROM paradedb.bm25_search
+    WHERE description @@@ 'keyboard' AND 3.0 < paradedb.score(id)
+    ORDER BY paradedb.score(id) DESC LIMIT 5
+    "#
+    .fetch::<(i32, f32)>(&mut conn);
+    assert_eq!(rows, vec![(2, 3.2668595)]);
+
+    // a bound just below the score stays below it, even though it rounds to it as a `real`
+    let bound = rows[0].1 as f64 - 1e-9;
+    let rows = format!(
+        "SELECT id FROM paradedb.bm25_search
+        WHERE description @@@ 'keyboard' AND paradedb.score(id) > {bound:?}::float8"
+    )
+    .fetch::<(i32,)>(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let (plan,) = r#"
+    EXPLAIN (FORMAT JSON) SELECT id FROM paradedb.bm25_search
+    WHERE description @@@ 'keyboard' AND paradedb.score(id) > 3
+    "#
+    .fetch_one::<(Value,)>(&mut conn);
+    // the comparison is part of the query we run, and not left for postgres to evaluate
+    let plan = plan.to_string();
+    assert!(plan.contains("ParadeDB Scan"));
+    assert!(plan.contains("score_filter"));
+}
+
+#[rstest]
+fn matched_queries_project(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    let rows = r#"
+    SELECT id, paradedb.matched_queries(id) FROM paradedb.bm25_search
+    WHERE id @@@ paradedb.boolean(
+        should => ARRAY[
+            paradedb.named('keyboard', paradedb.term('description', 'keyboard')),
+            paradedb.named('metal', paradedb.term('description', 'metal')),
+            paradedb.named('shoes', paradedb.term('description', 'shoes'))
+        ]
+    )
+    ORDER BY id
+    "#
+    .fetch::<(i32, Vec<String>)>(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            (1, vec!["keyboard".to_string(), "metal".to_string()]),
+            (2, vec!["keyboard".to_string()]),
+            (3, vec!["shoes".to_string()]),
+            (4, vec!["shoes".to_string()]),
+            (5, vec!["shoes".to_string()]),
+        ]
+    );
+
+    // names can be nested, and work alongside scores with TopN
+    let rows = r#"
+    SELECT id, paradedb.score(id), paradedb.matched_queries(id) FROM paradedb.bm25_search
+    WHERE id @@@ paradedb.named('electronics', paradedb.boolean(
+        must => ARRAY[
+            paradedb.term('category', 'electronics'),
+            paradedb.named('keyboard', paradedb.term('description', 'keyboard'))
+        ]
+    ))
+    ORDER BY paradedb.score(id) DESC LIMIT 1
+    "#
+    .fetch::<(i32, f32, Vec<String>)>(&mut conn);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].0, 2);
+    assert_eq!(
+        rows[0].2,
+        vec!["electronics".to_string(), "keyboard".to_string()]
+    );
+}
@@ -0,0 +1,153 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgConnection;
+
+#[rstest]
+fn decimal_term(mut conn: PgConnection) {
+    AccountsTable::setup().execute(&mut conn);
+
+    // both balances are the same float8
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM accounts WHERE id @@@ paradedb.term('balance', 90071992547409.94) ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM accounts WHERE id @@@ paradedb.term('balance', -0.010) ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(4,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM accounts WHERE id @@@ paradedb.term('deposits', 0.3) ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM accounts
+    WHERE id @@@ paradedb.term_set(ARRAY[
+        paradedb.term('balance', 12345678901234.56),
+        paradedb.term('deposits', 0.1)
+    ])
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (3,)]);
+
+    // a value with more digits than the field keeps can't be looked for
+    let result = r#"
+    SELECT id FROM accounts WHERE id @@@ paradedb.term('balance', 0.001)
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn decimal_range(mut conn: PgConnection) {
+    AccountsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM accounts
+    WHERE id @@@ paradedb.range('balance', '(90071992547409.93, 90071992547410)'::numrange)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM accounts
+    WHERE id @@@ paradedb.range('balance', '[-1, 12345678901234.56]'::numrange)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,), (4,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM accounts
+    WHERE id @@@ paradedb.range('deposits', '[999999999999999.99,)'::numrange)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(4,)]);
+}
+
+#[rstest]
+fn decimal_fast_field(mut conn: PgConnection) {
+    AccountsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(String,)> = r#"
+    SELECT balance::text FROM (
+        SELECT id, balance FROM accounts WHERE owner @@@ 'alice OR bob OR dave' OFFSET 0
+    ) accounts
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("90071992547409.93".into(),),
+            ("90071992547409.94".into(),),
+            ("-0.01".into(),)
+        ]
+    );
+}
+
+#[rstest]
+fn decimal_scale_requires_numeric(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE measurements (id SERIAL PRIMARY KEY, reading FLOAT8);
+    "#
+    .execute(&mut conn);
+
+    let result = r#"
+    CREATE INDEX measurements_idx ON measurements
+    USING bm25 (id, reading)
+    WITH (key_field = 'id', numeric_fields = '{"reading": {"scale": 2}}')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn decimal_decay(mut conn: PgConnection) {
+    AccountsTable::setup().execute(&mut conn);
+
+    // -0.01 is one hundredth from the origin, which is exactly `scale` away, so its score halves
+    let (decayed, original): (f32, f32) = r#"
+    SELECT
+        (SELECT paradedb.score(id) FROM accounts WHERE id @@@ paradedb.decay('balance', paradedb.parse('owner:dave'), origin => 0, scale => 0.01) AND id = 4),
+        (SELECT paradedb.score(id) FROM accounts WHERE id @@@ paradedb.parse('owner:dave') AND id = 4)
+    "#
+    .fetch_one(&mut conn);
+    assert!((decayed - original * 0.5).abs() < 1e-6);
+
+    // an origin with more digits than the field keeps can't be scaled exactly
+    let result = r#"
+    SELECT id FROM accounts
+    WHERE id @@@ paradedb.decay('balance', paradedb.parse('owner:dave'), origin => 0.001, scale => 1)
+    "#
+    .fetch_result::<(i32,)>(&mut conn);
+    assert!(result.is_err());
+}
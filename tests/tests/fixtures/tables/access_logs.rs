@@ -0,0 +1,48 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub struct AccessLogsTable;
+
+impl AccessLogsTable {
+    pub fn setup() -> String {
+        ACCESS_LOGS_TABLE_SETUP.into()
+    }
+}
+
+static ACCESS_LOGS_TABLE_SETUP: &str = r#"
+CREATE TABLE access_logs (
+    id SERIAL PRIMARY KEY,
+    message TEXT,
+    client_ip INET,
+    network CIDR
+);
+
+INSERT INTO access_logs (message, client_ip, network) VALUES
+    ('login succeeded', '10.0.0.1', '10.0.0.0/8'),
+    ('login failed', '10.1.2.3', '10.0.0.0/8'),
+    ('login failed', '192.168.1.20', '192.168.1.0/24'),
+    ('logout', '192.168.1.21', '192.168.1.0/24'),
+    ('login failed', '2001:db8::1', '2001:db8::/32');
+
+CREATE INDEX access_logs_idx ON access_logs
+USING bm25 (id, message, client_ip, network)
+WITH (
+    key_field = 'id',
+    text_fields = '{"message": {}}',
+    inet_fields = '{"client_ip": {}, "network": {}}'
+);
+"#;
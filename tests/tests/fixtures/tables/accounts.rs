@@ -0,0 +1,47 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub struct AccountsTable;
+
+impl AccountsTable {
+    pub fn setup() -> String {
+        ACCOUNTS_TABLE_SETUP.into()
+    }
+}
+
+static ACCOUNTS_TABLE_SETUP: &str = r#"
+CREATE TABLE accounts (
+    id SERIAL PRIMARY KEY,
+    owner TEXT,
+    balance NUMERIC(18, 2),
+    deposits NUMERIC(18, 2)[]
+);
+
+INSERT INTO accounts (owner, balance, deposits) VALUES
+    ('alice', 90071992547409.93, ARRAY[0.10, 0.20]),
+    ('bob', 90071992547409.94, ARRAY[0.30]),
+    ('carol', 12345678901234.56, NULL),
+    ('dave', -0.01, ARRAY[0.01, 1000000000000000.00]);
+
+CREATE INDEX accounts_idx ON accounts
+USING bm25 (id, owner, balance, deposits)
+WITH (
+    key_field = 'id',
+    text_fields = '{"owner": {}}',
+    numeric_fields = '{"balance": {"scale": 2}, "deposits": {"scale": 2}}'
+);
+"#;
@@ -0,0 +1,46 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub struct ArticlesTable;
+
+impl ArticlesTable {
+    pub fn setup() -> String {
+        ARTICLES_TABLE_SETUP.into()
+    }
+}
+
+static ARTICLES_TABLE_SETUP: &str = r#"
+CREATE TABLE articles (
+    id SERIAL PRIMARY KEY,
+    body TEXT,
+    body_tsv TSVECTOR GENERATED ALWAYS AS (to_tsvector('english', body)) STORED
+);
+
+INSERT INTO articles (body) VALUES
+    ('The fat cats sat on the mat'),
+    ('The fat rats ran away'),
+    ('A cat and a fat dog'),
+    ('Supernovae are exploding stars'),
+    ('The cat was fat');
+
+CREATE INDEX articles_idx ON articles
+USING bm25 (id, body, body_tsv)
+WITH (
+    key_field = 'id',
+    text_fields = '{"body": {}, "body_tsv": {}}'
+);
+"#;
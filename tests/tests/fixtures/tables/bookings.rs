@@ -0,0 +1,47 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub struct BookingsTable;
+
+impl BookingsTable {
+    pub fn setup() -> String {
+        BOOKINGS_TABLE_SETUP.into()
+    }
+}
+
+static BOOKINGS_TABLE_SETUP: &str = r#"
+CREATE TABLE bookings (
+    id SERIAL PRIMARY KEY,
+    room TEXT,
+    booked INT4MULTIRANGE,
+    slots TSMULTIRANGE
+);
+
+INSERT INTO bookings (room, booked, slots) VALUES
+    ('kitchen', '{[1,5), [10,15)}', '{["2024-01-01 09:00","2024-01-01 12:00"), ["2024-01-01 14:00","2024-01-01 17:00")}'),
+    ('library', '{[5,10)}', '{["2024-01-01 12:00","2024-01-01 14:00")}'),
+    ('garden', '{[20,30)}', NULL),
+    ('attic', '{[1,3), [28,40)}', NULL);
+
+CREATE INDEX bookings_idx ON bookings
+USING bm25 (id, room, booked, slots)
+WITH (
+    key_field = 'id',
+    text_fields = '{"room": {}}',
+    range_fields = '{"booked": {}, "slots": {}}'
+);
+"#;
@@ -0,0 +1,46 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub struct DocsTable;
+
+impl DocsTable {
+    pub fn setup() -> String {
+        DOCS_TABLE_SETUP.into()
+    }
+}
+
+static DOCS_TABLE_SETUP: &str = r#"
+CREATE TABLE docs (
+    id SERIAL PRIMARY KEY,
+    body TEXT,
+    embedding REAL[]
+);
+
+INSERT INTO docs (body, embedding) VALUES
+    ('red apple', ARRAY[1, 0, 0]),
+    ('green apple', ARRAY[0.9, 0.1, 0]),
+    ('red car', ARRAY[0, 1, 0]),
+    ('blue car', ARRAY[0, 0.2, 1]);
+
+CREATE INDEX docs_idx ON docs
+USING bm25 (id, body, embedding)
+WITH (
+    key_field = 'id',
+    text_fields = '{"body": {}}',
+    vector_fields = '{"embedding": {"dimensions": 3}}'
+);
+"#;
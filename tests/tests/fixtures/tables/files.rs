@@ -0,0 +1,46 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub struct FilesTable;
+
+impl FilesTable {
+    pub fn setup() -> String {
+        FILES_TABLE_SETUP.into()
+    }
+}
+
+static FILES_TABLE_SETUP: &str = r#"
+CREATE TABLE files (
+    id SERIAL PRIMARY KEY,
+    path TEXT,
+    digest BYTEA
+);
+
+INSERT INTO files (path, digest) VALUES
+    ('/etc/hosts', sha256('hosts')),
+    ('/etc/passwd', sha256('passwd')),
+    ('/tmp/hosts.bak', sha256('hosts')),
+    ('/bin/ls', '\x00ff00ff');
+
+CREATE INDEX files_idx ON files
+USING bm25 (id, path, digest)
+WITH (
+    key_field = 'id',
+    text_fields = '{"path": {}}',
+    bytes_fields = '{"digest": {}}'
+);
+"#;
@@ -15,22 +15,40 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program. If not, see <http://www.gnu.org/licenses/>.
 
+mod access_logs;
+mod accounts;
+mod articles;
+mod bookings;
 mod deliveries;
+mod docs;
 mod duckdb_types;
+mod files;
 mod icu_amharic_posts;
 mod icu_arabic_posts;
 mod icu_czech_posts;
 mod icu_greek_posts;
 mod nyc_trips;
+mod orders;
+mod products;
+mod shops;
 mod simple_products;
 mod user_session_logs;
 
+pub use access_logs::*;
+pub use accounts::*;
+pub use articles::*;
+pub use bookings::*;
 pub use deliveries::*;
+pub use docs::*;
 pub use duckdb_types::*;
+pub use files::*;
 pub use icu_amharic_posts::*;
 pub use icu_arabic_posts::*;
 pub use icu_czech_posts::*;
 pub use icu_greek_posts::*;
 pub use nyc_trips::*;
+pub use orders::*;
+pub use products::*;
+pub use shops::*;
 pub use simple_products::*;
 pub use user_session_logs::*;
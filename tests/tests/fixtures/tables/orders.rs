@@ -0,0 +1,50 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub struct OrdersTable;
+
+impl OrdersTable {
+    pub fn setup() -> String {
+        ORDERS_TABLE_SETUP.into()
+    }
+}
+
+static ORDERS_TABLE_SETUP: &str = r#"
+SET enable_indexscan TO off;
+
+CREATE TABLE orders (
+    id SERIAL PRIMARY KEY,
+    customer TEXT,
+    reference UUID,
+    related UUID[]
+);
+
+INSERT INTO orders (customer, reference, related) VALUES
+    ('alice', 'f47ac10b-58cc-4372-a567-0e02b2c3d479', ARRAY['00000000-0000-0000-0000-000000000001'::uuid]),
+    ('bob', '0e984725-c51c-4bf4-9960-e1c80e27aba0', NULL),
+    ('carol', '9c858901-8a57-4791-81fe-4c455b099bc9', ARRAY['f47ac10b-58cc-4372-a567-0e02b2c3d479'::uuid]),
+    ('dave', NULL, NULL),
+    ('erin', 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11', NULL);
+
+CREATE INDEX orders_idx ON orders
+USING bm25 (id, customer, reference, related)
+WITH (
+    key_field = 'id',
+    text_fields = '{"customer": {}}',
+    uuid_fields = '{"reference": {}, "related": {}}'
+);
+"#;
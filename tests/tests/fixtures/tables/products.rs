@@ -0,0 +1,50 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub struct ProductsTable;
+
+impl ProductsTable {
+    pub fn setup() -> String {
+        PRODUCTS_TABLE_SETUP.into()
+    }
+}
+
+static PRODUCTS_TABLE_SETUP: &str = r#"
+CREATE TABLE products (
+    id SERIAL PRIMARY KEY,
+    name TEXT,
+    category TEXT,
+    tags TEXT[],
+    rating INT,
+    attrs JSONB
+);
+
+INSERT INTO products (name, category, tags, rating, attrs) VALUES
+    ('red shoes', 'footwear', ARRAY['sale'], 5, '{"size": 9}'),
+    ('blue shoes', NULL, NULL, NULL, NULL),
+    ('green hat', 'hats', ARRAY[]::text[], 3, NULL),
+    ('red hat', NULL, ARRAY['sale'], 4, '{"size": 7}');
+
+CREATE INDEX products_idx ON products
+USING bm25 (id, name, category, tags, rating, attrs)
+WITH (
+    key_field = 'id',
+    text_fields = '{"name": {}, "category": {"index_nulls": true}, "tags": {"index_nulls": true}}',
+    numeric_fields = '{"rating": {"index_nulls": true}}',
+    json_fields = '{"attrs": {"index_nulls": true}}'
+);
+"#;
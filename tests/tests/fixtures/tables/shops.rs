@@ -0,0 +1,46 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+pub struct ShopsTable;
+
+impl ShopsTable {
+    pub fn setup() -> String {
+        SHOPS_TABLE_SETUP.into()
+    }
+}
+
+static SHOPS_TABLE_SETUP: &str = r#"
+CREATE TABLE shops (
+    id SERIAL PRIMARY KEY,
+    name TEXT,
+    location POINT
+);
+
+INSERT INTO shops (name, location) VALUES
+    ('Blue Bottle Coffee', point(-73.9857, 40.7484)),
+    ('Joe Coffee', point(-73.9772, 40.7527)),
+    ('Stumptown Coffee', point(-73.9881, 40.7456)),
+    ('Brooklyn Roasting Coffee', point(-73.9903, 40.7033)),
+    ('Tea House', point(-73.9850, 40.7480));
+
+CREATE INDEX shops_idx ON shops
+USING bm25 (id, name, location)
+WITH (
+    key_field = 'id',
+    text_fields = '{"name": {}}'
+);
+"#;
@@ -0,0 +1,193 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use serde_json::Value;
+use sqlx::PgConnection;
+
+#[rstest]
+fn geo_distance_query(mut conn: PgConnection) {
+    ShopsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM shops
+    WHERE name @@@ 'coffee' AND id @@@ paradedb.geo_distance('location', 40.7484, -73.9857, 2000)
+    ORDER BY paradedb.distance(location, 40.7484, -73.9857)
+    LIMIT 10
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (3,), (2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM shops
+    WHERE name @@@ 'coffee'
+    ORDER BY paradedb.distance(location, 40.7484, -73.9857) DESC
+    LIMIT 2
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(4,), (2,)]);
+
+    let (distance,): (f64,) =
+        "SELECT paradedb.distance(location, 40.7484, -73.9857) FROM shops WHERE id = 2"
+            .fetch_one(&mut conn);
+    assert!((distance - 861.0).abs() < 1.0);
+}
+
+#[rstest]
+fn geo_distance_sort_is_pushed_down(mut conn: PgConnection) {
+    ShopsTable::setup().execute(&mut conn);
+
+    let (plan,) = r#"
+    EXPLAIN (ANALYZE, FORMAT JSON)
+    SELECT id FROM shops
+    WHERE name @@@ 'coffee'
+    ORDER BY paradedb.distance(location, 40.7484, -73.9857)
+    LIMIT 10
+    "#
+    .fetch_one::<(Value,)>(&mut conn);
+    let plan = plan
+        .pointer("/0/Plan/Plans/0/Plans/0")
+        .unwrap()
+        .as_object()
+        .unwrap();
+    eprintln!("{plan:#?}");
+    assert_eq!(
+        plan.get("   Sort Field"),
+        Some(&Value::String(String::from("location")))
+    );
+    assert_eq!(
+        plan.get("   Sort Distance From"),
+        Some(&Value::String(String::from("40.7484,-73.9857")))
+    );
+}
+
+#[rstest]
+fn geo_bounding_box_and_polygon(mut conn: PgConnection) {
+    ShopsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM shops
+    WHERE id @@@ paradedb.geo_bounding_box('location', 40.75, -73.99, 40.74, -73.98)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (3,), (5,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM shops
+    WHERE id @@@ paradedb.geo_polygon('location', '[
+        {"lat": 40.76, "lon": -73.99},
+        {"lat": 40.76, "lon": -73.97},
+        {"lat": 40.745, "lon": -73.97}
+    ]')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let result = r#"
+    SELECT id FROM shops WHERE id @@@ paradedb.geo_distance('name', 40.7484, -73.9857, 2000)
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn geo_jsonb_field(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE stops (
+        id SERIAL PRIMARY KEY,
+        name TEXT,
+        location JSONB
+    );
+
+    INSERT INTO stops (name, location) VALUES
+        ('Grand Central', '{"lat": 40.7527, "lon": -73.9772}'),
+        ('Penn Station', '{"lat": 40.7506, "lon": -73.9935}'),
+        ('Atlantic Avenue', '{"lat": 40.6843, "lon": -73.9772}');
+
+    CREATE INDEX stops_idx ON stops
+    USING bm25 (id, name, location)
+    WITH (
+        key_field = 'id',
+        text_fields = '{"name": {}}',
+        geo_fields = '{"location": {}}'
+    );
+    "#
+    .execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM stops
+    WHERE id @@@ paradedb.geo_distance('location', 40.7484, -73.9857, 2000)
+    ORDER BY paradedb.distance(location, 40.7484, -73.9857)
+    LIMIT 10
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (1,)]);
+
+    let result =
+        r#"INSERT INTO stops (name, location) VALUES ('Nowhere', '{"lat": 100, "lon": 0}')"#
+            .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn geo_queries_require_a_geo_field(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE markers (
+        id SERIAL PRIMARY KEY,
+        location POINT,
+        digest BYTEA
+    );
+
+    INSERT INTO markers (location, digest) VALUES (point(-73.9857, 40.7484), '\x00ff');
+
+    CREATE INDEX markers_idx ON markers
+    USING bm25 (id, location, digest)
+    WITH (
+        key_field = 'id',
+        bytes_fields = '{"digest": {}}'
+    );
+    "#
+    .execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM markers
+    WHERE id @@@ paradedb.geo_distance('location', 40.7484, -73.9857, 10)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    // bytes fields are stored the way geo fields are, but don't hold points
+    for query in [
+        "paradedb.geo_distance('digest', 40.7484, -73.9857, 10)",
+        "paradedb.geo_bounding_box('digest', 40.75, -73.99, 40.74, -73.98)",
+        r#"paradedb.geo_polygon('digest', '[
+            {"lat": 40.76, "lon": -73.99},
+            {"lat": 40.76, "lon": -73.97},
+            {"lat": 40.745, "lon": -73.97}
+        ]')"#,
+    ] {
+        let result =
+            format!("SELECT id FROM markers WHERE id @@@ {query}").execute_result(&mut conn);
+        assert!(result.is_err(), "{query} should fail");
+    }
+}
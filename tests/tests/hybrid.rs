@@ -154,3 +154,115 @@ fn reciprocal_rank_fusion(mut conn: PgConnection) {
         )
     );
 }
+
+#[rstest]
+fn hybrid_fusion(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    // 'Plastic Keyboard' is the shorter description, so bm25 ranks it ahead of id 1
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT key, score FROM paradedb.hybrid(
+        'paradedb.bm25_search_bm25_index',
+        paradedb.term('description', 'keyboard'),
+        ARRAY[1, 3]
+    )
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+    assert!((rows[0].1 - (1.0 / 61.0 + 1.0 / 62.0)).abs() < 1e-6);
+    assert!((rows[1].1 - 1.0 / 61.0).abs() < 1e-6);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT key FROM paradedb.hybrid(
+        'paradedb.bm25_search_bm25_index',
+        paradedb.term('description', 'keyboard'),
+        ARRAY[1, 3],
+        k => 0,
+        bm25_weight => 0,
+        "limit" => 2
+    )
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT key FROM paradedb.hybrid(
+        'paradedb.bm25_search_bm25_index',
+        paradedb.term('description', 'keyboard'),
+        ARRAY[1, 3],
+        ranked_scores => ARRAY[0.9, 0.1],
+        method => 'MinMax',
+        ranked_weight => 0.5
+    )
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (1,), (3,)]);
+
+    // keys missing from a ranking get its lowest z-score
+    let rows: Vec<(i32,)> = r#"
+    SELECT key FROM paradedb.hybrid(
+        'paradedb.bm25_search_bm25_index',
+        paradedb.term('description', 'keyboard'),
+        ARRAY[3, 1, 4],
+        ranked_scores => ARRAY[0.9, 0.5, 0.1],
+        method => 'ZScore'
+    )
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,), (2,), (1,), (4,)]);
+
+    let result = r#"
+    SELECT * FROM paradedb.hybrid(
+        'paradedb.bm25_search_bm25_index',
+        paradedb.term('description', 'keyboard'),
+        ARRAY[1, 3],
+        method => 'MinMax'
+    )
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    // keys come back as the type of ranked_keys, compared as the key field's type
+    let rows: Vec<(i64,)> = r#"
+    SELECT key FROM paradedb.hybrid(
+        'paradedb.bm25_search_bm25_index',
+        paradedb.term('description', 'keyboard'),
+        ARRAY[1, 3]::bigint[]
+    )
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,), (3,)]);
+}
+
+#[rstest]
+fn hybrid_text_keys(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE documents (
+        code TEXT PRIMARY KEY,
+        body TEXT
+    );
+
+    INSERT INTO documents (code, body) VALUES
+        ('doc-a', 'red apple'),
+        ('doc-b', 'green apple'),
+        ('doc-c', 'yellow banana');
+
+    CREATE INDEX documents_idx ON documents
+    USING bm25 (code, body)
+    WITH (key_field = 'code', text_fields = '{"body": {}}');
+    "#
+    .execute(&mut conn);
+
+    let rows: Vec<(String,)> = r#"
+    SELECT key FROM paradedb.hybrid(
+        'documents_idx',
+        paradedb.term('body', 'red'),
+        ARRAY['doc-c', 'doc-a']
+    )
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("doc-a".into(),), ("doc-c".into(),)]);
+}
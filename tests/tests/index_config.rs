@@ -587,3 +587,128 @@ fn custom_enum_parse(mut conn: PgConnection) {
 
     assert_eq!(rows, vec![(1, "Item 1".into())]);
 }
+
+#[rstest]
+fn text_field_similarity(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE paradedb.index_config(id INTEGER, title TEXT, tags TEXT);
+    INSERT INTO paradedb.index_config VALUES
+        (1, 'rust', 'rust'),
+        (2, 'rust in action a book about the rust language', 'rust rust'),
+        (3, 'programming', 'python');
+    "#
+    .execute(&mut conn);
+
+    r#"
+    CREATE INDEX index_config_index ON paradedb.index_config
+    USING bm25 (id, title, tags)
+    WITH (
+        key_field='id',
+        text_fields='{
+            "title": {"similarity": {"type": "bm25", "b": 0.0}},
+            "tags": {"similarity": {"type": "constant"}}
+        }'
+    );
+    "#
+    .execute(&mut conn);
+
+    // without length normalization, the longer title with the same term frequency would tie, but
+    // the second title mentions "rust" twice
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM paradedb.index_config
+    WHERE id @@@ paradedb.term('title', 'rust')
+    ORDER BY paradedb.score(id) DESC LIMIT 5
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].0, 2);
+    assert!(rows[0].1 > rows[1].1);
+
+    // constant similarity ignores term frequency entirely
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM paradedb.index_config
+    WHERE id @@@ paradedb.parse('tags:rust')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1, 1.0), (2, 1.0)]);
+
+    // and the same holds on the top-n path
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM paradedb.index_config
+    WHERE id @@@ paradedb.term('tags', 'rust')
+    ORDER BY paradedb.score(id) DESC LIMIT 1
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows[0].1, 1.0);
+
+    "DROP INDEX paradedb.index_config_index".execute(&mut conn);
+    let result = r#"
+    CREATE INDEX index_config_index ON paradedb.index_config
+    USING bm25 (id, title)
+    WITH (key_field='id', text_fields='{"title": {"similarity": {"type": "bm25", "b": 2.0}}}');
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn similarity_in_wrapped_queries(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE paradedb.index_config(id INTEGER, title TEXT, tags TEXT);
+    INSERT INTO paradedb.index_config VALUES
+        (1, 'rust', 'rust'),
+        (2, 'rust in action', 'rust rust language');
+
+    CREATE INDEX index_config_index ON paradedb.index_config
+    USING bm25 (id, title, tags)
+    WITH (
+        key_field='id',
+        text_fields='{"title": {}, "tags": {"similarity": {"type": "constant"}}}'
+    );
+    "#
+    .execute(&mut conn);
+
+    // the similarity of the tags field applies inside boosts, dis-max queries, phrases and the
+    // boosts of a query string, not just to bare terms
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM paradedb.index_config
+    WHERE id @@@ paradedb.boost(2.0, paradedb.term('tags', 'rust'))
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1, 2.0), (2, 2.0)]);
+
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM paradedb.index_config
+    WHERE id @@@ paradedb.disjunction_max(ARRAY[paradedb.term('tags', 'rust')])
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1, 1.0), (2, 1.0)]);
+
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM paradedb.index_config
+    WHERE id @@@ paradedb.parse('tags:rust^3')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1, 3.0), (2, 3.0)]);
+
+    // each term of a matching phrase scores 1.0
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM paradedb.index_config
+    WHERE id @@@ paradedb.parse('tags:"rust language"')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2, 2.0)]);
+
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM paradedb.index_config
+    WHERE id @@@ paradedb.multi_match(ARRAY['tags^2', 'title'], 'language', 'BestFields')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2, 2.0)]);
+}
@@ -0,0 +1,147 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgConnection;
+
+#[rstest]
+fn inet_term_query(mut conn: PgConnection) {
+    AccessLogsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM access_logs
+    WHERE id @@@ paradedb.term('client_ip', '192.168.1.20'::inet)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM access_logs
+    WHERE id @@@ paradedb.term('client_ip', '2001:db8::1'::inet)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(5,)]);
+
+    // a cidr column is indexed by its network address
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM access_logs
+    WHERE id @@@ paradedb.term('network', '192.168.1.0'::inet)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,), (4,)]);
+}
+
+#[rstest]
+fn inet_cidr_subnet_query(mut conn: PgConnection) {
+    AccessLogsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM access_logs
+    WHERE id @@@ paradedb.term('client_ip', '10.0.0.0/8'::cidr)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM access_logs
+    WHERE message @@@ 'failed' AND id @@@ paradedb.term('client_ip', '192.168.0.0/16'::cidr)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM access_logs
+    WHERE id @@@ paradedb.term('client_ip', '2001:db8::/32'::cidr)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(5,)]);
+}
+
+#[rstest]
+fn inet_range_query(mut conn: PgConnection) {
+    AccessLogsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM access_logs
+    WHERE id @@@ paradedb.range('client_ip', '10.1.0.0'::inet, '192.168.1.20'::inet)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM access_logs
+    WHERE id @@@ paradedb.range('client_ip', '192.168.0.0/16'::cidr, NULL)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,), (4,), (5,)]);
+}
+
+#[rstest]
+fn inet_field_config(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE hosts (
+        id SERIAL PRIMARY KEY,
+        name TEXT,
+        addr INET
+    );
+
+    INSERT INTO hosts (name, addr) VALUES
+        ('gateway', '10.0.0.1'),
+        ('resolver', '2001:4860:4860::8888');
+
+    CREATE INDEX hosts_idx ON hosts
+    USING bm25 (id, name, addr)
+    WITH (
+        key_field = 'id',
+        text_fields = '{"name": {}}',
+        inet_fields = '{"addr": {"stored": true}}'
+    );
+    "#
+    .execute(&mut conn);
+
+    let rows: Vec<(String, String, bool)> = r#"
+    SELECT name, field_type, stored FROM paradedb.schema('hosts_idx') WHERE name = 'addr'
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("addr".into(), "IpAddr".into(), true)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM hosts WHERE id @@@ paradedb.term('addr', '2001:4860:4860::8888'::inet)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let result = r#"
+    CREATE INDEX hosts_name_idx ON hosts
+    USING bm25 (id, name)
+    WITH (key_field = 'id', inet_fields = '{"name": {}}')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
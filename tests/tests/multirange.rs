@@ -0,0 +1,120 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgConnection;
+
+#[rstest]
+fn multirange_term(mut conn: PgConnection) {
+    BookingsTable::setup().execute(&mut conn);
+
+    // 7 is between the ranges of the kitchen, but only inside the library's
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings WHERE id @@@ paradedb.range_term('booked', 7) ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings WHERE id @@@ paradedb.range_term('booked', 12) ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings
+    WHERE id @@@ paradedb.range_term('slots', '2024-01-01 13:00'::timestamp)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+}
+
+#[rstest]
+fn multirange_relations(mut conn: PgConnection) {
+    BookingsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings
+    WHERE id @@@ paradedb.range_term('booked', '[6,11)'::int4range, 'Intersects')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,)]);
+
+    // the attic's ranges are on either side of the query, but neither overlaps it
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings
+    WHERE id @@@ paradedb.range_term('booked', '[16,19)'::int4range, 'Intersects')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings
+    WHERE id @@@ paradedb.range_term('booked', '[0,6)'::int4range, 'Contains')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (4,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings
+    WHERE id @@@ paradedb.range_term('booked', '[2,4)'::int4range, 'Within')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings
+    WHERE id @@@ paradedb.range_term('booked', '[11,13)'::int4range, 'Within')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings
+    WHERE id @@@ paradedb.range_term(
+        'slots',
+        '["2024-01-01 11:30","2024-01-01 12:30")'::tsrange,
+        'Intersects'
+    )
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,)]);
+
+    // the ranges match the same rows as the multirange operators do
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM bookings WHERE booked && '{[14,21)}'::int4multirange ORDER BY id
+    "#
+    .fetch(&mut conn);
+    let expected: Vec<(i32,)> = r#"
+    SELECT id FROM bookings
+    WHERE id @@@ paradedb.range_term('booked', '[14,21)'::int4range, 'Intersects')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, expected);
+}
@@ -0,0 +1,112 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use serde_json::Value;
+use sqlx::PgConnection;
+
+#[rstest]
+fn is_null_query(mut conn: PgConnection) {
+    ProductsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM products WHERE id @@@ paradedb.is_null('category') ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (4,)]);
+
+    // an empty array isn't NULL
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM products WHERE id @@@ paradedb.is_null('tags') ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM products WHERE id @@@ paradedb.is_null('rating') ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM products WHERE id @@@ paradedb.is_null('attrs') ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM products
+    WHERE id @@@ paradedb.boolean(
+        must => ARRAY[paradedb.term('name', 'red')],
+        must_not => ARRAY[paradedb.is_null('category')]
+    )
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    "INSERT INTO products (name) VALUES ('yellow scarf')".execute(&mut conn);
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM products WHERE id @@@ paradedb.is_null('category') ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (4,), (5,)]);
+
+    // the nulls of `name` aren't indexed
+    let result = r#"
+    SELECT id FROM products WHERE id @@@ paradedb.is_null('name')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn is_null_pushdown(mut conn: PgConnection) {
+    ProductsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM products WHERE name @@@ 'shoes' AND category IS NULL ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM products WHERE name @@@ 'red' AND category IS NOT NULL ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM products WHERE name @@@ 'hat' AND (attrs IS NULL OR rating IS NULL) ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    // the test is part of the query we run, and not left for postgres to evaluate
+    "SET enable_indexscan TO off;".execute(&mut conn);
+    let (plan,) = r#"
+    EXPLAIN (FORMAT JSON) SELECT id FROM products WHERE name @@@ 'shoes' AND category IS NULL
+    "#
+    .fetch_one::<(Value,)>(&mut conn);
+    let plan = plan.to_string();
+    assert!(plan.contains("ParadeDB Scan"));
+    assert!(plan.contains("is_null"));
+}
@@ -0,0 +1,148 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgConnection;
+
+#[rstest]
+fn percolate_stored_queries(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    r#"
+    CREATE TABLE alerts (
+        id SERIAL PRIMARY KEY,
+        query paradedb.searchqueryinput
+    );
+
+    INSERT INTO alerts (query) VALUES
+        (paradedb.term('description', 'shoes')),
+        (paradedb.parse('description:keyboard AND rating:>3')),
+        (paradedb.range('rating', int4range(1, 3, '[]'))),
+        (paradedb.boolean(must => ARRAY[paradedb.term('category', 'footwear'), paradedb.term('description', 'sandals')]));
+    "#
+    .execute(&mut conn);
+
+    let rows: Vec<(i64,)> = r#"
+    SELECT * FROM paradedb.percolate(
+        'paradedb.bm25_search_bm25_index', 'alerts',
+        '{"description": "Comfortable Running Shoes", "category": "Footwear", "rating": 2}'
+    ) ORDER BY 1
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (3,)]);
+
+    let rows: Vec<(i64,)> = r#"
+    SELECT * FROM paradedb.percolate(
+        'paradedb.bm25_search_bm25_index', 'alerts',
+        '{"description": "Wireless keyboard", "rating": 5, "not_a_field": true}'
+    ) ORDER BY 1
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let rows: Vec<(i64,)> = r#"
+    SELECT paradedb.percolate(
+        'paradedb.bm25_search_bm25_index', 'alerts',
+        jsonb_build_object('description', description, 'category', category, 'rating', rating)
+    )
+    FROM paradedb.bm25_search WHERE id = 2
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let result = r#"
+    SELECT * FROM paradedb.percolate('paradedb.bm25_search_bm25_index', 'alerts', '[1, 2]')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn percolate_non_text_fields(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    r#"
+    CREATE TABLE alerts (
+        id SERIAL PRIMARY KEY,
+        query paradedb.searchqueryinput
+    );
+
+    INSERT INTO alerts (query) VALUES
+        (paradedb.term('in_stock', true)),
+        (paradedb.term('metadata.color', 'white')),
+        (paradedb.range('last_updated_date', daterange('2023-05-01', '2023-05-31', '[]'))),
+        (paradedb.term('created_at', '2023-05-03 09:12:34'::timestamp));
+    "#
+    .execute(&mut conn);
+
+    let rows: Vec<(i64,)> = r#"
+    SELECT * FROM paradedb.percolate(
+        'paradedb.bm25_search_bm25_index', 'alerts',
+        '{"in_stock": true, "metadata": {"color": "White"}, "last_updated_date": "2023-05-04", "created_at": "2023-05-03 09:12:34"}'
+    ) ORDER BY 1
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,), (3,), (4,)]);
+
+    let rows: Vec<(i64,)> = r#"
+    SELECT * FROM paradedb.percolate(
+        'paradedb.bm25_search_bm25_index', 'alerts',
+        '{"in_stock": false, "last_updated_date": "2023-06-04", "created_at": "2023-05-03 09:12:35"}'
+    ) ORDER BY 1
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![]);
+
+    let result = r#"
+    SELECT * FROM paradedb.percolate(
+        'paradedb.bm25_search_bm25_index', 'alerts', '{"in_stock": "not a boolean"}'
+    )
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn percolate_rejects_heap_queries(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    r#"
+    CREATE TABLE alerts (
+        id SERIAL PRIMARY KEY,
+        query paradedb.searchqueryinput
+    );
+
+    INSERT INTO alerts (query) VALUES
+        (paradedb.collapse('rating', paradedb.term('description', 'shoes')));
+    "#
+    .execute(&mut conn);
+
+    let result = r#"
+    SELECT * FROM paradedb.percolate(
+        'paradedb.bm25_search_bm25_index', 'alerts', '{"description": "Running Shoes"}'
+    )
+    "#
+    .execute_result(&mut conn);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("stored query 1 needs the table's rows"));
+}
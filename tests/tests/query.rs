@@ -1087,6 +1087,565 @@ fn parse_with_field_conjunction(mut conn: PgConnection) {
     assert_eq!(rows, vec![(3,)]);
 }
 
+#[rstest]
+fn match_query(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.match('description', 'running shoes')
+    ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,), (4,), (5,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@
+        paradedb.match('description', 'running shoes', conjunction_mode => true)
+    ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    // query syntax is never interpreted
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.match('description', 'shoes: (running')
+    ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,), (4,), (5,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@
+        paradedb.match('description', 'shoez keybaord', fuzziness => 'auto')
+    ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,), (3,), (4,), (5,)]);
+
+    // `sho` is too short for automatic fuzziness to match `shoes`, but can be a prefix
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@
+        paradedb.match('description', 'runing sho', fuzziness => 'auto', conjunction_mode => true)
+    ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.match(
+        'description',
+        'runing sho',
+        fuzziness => 'auto',
+        prefix => true,
+        conjunction_mode => true
+    )
+    ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    // the raw tokenizer keeps the whole value as a single term, which isn't in the index
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@
+        paradedb.match('description', 'running shoes', tokenizer => paradedb.tokenizer('raw'))
+    ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@
+    '{"match": {"field": "description", "value": "shoez", "fuzziness": 1}}'::jsonb
+    ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,), (4,), (5,)]);
+
+    let result = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.match('description', 'shoes', fuzziness => '5')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn minimum_should_match(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.boolean(
+        should => ARRAY[
+            paradedb.term('description', 'shoes'),
+            paradedb.term('description', 'running'),
+            paradedb.term('description', 'keyboard')
+        ],
+        minimum_should_match => '2'
+    ) ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    // a negative count is the number of clauses allowed to be missing
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.boolean(
+        should => ARRAY[
+            paradedb.term('description', 'shoes'),
+            paradedb.term('description', 'running'),
+            paradedb.term('description', 'keyboard')
+        ],
+        minimum_should_match => '-1'
+    ) ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    // percentages round down
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.boolean(
+        should => ARRAY[
+            paradedb.term('description', 'shoes'),
+            paradedb.term('description', 'running'),
+            paradedb.term('description', 'keyboard')
+        ],
+        minimum_should_match => '50%'
+    ) ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,), (3,), (4,), (5,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.parse(
+        'description:shoes description:running description:keyboard',
+        minimum_should_match => '67%'
+    ) ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.parse_with_field(
+        'description',
+        'shoes running',
+        minimum_should_match => '100%'
+    ) ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@
+    '{"boolean": {"should": [{"term": {"field": "description", "value": "shoes"}}, {"term": {"field": "description", "value": "running"}}], "minimum_should_match": 2}}'::jsonb
+    ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    let result = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.boolean(
+        should => paradedb.term('description', 'shoes'),
+        minimum_should_match => 'most'
+    );
+    "#
+    .execute_result(&mut conn);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("invalid minimum_should_match argument"));
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.boolean(
+        should => ARRAY[
+            paradedb.term('description', 'shoes'),
+            paradedb.term('description', 'running'),
+            paradedb.term('description', 'keyboard')
+        ],
+        minimum_should_match => 2
+    ) ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM paradedb.bm25_search
+    WHERE paradedb.bm25_search.id @@@ paradedb.parse_with_field(
+        'description',
+        'shoes running',
+        minimum_should_match => 2
+    ) ORDER BY id;
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+}
+
+#[rstest]
+fn multi_match(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE people (
+        id SERIAL PRIMARY KEY,
+        first_name TEXT,
+        last_name TEXT,
+        bio TEXT
+    );
+
+    INSERT INTO people (first_name, last_name, bio) VALUES
+        ('John', 'Smith', 'a carpenter'),
+        ('Smith', 'Jones', 'john is a friend'),
+        ('Jane', 'Doe', 'works with john smith'),
+        ('Alice', 'Smith', 'a baker');
+
+    CREATE INDEX people_idx ON people
+    USING bm25 (id, first_name, last_name, bio)
+    WITH (
+        key_field = 'id',
+        text_fields = '{"first_name": {}, "last_name": {}, "bio": {}}'
+    );
+    "#
+    .execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM people
+    WHERE id @@@ paradedb.multi_match(ARRAY['first_name', 'last_name'], 'john smith')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,), (4,)]);
+
+    // every term has to match within a single field
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM people
+    WHERE id @@@ paradedb.multi_match(
+        ARRAY['first_name', 'last_name', 'bio'],
+        'john smith',
+        conjunction_mode => true
+    )
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    // each term can be matched by a different field
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM people
+    WHERE id @@@ paradedb.multi_match(
+        ARRAY['first_name', 'last_name'],
+        'john smith',
+        match_type => 'CrossFields',
+        conjunction_mode => true
+    )
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM people
+    WHERE id @@@ paradedb.multi_match(
+        ARRAY['first_name', 'last_name', 'bio'],
+        'smith',
+        match_type => 'MostFields'
+    )
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,), (3,), (4,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM people
+    WHERE id @@@ paradedb.multi_match(
+        ARRAY['first_name', 'bio'],
+        'john smith',
+        match_type => 'Phrase'
+    )
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    // `smith` is rarer as a first name, so it scores highest unless `last_name` is boosted
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM people
+    WHERE id @@@ paradedb.multi_match(ARRAY['first_name', 'last_name'], 'smith')
+    ORDER BY paradedb.score(id) DESC, id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (1,), (4,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM people
+    WHERE id @@@ paradedb.multi_match(ARRAY['first_name', 'last_name^10'], 'smith')
+    ORDER BY paradedb.score(id) DESC, id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (4,), (2,)]);
+
+    // across fields, `smith` is as common in `first_name` as it is in `last_name`
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM people
+    WHERE id @@@ paradedb.multi_match(
+        ARRAY['first_name', 'last_name'],
+        'smith',
+        match_type => 'CrossFields'
+    )
+    ORDER BY paradedb.score(id) DESC, id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,), (4,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM people
+    WHERE id @@@
+    '{"multi_match": {"fields": ["first_name", "last_name"], "value": "john smith", "match_type": "cross_fields", "conjunction_mode": true}}'::jsonb
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    let result = r#"
+    SELECT id FROM people
+    WHERE id @@@ paradedb.multi_match(ARRAY['first_name^high'], 'john')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn multi_match_cross_fields_tokenizers(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE posts (
+        id SERIAL PRIMARY KEY,
+        title TEXT,
+        body TEXT
+    );
+
+    INSERT INTO posts (title, body) VALUES
+        ('new shoes', 'she runs every day'),
+        ('running', 'in the rain'),
+        ('shoes', 'for walking');
+
+    CREATE INDEX posts_idx ON posts
+    USING bm25 (id, title, body)
+    WITH (
+        key_field = 'id',
+        text_fields = '{
+            "title": {},
+            "body": {"tokenizer": {"type": "default", "stemmer": "English"}}
+        }'
+    );
+    "#
+    .execute(&mut conn);
+
+    // each word of the value is matched by any field, however that field analyzes it
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM posts
+    WHERE id @@@ paradedb.multi_match(
+        ARRAY['title', 'body'],
+        'running shoes',
+        match_type => 'CrossFields',
+        conjunction_mode => true
+    )
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+}
+
+#[rstest]
+fn decay(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE articles (
+        id SERIAL PRIMARY KEY,
+        title TEXT,
+        views INTEGER,
+        published_at TIMESTAMPTZ
+    );
+
+    INSERT INTO articles (title, views, published_at) VALUES
+        ('postgres news', 100, '2024-01-01T00:00:00Z'),
+        ('postgres news', 500, '2024-01-10T00:00:00Z'),
+        ('postgres news', 1000, '2024-01-20T00:00:00Z'),
+        ('postgres news', 5000, '2024-01-30T00:00:00Z');
+
+    CREATE INDEX articles_idx ON articles
+    USING bm25 (id, title, views, published_at)
+    WITH (
+        key_field = 'id',
+        text_fields = '{"title": {}}',
+        numeric_fields = '{"views": {"fast": true}}',
+        datetime_fields = '{"published_at": {"fast": true}}'
+    );
+    "#
+    .execute(&mut conn);
+
+    // the closer `views` is to 1000, the higher the score
+    for function in ["Gauss", "Exp", "Linear"] {
+        let rows: Vec<(i32,)> = format!(
+            r#"
+            SELECT id FROM articles
+            WHERE id @@@ paradedb.decay(
+                'views',
+                paradedb.parse('title:postgres'),
+                origin => 1000,
+                scale => 1000,
+                function => '{function}'
+            )
+            ORDER BY paradedb.score(id) DESC, id LIMIT 4
+            "#
+        )
+        .fetch(&mut conn);
+        assert_eq!(rows, vec![(3,), (2,), (1,), (4,)], "{function}");
+    }
+
+    // the most recent articles score highest
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles
+    WHERE id @@@ paradedb.decay(
+        'published_at',
+        paradedb.parse('title:postgres'),
+        origin => '2024-02-01T00:00:00Z'::timestamptz,
+        scale => '7 days'::interval,
+        "offset" => '1 day'::interval
+    )
+    ORDER BY paradedb.score(id) DESC LIMIT 2
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(4,), (3,)]);
+
+    // the decay is capped at 1, so a document inside the offset keeps its score
+    let (decayed, original): (f32, f32) = r#"
+    SELECT
+        (SELECT paradedb.score(id) FROM articles WHERE id @@@ paradedb.decay('views', paradedb.parse('title:postgres'), origin => 1000, scale => 10, "offset" => 10) AND id = 3),
+        (SELECT paradedb.score(id) FROM articles WHERE id @@@ paradedb.parse('title:postgres') AND id = 3)
+    "#
+    .fetch_one(&mut conn);
+    assert_eq!(decayed, original);
+
+    // stored queries spell the function in snake case, but the SQL spelling is accepted too
+    for function in ["gauss", "Gauss"] {
+        let rows: Vec<(i32,)> = format!(
+            r#"
+            SELECT id FROM articles
+            WHERE id @@@ '{{"decay": {{
+                "field": "views",
+                "query": {{"parse": {{"query_string": "title:postgres"}}}},
+                "function": "{function}",
+                "origin": 1000,
+                "scale": 1000
+            }}}}'::paradedb.searchqueryinput
+            ORDER BY paradedb.score(id) DESC, id LIMIT 4
+            "#
+        )
+        .fetch(&mut conn);
+        assert_eq!(rows, vec![(3,), (2,), (1,), (4,)], "{function}");
+    }
+
+    let result = r#"
+    SELECT id FROM articles
+    WHERE id @@@ paradedb.decay('title', paradedb.all(), origin => 0, scale => 1)
+    "#
+    .fetch_result::<(i32,)>(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn collapse(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE variants (
+        id SERIAL PRIMARY KEY,
+        name TEXT,
+        product_id INTEGER,
+        brand TEXT
+    );
+
+    INSERT INTO variants (name, product_id, brand) VALUES
+        ('shoe', 1, 'acme'),
+        ('red shoe', 1, 'acme'),
+        ('big blue shoe', 2, 'acme'),
+        ('big blue suede shoe', 2, 'zeta'),
+        ('big red blue suede shoe', 3, 'zeta');
+
+    CREATE INDEX variants_idx ON variants
+    USING bm25 (id, name, product_id, brand)
+    WITH (
+        key_field = 'id',
+        text_fields = '{"name": {}, "brand": {"fast": true, "tokenizer": {"type": "raw"}}}',
+        numeric_fields = '{"product_id": {"fast": true}}'
+    );
+    "#
+    .execute(&mut conn);
+
+    // rows inserted after the index is built land in their own segment, and are collapsed
+    // together with the ones already there
+    "INSERT INTO variants (name, product_id, brand) VALUES ('shoe', 3, 'zeta')".execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM variants
+    WHERE id @@@ paradedb.collapse('product_id', paradedb.term('name', 'shoe'))
+    ORDER BY paradedb.score(id) DESC, id LIMIT 10
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (6,), (3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM variants
+    WHERE id @@@ paradedb.collapse('product_id', paradedb.term('name', 'shoe'), max_per_group => 2)
+    ORDER BY paradedb.score(id) DESC, id LIMIT 3
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (6,), (2,)]);
+
+    // collapsing doesn't depend on the results being ordered by score
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM variants
+    WHERE id @@@ paradedb.collapse('brand', paradedb.term('name', 'shoe'))
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (6,)]);
+
+    // nor on a limit, when they're ordered by something else
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM variants
+    WHERE id @@@ paradedb.collapse('brand', paradedb.term('name', 'shoe'))
+    ORDER BY id DESC LIMIT 1
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(6,)]);
+
+    // the old version of an updated row is still in the index, but doesn't take its group's slot
+    "UPDATE variants SET name = 'boot' WHERE id = 1".execute(&mut conn);
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM variants
+    WHERE id @@@ paradedb.collapse('product_id', paradedb.term('name', 'shoe'))
+    ORDER BY paradedb.score(id) DESC, id LIMIT 10
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(6,), (2,), (3,)]);
+
+    let result = r#"
+    SELECT id FROM variants
+    WHERE id @@@ paradedb.collapse('name', paradedb.term('name', 'shoe'))
+    "#
+    .fetch_result::<(i32,)>(&mut conn);
+    assert!(result.is_err());
+}
+
 #[rstest]
 fn range_term(mut conn: PgConnection) {
     r#"
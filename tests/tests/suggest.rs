@@ -0,0 +1,301 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgConnection;
+
+#[rstest]
+fn suggest_misspelled_term(mut conn: PgConnection) {
+    SimpleProductsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(String, i64)> = r#"
+    SELECT suggestion, doc_freq
+    FROM paradedb.suggest('paradedb.bm25_search_bm25_index', 'description', 'Shoez')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("shoes".to_string(), 3)]);
+
+    let rows: Vec<(String, i64)> = r#"
+    SELECT suggestion, doc_freq
+    FROM paradedb.suggest('paradedb.bm25_search_bm25_index', 'description', 'keybaord', max_edits => 1)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("keyboard".to_string(), 2)]);
+
+    let rows: Vec<(String, i64)> = r#"
+    SELECT suggestion, doc_freq
+    FROM paradedb.suggest('paradedb.bm25_search_bm25_index', 'description', 'shoez', max_edits => 0)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![]);
+
+    let result = r#"
+    SELECT * FROM paradedb.suggest('paradedb.bm25_search_bm25_index', 'description', 'shoez', max_edits => 3)
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    let result = r#"
+    SELECT * FROM paradedb.suggest('paradedb.bm25_search_bm25_index', 'description', 'running shoez')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn suggest_ranking(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE suggestions (
+        id SERIAL PRIMARY KEY,
+        word TEXT
+    );
+
+    INSERT INTO suggestions (word) VALUES ('cart'), ('card'), ('card'), ('care');
+
+    CREATE INDEX suggestions_idx ON suggestions
+    USING bm25 (id, word)
+    WITH (key_field = 'id', text_fields = '{"word": {}}');
+    "#
+    .execute(&mut conn);
+
+    // rows inserted after the index is built land in their own segment
+    "INSERT INTO suggestions (word) VALUES ('care'), ('care'), ('cards')".execute(&mut conn);
+
+    // closer terms come first, and then the more common ones, counted across every segment
+    let rows: Vec<(String, i64)> = r#"
+    SELECT suggestion, doc_freq
+    FROM paradedb.suggest('suggestions_idx', 'word', 'carx', max_edits => 2, "limit" => 4)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("care".to_string(), 3),
+            ("card".to_string(), 2),
+            ("cart".to_string(), 1),
+            ("cards".to_string(), 1),
+        ]
+    );
+
+    // the input term itself isn't a suggestion
+    let rows: Vec<(String,)> = r#"
+    SELECT suggestion
+    FROM paradedb.suggest('suggestions_idx', 'word', 'care', "limit" => 1)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("card".to_string(),)]);
+
+    // deleted rows don't count
+    "DELETE FROM suggestions WHERE word = 'card'".execute(&mut conn);
+    "VACUUM suggestions".execute(&mut conn);
+    let rows: Vec<(String, i64)> = r#"
+    SELECT suggestion, doc_freq
+    FROM paradedb.suggest('suggestions_idx', 'word', 'carx', max_edits => 1)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("care".to_string(), 3), ("cart".to_string(), 1)]);
+}
+
+#[rstest]
+fn complete_prefix(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE completions (
+        id SERIAL PRIMARY KEY,
+        title TEXT,
+        category TEXT,
+        popularity INTEGER
+    );
+
+    INSERT INTO completions (title, category, popularity) VALUES
+        ('Running Shoes', 'footwear', 10),
+        ('Running Shorts', 'apparel', 30),
+        ('Rugby Ball', 'sports', 20),
+        ('Reading Lamp', 'home', 50);
+
+    CREATE INDEX completions_idx ON completions
+    USING bm25 (id, title, category, popularity)
+    WITH (
+        key_field = 'id',
+        text_fields = '{"title": {}, "category": {}}',
+        numeric_fields = '{"popularity": {"fast": true}}',
+        completion_fields = '{"title_suggest": {"column": "title", "weight": "popularity"}}'
+    );
+    "#
+    .execute(&mut conn);
+
+    // prefixes match case-insensitively, and heavier inputs come first
+    let rows: Vec<(String, f32)> = r#"
+    SELECT suggestion, score FROM paradedb.complete('completions_idx', 'title_suggest', 'runn')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("Running Shorts".to_string(), 30.0),
+            ("Running Shoes".to_string(), 10.0)
+        ]
+    );
+
+    let rows: Vec<(String,)> = r#"
+    SELECT suggestion FROM paradedb.complete('completions_idx', 'title_suggest', 'r', "limit" => 2)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("Reading Lamp".to_string(),),
+            ("Running Shorts".to_string(),)
+        ]
+    );
+
+    // exact prefixes outrank fuzzy ones, whatever their weight
+    let rows: Vec<(String,)> = r#"
+    SELECT suggestion FROM paradedb.complete('completions_idx', 'title_suggest', 'rug', fuzziness => 1)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("Rugby Ball".to_string(),),
+            ("Running Shorts".to_string(),),
+            ("Running Shoes".to_string(),)
+        ]
+    );
+
+    let rows: Vec<(String,)> = r#"
+    SELECT suggestion FROM paradedb.complete(
+        'completions_idx', 'title_suggest', 'run',
+        context_field => 'category', context => 'footwear'
+    )
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("Running Shoes".to_string(),)]);
+
+    let result = r#"
+    SELECT * FROM paradedb.complete('completions_idx', 'title', 'run')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn complete_without_weight(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE completions (
+        id SERIAL PRIMARY KEY,
+        query TEXT
+    );
+
+    INSERT INTO completions (query) VALUES ('laptop'), ('lamp'), ('lamp');
+
+    CREATE INDEX completions_idx ON completions
+    USING bm25 (id, query)
+    WITH (key_field = 'id', completion_fields = '{"query": {}}');
+    "#
+    .execute(&mut conn);
+
+    // rows inserted after the index is built land in their own segment
+    "INSERT INTO completions (query) VALUES ('laptop'), ('laptop'), ('lantern')".execute(&mut conn);
+
+    // without a weight column, inputs are ranked by how many rows have them
+    let rows: Vec<(String, f32)> = r#"
+    SELECT suggestion, score FROM paradedb.complete('completions_idx', 'query', 'la')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("laptop".to_string(), 3.0),
+            ("lamp".to_string(), 2.0),
+            ("lantern".to_string(), 1.0)
+        ]
+    );
+
+    "DELETE FROM completions WHERE query = 'laptop'; VACUUM completions".execute(&mut conn);
+
+    let rows: Vec<(String,)> = r#"
+    SELECT suggestion FROM paradedb.complete('completions_idx', 'query', 'la')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("lamp".to_string(),), ("lantern".to_string(),)]);
+}
+
+#[rstest]
+fn complete_fuzzy_prefix_stays_in_the_input(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE completions (
+        id SERIAL PRIMARY KEY,
+        query TEXT
+    );
+
+    INSERT INTO completions (query) VALUES ('ab'), ('abac');
+
+    CREATE INDEX completions_idx ON completions
+    USING bm25 (id, query)
+    WITH (key_field = 'id', completion_fields = '{"query": {}}');
+    "#
+    .execute(&mut conn);
+
+    // 'ab' is indexed along with its original spelling, 'ab', which is no reason to think it
+    // starts with something one edit away from 'abab'
+    let rows: Vec<(String,)> = r#"
+    SELECT suggestion FROM paradedb.complete('completions_idx', 'query', 'abab', fuzziness => 1)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("abac".to_string(),)]);
+}
+
+#[rstest]
+fn complete_weight_must_be_numeric_fast_field(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE completions (
+        id SERIAL PRIMARY KEY,
+        title TEXT,
+        category TEXT,
+        popularity INTEGER
+    );
+    "#
+    .execute(&mut conn);
+
+    let result = r#"
+    CREATE INDEX completions_idx ON completions
+    USING bm25 (id, title, category)
+    WITH (
+        key_field = 'id',
+        text_fields = '{"category": {"fast": true}}',
+        completion_fields = '{"title_suggest": {"column": "title", "weight": "category"}}'
+    )
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    let result = r#"
+    CREATE INDEX completions_idx ON completions
+    USING bm25 (id, title, popularity)
+    WITH (
+        key_field = 'id',
+        numeric_fields = '{"popularity": {"fast": false}}',
+        completion_fields = '{"title_suggest": {"column": "title", "weight": "popularity"}}'
+    )
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
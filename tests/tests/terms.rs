@@ -0,0 +1,134 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgConnection;
+
+#[rstest]
+fn terms_across_segments(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE tags (
+        id SERIAL PRIMARY KEY,
+        body TEXT
+    );
+
+    INSERT INTO tags (body) VALUES ('red red apple'), ('green apple');
+
+    CREATE INDEX tags_idx ON tags
+    USING bm25 (id, body)
+    WITH (key_field = 'id', text_fields = '{"body": {}}');
+    "#
+    .execute(&mut conn);
+
+    // rows inserted after the index is built land in their own segment
+    "INSERT INTO tags (body) VALUES ('red pear'), ('ripe apple')".execute(&mut conn);
+
+    let rows: Vec<(String, i64, i64)> = r#"
+    SELECT term, doc_freq, total_term_freq FROM paradedb.terms('tags_idx', 'body')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("apple".to_string(), 3, 3),
+            ("green".to_string(), 1, 1),
+            ("pear".to_string(), 1, 1),
+            ("red".to_string(), 2, 3),
+            ("ripe".to_string(), 1, 1),
+        ]
+    );
+
+    let rows: Vec<(String, i64, i64)> = r#"
+    SELECT term, doc_freq, total_term_freq FROM paradedb.terms('tags_idx', 'body', prefix => 'r')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![("red".to_string(), 2, 3), ("ripe".to_string(), 1, 1)]
+    );
+
+    let rows: Vec<(String,)> = r#"
+    SELECT term FROM paradedb.terms('tags_idx', 'body', "limit" => 2)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("apple".to_string(),), ("green".to_string(),)]);
+
+    // deleted documents no longer count once they've been vacuumed
+    "DELETE FROM tags WHERE body = 'red red apple'; VACUUM tags".execute(&mut conn);
+
+    let rows: Vec<(String, i64, i64)> = r#"
+    SELECT term, doc_freq, total_term_freq FROM paradedb.terms('tags_idx', 'body', prefix => 'red')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![("red".to_string(), 1, 1)]);
+
+    let result = "SELECT * FROM paradedb.terms('tags_idx', 'id')".execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn term_vectors(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE tags (
+        id SERIAL PRIMARY KEY,
+        body TEXT
+    );
+
+    INSERT INTO tags (body) VALUES ('red red apple'), ('green apple');
+
+    CREATE INDEX tags_idx ON tags
+    USING bm25 (id, body)
+    WITH (key_field = 'id', text_fields = '{"body": {"record": "position"}}');
+    "#
+    .execute(&mut conn);
+
+    let rows: Vec<(String, i32, Vec<i32>)> = r#"
+    SELECT term, term_freq, positions FROM paradedb.term_vectors('tags_idx', 1, 'body')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("apple".to_string(), 1, vec![2]),
+            ("red".to_string(), 2, vec![0, 1]),
+        ]
+    );
+
+    let rows: Vec<(String, i32, Vec<i32>)> = r#"
+    SELECT term, term_freq, positions FROM paradedb.term_vectors('tags_idx', 3, 'body')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![]);
+
+    // the old version of an updated row is still in the index, but isn't visible
+    "UPDATE tags SET body = 'yellow banana' WHERE id = 2".execute(&mut conn);
+    let rows: Vec<(String, i32, Vec<i32>)> = r#"
+    SELECT term, term_freq, positions FROM paradedb.term_vectors('tags_idx', 2, 'body')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("banana".to_string(), 1, vec![1]),
+            ("yellow".to_string(), 1, vec![0]),
+        ]
+    );
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgConnection;
+
+#[rstest]
+fn tsvector_field(mut conn: PgConnection) {
+    ArticlesTable::setup().execute(&mut conn);
+
+    // the stemmed lexemes of the tsvector are indexed as they are
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles WHERE id @@@ paradedb.term('body_tsv', 'cat') ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (3,), (5,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles WHERE id @@@ paradedb.phrase('body_tsv', ARRAY['fat', 'cat']) ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    "INSERT INTO articles (body) VALUES ('Fat cats everywhere')".execute(&mut conn);
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles WHERE id @@@ paradedb.phrase('body_tsv', ARRAY['fat', 'cat']) ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (6,)]);
+}
+
+#[rstest]
+fn tsvector_without_positions(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE stripped (id SERIAL PRIMARY KEY, body_tsv TSVECTOR);
+    INSERT INTO stripped (body_tsv) VALUES
+        (to_tsvector('english', 'a fat cat')),
+        (strip(to_tsvector('english', 'a fat cat')));
+    CREATE INDEX stripped_idx ON stripped
+    USING bm25 (id, body_tsv) WITH (key_field = 'id', text_fields = '{"body_tsv": {}}');
+    "#
+    .execute(&mut conn);
+
+    // the lexemes of a stripped tsvector have no positions, so they're never a phrase
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM stripped WHERE id @@@ paradedb.term('body_tsv', 'cat') ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM stripped WHERE id @@@ paradedb.phrase('body_tsv', ARRAY['fat', 'cat']) ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+}
+
+#[rstest]
+fn tsquery_builder(mut conn: PgConnection) {
+    ArticlesTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles
+    WHERE id @@@ paradedb.tsquery('body_tsv', to_tsquery('english', 'fat & (cats | rats)'))
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,), (3,), (5,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles
+    WHERE id @@@ paradedb.tsquery('body_tsv', to_tsquery('english', 'cat & !dog'))
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (5,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles
+    WHERE id @@@ paradedb.tsquery('body_tsv', to_tsquery('english', 'fat <-> cat'))
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,)]);
+
+    // only adjacent lexemes can be matched as a phrase
+    let result = r#"
+    SELECT id FROM articles
+    WHERE id @@@ paradedb.tsquery('body_tsv', to_tsquery('english', 'cat <2> fat'))
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles
+    WHERE id @@@ paradedb.tsquery('body_tsv', to_tsquery('english', 'super:*'))
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(4,)]);
+
+    // the tsquery matches the same rows as Postgres does
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles
+    WHERE body_tsv @@ to_tsquery('english', 'fat & (cats | rats) & !dog')
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    let expected: Vec<(i32,)> = r#"
+    SELECT id FROM articles
+    WHERE id @@@ paradedb.tsquery('body_tsv', to_tsquery('english', 'fat & (cats | rats) & !dog'))
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, expected);
+}
+
+#[rstest]
+fn tsquery_operator(mut conn: PgConnection) {
+    ArticlesTable::setup().execute(&mut conn);
+
+    // the lexemes of the tsquery are matched against the text column's own tokens
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles WHERE body @@@ to_tsquery('simple', 'fat & cat') ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,), (5,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles WHERE body @@@ 'rats | dog'::tsquery ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM articles WHERE body @@@ 'fat <-> rats'::tsquery ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+
+    let result = r#"
+    SELECT id FROM articles WHERE body @@@ '(fat | cat) <-> rats'::tsquery
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
@@ -0,0 +1,133 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use serde_json::Value;
+use sqlx::PgConnection;
+
+#[rstest]
+fn uuid_term(mut conn: PgConnection) {
+    OrdersTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM orders
+    WHERE id @@@ paradedb.term('reference', '9c858901-8a57-4791-81fe-4c455b099bc9'::uuid)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM orders
+    WHERE id @@@ paradedb.term('related', 'f47ac10b-58cc-4372-a567-0e02b2c3d479'::uuid)
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM orders
+    WHERE id @@@ paradedb.term_set(ARRAY[
+        paradedb.term('reference', 'f47ac10b-58cc-4372-a567-0e02b2c3d479'::uuid),
+        paradedb.term('reference', 'a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11'::uuid),
+        paradedb.term('related', '00000000-0000-0000-0000-000000000001'::uuid)
+    ])
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (5,)]);
+}
+
+#[rstest]
+fn uuid_top_n(mut conn: PgConnection) {
+    OrdersTable::setup().execute(&mut conn);
+
+    // uuids sort by value, with the missing one last, just as postgres sorts them
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM orders WHERE customer @@@ 'alice OR bob OR carol OR dave OR erin'
+    ORDER BY reference
+    LIMIT 5
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (3,), (5,), (1,), (4,)]);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM orders WHERE customer @@@ 'alice OR bob OR carol OR erin'
+    ORDER BY reference DESC
+    LIMIT 2
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (5,)]);
+
+    let (plan,) = r#"
+    EXPLAIN (ANALYZE, FORMAT JSON)
+    SELECT * FROM orders WHERE customer @@@ 'alice OR bob'
+    ORDER BY reference
+    LIMIT 5
+    "#
+    .fetch_one::<(Value,)>(&mut conn);
+    let plan = plan
+        .pointer("/0/Plan/Plans/0/Plans/0")
+        .unwrap()
+        .as_object()
+        .unwrap();
+    eprintln!("{plan:#?}");
+    assert_eq!(
+        plan.get("   Sort Field"),
+        Some(&Value::String(String::from("reference")))
+    );
+}
+
+#[rstest]
+fn uuid_fast_field(mut conn: PgConnection) {
+    OrdersTable::setup().execute(&mut conn);
+
+    let rows: Vec<(String,)> = r#"
+    SELECT reference::text FROM (
+        SELECT id, reference FROM orders WHERE customer @@@ 'alice OR erin' OFFSET 0
+    ) orders
+    ORDER BY id
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows,
+        vec![
+            ("f47ac10b-58cc-4372-a567-0e02b2c3d479".into(),),
+            ("a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11".into(),)
+        ]
+    );
+}
+
+#[rstest]
+fn uuid_field_requires_uuid_column(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE tickets (id SERIAL PRIMARY KEY, code TEXT);
+    "#
+    .execute(&mut conn);
+
+    let result = r#"
+    CREATE INDEX tickets_idx ON tickets
+    USING bm25 (id, code)
+    WITH (key_field = 'id', uuid_fields = '{"code": {}}')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
@@ -0,0 +1,201 @@
+// Copyright (c) 2023-2025 Retake, Inc.
+//
+// This file is part of ParadeDB - Postgres for Search and Analytics
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program. If not, see <http://www.gnu.org/licenses/>.
+
+mod fixtures;
+
+use fixtures::*;
+use pretty_assertions::assert_eq;
+use rstest::*;
+use sqlx::PgConnection;
+
+#[rstest]
+fn knn_query(mut conn: PgConnection) {
+    DocsTable::setup().execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM docs
+    WHERE id @@@ paradedb.knn('embedding', ARRAY[1, 0, 0], 2)
+    ORDER BY paradedb.score(id) DESC
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(1,), (2,)]);
+
+    // only the documents matching the filter are compared
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM docs
+    WHERE id @@@ paradedb.knn('embedding', ARRAY[0, 1, 0], 2, filter => paradedb.term('body', 'car'))
+    ORDER BY paradedb.score(id) DESC
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(3,), (4,)]);
+
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM docs
+    WHERE id @@@ paradedb.knn('embedding', ARRAY[0, 0, 1], 1, metric => 'L2')
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].0, 4);
+    assert!((rows[0].1 - 1.0 / 1.04).abs() < 1e-6);
+
+    // vector similarity adds to the bm25 score of the text match
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM docs
+    WHERE id @@@ paradedb.boolean(should => ARRAY[
+        paradedb.term('body', 'apple'),
+        paradedb.knn('embedding', ARRAY[0.9, 0.1, 0], 1)
+    ])
+    ORDER BY paradedb.score(id) DESC
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (1,)]);
+
+    // rows inserted after the index is built land in their own segment, and the k nearest are
+    // found across all of them, whatever the results are ordered by
+    "INSERT INTO docs (body, embedding) VALUES ('pink apple', ARRAY[0.8, 0.2, 0])"
+        .execute(&mut conn);
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM docs
+    WHERE id @@@ paradedb.knn('embedding', ARRAY[1, 0, 0], 2)
+    ORDER BY id DESC LIMIT 10
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (1,)]);
+}
+
+#[rstest]
+fn vector_dimensions(mut conn: PgConnection) {
+    DocsTable::setup().execute(&mut conn);
+
+    let result = "INSERT INTO docs (body, embedding) VALUES ('short', ARRAY[1, 0])"
+        .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    let result = r#"
+    SELECT id FROM docs WHERE id @@@ paradedb.knn('body', ARRAY[1, 0, 0], 2)
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    let result = r#"
+    SELECT id FROM docs WHERE id @@@ paradedb.knn('embedding', ARRAY[1, 0], 2)
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+}
+
+#[rstest]
+fn knn_skips_dead_rows(mut conn: PgConnection) {
+    DocsTable::setup().execute(&mut conn);
+
+    // the old version of the row is still in the index, but it's no longer visible
+    "UPDATE docs SET embedding = ARRAY[0, 0, 1] WHERE id = 1".execute(&mut conn);
+
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM docs
+    WHERE id @@@ paradedb.knn('embedding', ARRAY[1, 0, 0], 1)
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,)]);
+}
+
+#[rstest]
+fn sparse_vector_query(mut conn: PgConnection) {
+    r#"
+    CREATE TABLE passages (
+        id SERIAL PRIMARY KEY,
+        body TEXT,
+        terms JSONB
+    );
+
+    INSERT INTO passages (body, terms) VALUES
+        ('apple pie', '{"apple": 1.5, "fruit": 0.5}'),
+        ('fast car', '{"car": 2, "red": 0.5}'),
+        ('red apple', '{"apple": 0.2, "red": 1}');
+
+    CREATE INDEX passages_idx ON passages
+    USING bm25 (id, body, terms)
+    WITH (
+        key_field = 'id',
+        text_fields = '{"body": {}}',
+        sparse_vector_fields = '{"terms": {}}'
+    );
+    "#
+    .execute(&mut conn);
+
+    // documents are scored by the dot product of their weights with the query's
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM passages
+    WHERE id @@@ paradedb.sparse_vector('terms', '{"apple": 1, "red": 1}')
+    ORDER BY paradedb.score(id) DESC
+    "#
+    .fetch(&mut conn);
+    assert_eq!(
+        rows.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+        vec![1, 3, 2]
+    );
+    for ((_, score), expected) in rows.iter().zip([1.5, 1.2, 0.5]) {
+        assert!((score - expected).abs() < 1e-6);
+    }
+
+    // sparse vector scores add to the bm25 score of the text match
+    let rows: Vec<(i32,)> = r#"
+    SELECT id FROM passages
+    WHERE id @@@ paradedb.boolean(should => ARRAY[
+        paradedb.term('body', 'car'),
+        paradedb.sparse_vector('terms', '{"red": 1}')
+    ])
+    ORDER BY paradedb.score(id) DESC
+    "#
+    .fetch(&mut conn);
+    assert_eq!(rows, vec![(2,), (3,)]);
+
+    let result = r#"INSERT INTO passages (body, terms) VALUES ('bad', '{"apple": -1}')"#
+        .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    let result = r#"
+    SELECT id FROM passages WHERE id @@@ paradedb.sparse_vector('terms', '[1, 2]')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    // text fields aren't sparse vectors, even though both are indexed as text
+    let result = r#"
+    SELECT id FROM passages WHERE id @@@ paradedb.sparse_vector('body', '{"apple": 1}')
+    "#
+    .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    // weights are indexed in hundredths, up to 100
+    let result = r#"INSERT INTO passages (body, terms) VALUES ('heavy', '{"apple": 100.5}')"#
+        .execute_result(&mut conn);
+    assert!(result.is_err());
+
+    r#"INSERT INTO passages (body, terms) VALUES ('heavy', '{"apple": 99.994}')"#
+        .execute(&mut conn);
+    let rows: Vec<(i32, f32)> = r#"
+    SELECT id, paradedb.score(id) FROM passages
+    WHERE id @@@ paradedb.sparse_vector('terms', '{"apple": 2}')
+    ORDER BY paradedb.score(id) DESC
+    LIMIT 1
+    "#
+    .fetch(&mut conn);
+    // the rejected rows above still used up ids 4 and 5
+    assert_eq!(rows[0].0, 6);
+    assert!((rows[0].1 - 199.98).abs() < 1e-3);
+}